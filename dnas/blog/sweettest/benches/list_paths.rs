@@ -0,0 +1,326 @@
+//! Benchmarks of the list and hydration paths of the blog DNA.
+//!
+//! Seeds a local conductor with thousands of posts and comments, then reports the latency of each
+//! zome call along with the DHT reads that the host served for it, so that a change that adds a
+//! `get` per item shows up even when the timings are noisy.
+//!
+//! Run it with `npm run bench:sweettest`. The seeded data is sized by `BLOG_BENCH_POSTS`,
+//! `BLOG_BENCH_COMMENTS` and `BLOG_BENCH_REVISIONS`, the number of timed runs per zome call by
+//! `BLOG_BENCH_RUNS`, and `BLOG_BENCH_OUTPUT` names a file to save the report to as JSON.
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use blog::comment::CommentListing;
+use blog::feed::{FeedFormat, FeedInput, FeedSource};
+use blog::post::{PostListing, UpdatePostInput};
+use blog_sweettest::*;
+use holochain::prelude::*;
+use holochain::sweettest::await_consistency;
+use serde::Serialize;
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+/// The cascade methods behind the host functions that read from the DHT, each called once per read
+const HOST_READS: [&str; 6] = [
+    "dht_get",
+    "get_details",
+    "dht_get_links",
+    "get_link_details",
+    "dht_count_links",
+    "get_agent_activity",
+];
+
+static HOST_CALLS: Mutex<BTreeMap<&'static str, u64>> = Mutex::new(BTreeMap::new());
+
+/// Counts the spans that the cascade opens for the reads in `HOST_READS`
+struct HostCallCounter;
+
+impl<S: Subscriber> Layer<S> for HostCallCounter {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        *HOST_CALLS
+            .lock()
+            .unwrap()
+            .entry(attrs.metadata().name())
+            .or_default() += 1;
+    }
+}
+
+fn count_host_calls() {
+    let filter = filter_fn(|metadata| {
+        metadata.is_span()
+            && metadata.target().starts_with("holochain_cascade")
+            && HOST_READS.contains(&metadata.name())
+    });
+    tracing_subscriber::registry()
+        .with(HostCallCounter.with_filter(filter))
+        .init();
+}
+
+fn host_calls() -> BTreeMap<&'static str, u64> {
+    HOST_CALLS.lock().unwrap().clone()
+}
+
+fn env_or(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .map(|value| {
+            value
+                .parse()
+                .unwrap_or_else(|_| panic!("{name} should be a number"))
+        })
+        .unwrap_or(default)
+}
+
+struct Scale {
+    posts: usize,
+    comments: usize,
+    revisions: usize,
+    runs: usize,
+}
+
+impl Scale {
+    fn from_env() -> Scale {
+        Scale {
+            posts: env_or("BLOG_BENCH_POSTS", 2000),
+            comments: env_or("BLOG_BENCH_COMMENTS", 2000),
+            revisions: env_or("BLOG_BENCH_REVISIONS", 200),
+            runs: env_or("BLOG_BENCH_RUNS", 20).max(1),
+        }
+    }
+}
+
+/// Creates the posts, then puts every comment and revision on the first one
+async fn seed(agents: &Agents, scale: &Scale) -> ActionHash {
+    let author = agents.agent(0);
+    let mut hot_post_hash = None;
+    for i in 0..scale.posts {
+        let record: Record = agents
+            .call(
+                0,
+                "create_post",
+                sample_post(author.clone(), &format!("Post {i}")),
+            )
+            .await;
+        hot_post_hash.get_or_insert_with(|| record.action_address().clone());
+    }
+    let hot_post_hash = hot_post_hash.expect("BLOG_BENCH_POSTS should be at least 1");
+
+    for i in 0..scale.comments {
+        let comment = sample_comment(
+            author.clone(),
+            hot_post_hash.clone(),
+            &format!("Comment {i}"),
+        );
+        let _: Record = agents.call(0, "create_comment", comment).await;
+    }
+
+    let mut previous_post_hash = hot_post_hash.clone();
+    for i in 0..scale.revisions {
+        let record: Record = agents
+            .call(
+                0,
+                "update_post",
+                UpdatePostInput {
+                    original_post_hash: hot_post_hash.clone(),
+                    previous_post_hash,
+                    updated_post: sample_post(author.clone(), &format!("Post 0, revision {i}")),
+                    edit_reason: None,
+                    minor_edit: false,
+                },
+            )
+            .await;
+        previous_post_hash = record.action_address().clone();
+    }
+
+    // Integration must be over before anything is timed, since it reads through the cascade too
+    await_consistency(Duration::from_secs(600), agents.cells.iter())
+        .await
+        .unwrap();
+    hot_post_hash
+}
+
+#[derive(Serialize)]
+struct Report {
+    name: String,
+    // The number of items in the response, to tell apart a faster call from one that lost data
+    items: usize,
+    runs: usize,
+    min_ms: f64,
+    median_ms: f64,
+    p95_ms: f64,
+    max_ms: f64,
+    host_calls_per_run: BTreeMap<&'static str, f64>,
+}
+
+impl Report {
+    fn total_host_calls(&self) -> f64 {
+        self.host_calls_per_run.values().sum()
+    }
+}
+
+/// Times `runs` calls of the zome function, after one call to warm up the wasm instance
+async fn measure<I, O>(
+    agents: &Agents,
+    name: &str,
+    fn_name: &str,
+    payload: I,
+    runs: usize,
+    items: impl Fn(&O) -> usize,
+) -> Report
+where
+    I: Serialize + Debug + Clone,
+    O: serde::de::DeserializeOwned + Debug,
+{
+    let output: O = agents.call(0, fn_name, payload.clone()).await;
+    let before = host_calls();
+    let mut latencies = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let start = Instant::now();
+        let _: O = agents.call(0, fn_name, payload.clone()).await;
+        latencies.push(start.elapsed());
+    }
+    let after = host_calls();
+    latencies.sort();
+
+    let percentile = |p: f64| {
+        let index = ((latencies.len() - 1) as f64 * p).round() as usize;
+        latencies[index].as_secs_f64() * 1000.0
+    };
+    let host_calls_per_run = after
+        .into_iter()
+        .map(|(read, count)| {
+            let count = count - before.get(read).copied().unwrap_or_default();
+            (read, count as f64 / runs as f64)
+        })
+        .filter(|(_, count)| *count > 0.0)
+        .collect();
+    Report {
+        name: name.to_string(),
+        items: items(&output),
+        runs,
+        min_ms: percentile(0.0),
+        median_ms: percentile(0.5),
+        p95_ms: percentile(0.95),
+        max_ms: percentile(1.0),
+        host_calls_per_run,
+    }
+}
+
+fn print_reports(reports: &[Report]) {
+    println!(
+        "{:<28} {:>6} {:>9} {:>9} {:>9} {:>9} {:>11}  breakdown",
+        "zome call", "items", "min ms", "median ms", "p95 ms", "max ms", "host calls"
+    );
+    for report in reports {
+        let breakdown: Vec<String> = report
+            .host_calls_per_run
+            .iter()
+            .map(|(read, count)| format!("{read}={count}"))
+            .collect();
+        println!(
+            "{:<28} {:>6} {:>9.2} {:>9.2} {:>9.2} {:>9.2} {:>11}  {}",
+            report.name,
+            report.items,
+            report.min_ms,
+            report.median_ms,
+            report.p95_ms,
+            report.max_ms,
+            report.total_host_calls(),
+            breakdown.join(" ")
+        );
+    }
+}
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    count_host_calls();
+    let scale = Scale::from_env();
+    let agents = Agents::setup(1).await;
+
+    let start = Instant::now();
+    let hot_post_hash = seed(&agents, &scale).await;
+    println!(
+        "Seeded {} posts, {} comments and {} revisions in {:.1}s",
+        scale.posts,
+        scale.comments,
+        scale.revisions,
+        start.elapsed().as_secs_f64()
+    );
+
+    let feed = FeedInput {
+        source: FeedSource::AllPosts,
+        format: FeedFormat::Atom,
+        limit: 50,
+        title: "Benchmark".to_string(),
+        base_url: "http://localhost".to_string(),
+    };
+    let reports = vec![
+        measure(
+            &agents,
+            "get_all_posts",
+            "get_all_posts",
+            (),
+            scale.runs,
+            Vec::<Link>::len,
+        )
+        .await,
+        measure(
+            &agents,
+            "get_all_post_listings",
+            "get_all_post_listings",
+            (),
+            scale.runs,
+            Vec::<PostListing>::len,
+        )
+        .await,
+        measure(
+            &agents,
+            "get_comments_for_post",
+            "get_comments_for_post",
+            hot_post_hash.clone(),
+            scale.runs,
+            Vec::<Link>::len,
+        )
+        .await,
+        measure(
+            &agents,
+            "get_comment_listings_for_post",
+            "get_comment_listings_for_post",
+            hot_post_hash.clone(),
+            scale.runs,
+            Vec::<CommentListing>::len,
+        )
+        .await,
+        measure(
+            &agents,
+            "get_all_revisions_for_post",
+            "get_all_revisions_for_post",
+            hot_post_hash,
+            scale.runs,
+            Vec::<Record>::len,
+        )
+        .await,
+        measure(
+            &agents,
+            "render_feed (50 posts)",
+            "render_feed",
+            feed,
+            scale.runs,
+            |atom: &String| atom.matches("<entry>").count(),
+        )
+        .await,
+    ];
+    print_reports(&reports);
+
+    if let Ok(path) = std::env::var("BLOG_BENCH_OUTPUT") {
+        std::fs::write(&path, serde_json::to_string_pretty(&reports).unwrap()).unwrap();
+        println!("Saved the report to {path}");
+    }
+}
@@ -0,0 +1,191 @@
+//! Shared setup for the sweettest suite of the blog DNA.
+//!
+//! The suite loads the DNA bundle from `dnas/blog/workdir/blog.dna`, so run `npm run build:happ`
+//! before `cargo test --manifest-path dnas/blog/sweettest/Cargo.toml`. It also installs the
+//! `blog_test` coordinator, which `npm run build:zomes` builds but the DNA manifest leaves out.
+
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use blog_integrity::{Comment, ContentFormat, DnaProperties, Post};
+use holochain::conductor::api::error::ConductorApiResult;
+use holochain::prelude::*;
+use holochain::sweettest::*;
+
+pub const ZOME_NAME: &str = "blog";
+/// The coordinator that commits raw entries and links, to author ops that `blog` never would
+pub const TEST_ZOME_NAME: &str = "blog_test";
+
+pub fn dna_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../workdir/blog.dna")
+}
+
+pub fn test_zome_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../../target/wasm32-unknown-unknown/release/blog_test.wasm")
+}
+
+async fn load_dna() -> DnaFile {
+    let dna = SweetDnaFile::from_bundle(&dna_path())
+        .await
+        .expect("the DNA bundle should have been packed with `npm run build:happ`");
+    with_test_zome(dna).await
+}
+
+/// Adds the `blog_test` coordinator to the DNA, which leaves its hash unchanged
+async fn with_test_zome(mut dna: DnaFile) -> DnaFile {
+    let wasm = DnaWasm::from(
+        std::fs::read(test_zome_path())
+            .expect("the test zome should have been built with `npm run build:zomes`"),
+    );
+    let zome = ZomeDef::Wasm(WasmZome {
+        wasm_hash: WasmHash::with_data(&wasm).await,
+        dependencies: vec!["blog_integrity".into()],
+        preserialized_path: None,
+    });
+    dna.update_coordinators(vec![(TEST_ZOME_NAME.into(), zome.into())], vec![wasm])
+        .await
+        .unwrap();
+    dna
+}
+
+/// Conductors with one blog cell each, networked through an in-process bootstrap and signal server
+pub struct Agents {
+    pub conductors: SweetConductorBatch,
+    pub cells: Vec<SweetCell>,
+}
+
+impl Agents {
+    pub async fn setup(count: usize) -> Agents {
+        let dna = load_dna().await;
+        let mut conductors = SweetConductorBatch::from_standard_config_rendezvous(count).await;
+        let apps = conductors.setup_app("blog", [&dna]).await.unwrap();
+        conductors.exchange_peer_info().await;
+        Agents {
+            conductors,
+            cells: apps.cells_flattened(),
+        }
+    }
+
+    /// Like `setup`, with DNA properties that can refer to the agents, e.g. to make one a moderator
+    pub async fn setup_with_properties(
+        count: usize,
+        properties: impl FnOnce(&[AgentPubKey]) -> DnaProperties,
+    ) -> Agents {
+        let mut conductors = SweetConductorBatch::from_standard_config_rendezvous(count).await;
+        let mut agents = vec![];
+        for conductor in conductors.iter() {
+            agents.push(SweetAgents::one(conductor.keystore()).await);
+        }
+        let properties = SerializedBytes::try_from(properties(&agents)).unwrap();
+        let dna = load_dna().await.with_properties(properties).await;
+        let apps = conductors
+            .setup_app_for_zipped_agents("blog", &agents, [&dna])
+            .await
+            .unwrap();
+        conductors.exchange_peer_info().await;
+        Agents {
+            conductors,
+            cells: apps.cells_flattened(),
+        }
+    }
+
+    pub fn agent(&self, index: usize) -> AgentPubKey {
+        self.cells[index].agent_pubkey().clone()
+    }
+
+    pub async fn call<I, O>(&self, index: usize, fn_name: &str, payload: I) -> O
+    where
+        I: serde::Serialize + Debug,
+        O: serde::de::DeserializeOwned + Debug,
+    {
+        self.conductors[index]
+            .call(&self.cells[index].zome(ZOME_NAME), fn_name, payload)
+            .await
+    }
+
+    pub async fn call_fallible<I, O>(
+        &self,
+        index: usize,
+        fn_name: &str,
+        payload: I,
+    ) -> ConductorApiResult<O>
+    where
+        I: serde::Serialize + Debug,
+        O: serde::de::DeserializeOwned + Debug,
+    {
+        self.conductors[index]
+            .call_fallible(&self.cells[index].zome(ZOME_NAME), fn_name, payload)
+            .await
+    }
+
+    /// Calls the `blog_test` coordinator, whose commits only go through validation
+    pub async fn call_test_zome_fallible<I, O>(
+        &self,
+        index: usize,
+        fn_name: &str,
+        payload: I,
+    ) -> ConductorApiResult<O>
+    where
+        I: serde::Serialize + Debug,
+        O: serde::de::DeserializeOwned + Debug,
+    {
+        self.conductors[index]
+            .call_fallible(&self.cells[index].zome(TEST_ZOME_NAME), fn_name, payload)
+            .await
+    }
+
+    /// Waits until every agent has integrated every op that any of them authored
+    pub async fn sync(&self) {
+        await_consistency(Duration::from_secs(60), self.cells.iter())
+            .await
+            .unwrap();
+    }
+}
+
+pub fn sample_post(author: AgentPubKey, name: &str) -> Post {
+    Post {
+        name: name.to_string(),
+        content: format!("The content of {name}"),
+        author,
+        co_authors: vec![],
+        delegate: None,
+        envelope: None,
+        format: ContentFormat::Plain,
+        attachments: vec![],
+        reverted_from: None,
+        edit: None,
+    }
+}
+
+pub fn sample_comment(author: AgentPubKey, post_hash: ActionHash, content: &str) -> Comment {
+    Comment {
+        content: content.to_string(),
+        post_hash,
+        author,
+        reverted_from: None,
+        edit: None,
+        // Filled in by `create_comment`
+        comment_lock_hash: None,
+    }
+}
+
+pub fn post_of(record: &Record) -> Post {
+    Post::try_from(record).unwrap()
+}
+
+pub fn comment_of(record: &Record) -> Comment {
+    Comment::try_from(record).unwrap()
+}
+
+/// Asserts that a zome call failed with the given `BlogError` code
+pub fn assert_error_code<T: Debug>(result: ConductorApiResult<T>, code: &str) {
+    match result {
+        Ok(output) => panic!("expected a {code} error, got {output:?}"),
+        Err(error) => {
+            let error = format!("{error:?}");
+            assert!(error.contains(code), "expected a {code} error, got {error}");
+        }
+    }
+}
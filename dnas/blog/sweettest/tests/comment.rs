@@ -0,0 +1,343 @@
+use blog::comment::{CommentListing, RevertCommentInput, UpdateCommentInput};
+use blog::counts::LinkCount;
+use blog::diff::{DiffChunk, DiffGranularity, DiffRevisionsInput, RevisionSummary};
+use blog_integrity::{encode_link_tag, Comment, CommentTag, DnaProperties, EntryTypes, LinkTypes};
+use blog_sweettest::*;
+use blog_test::CreateRawLinkInput;
+use holochain::prelude::*;
+
+async fn create_post(agents: &Agents, index: usize) -> ActionHash {
+    let record: Record = agents
+        .call(
+            index,
+            "create_post",
+            sample_post(agents.agent(index), "Post"),
+        )
+        .await;
+    record.action_address().clone()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn create_and_read_comment() {
+    let agents = Agents::setup(2).await;
+    let post_hash = create_post(&agents, 0).await;
+    agents.sync().await;
+
+    let comment = sample_comment(agents.agent(1), post_hash.clone(), "Nice post");
+    let record: Record = agents.call(1, "create_comment", comment.clone()).await;
+    let comment_hash = record.action_address().clone();
+    // No Comment Lock is recorded for a Post whose comments were never locked
+    assert!(comment_of(&record).comment_lock_hash.is_none());
+    agents.sync().await;
+
+    let original: Option<Record> = agents
+        .call(0, "get_original_comment", comment_hash.clone())
+        .await;
+    assert_eq!(comment_of(&original.unwrap()).content, comment.content);
+    let latest: Option<Record> = agents
+        .call(0, "get_latest_comment", comment_hash.clone())
+        .await;
+    assert_eq!(latest.unwrap().action_address(), &comment_hash);
+
+    let links: Vec<Link> = agents
+        .call(0, "get_comments_for_post", post_hash.clone())
+        .await;
+    assert_eq!(links.len(), 1);
+    let listings: Vec<CommentListing> = agents
+        .call(0, "get_comment_listings_for_post", post_hash.clone())
+        .await;
+    assert_eq!(listings.len(), 1);
+    assert_eq!(listings[0].comment_hash, comment_hash);
+    assert_eq!(listings[0].tag, CommentTag::new(&comment));
+    let count: LinkCount = agents
+        .call(0, "count_comments_for_post", post_hash.clone())
+        .await;
+    assert_eq!(
+        count,
+        LinkCount {
+            live: 1,
+            deleted: 0
+        }
+    );
+    let counts: Vec<LinkCount> = agents
+        .call(
+            0,
+            "count_comments_for_posts",
+            vec![post_hash.clone(), post_hash],
+        )
+        .await;
+    assert_eq!(counts.len(), 2);
+
+    // Comments are only indexed under their post, `create_comment` doesn't link them to their author
+    let links: Vec<Link> = agents
+        .call(0, "get_comments_for_author", agents.agent(1))
+        .await;
+    assert!(links.is_empty());
+    let deleted: Vec<(SignedActionHashed, Vec<SignedActionHashed>)> = agents
+        .call(0, "get_deleted_comments_for_author", agents.agent(1))
+        .await;
+    assert!(deleted.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn update_revert_and_diff_comment() {
+    let agents = Agents::setup(1).await;
+    let post_hash = create_post(&agents, 0).await;
+    let record: Record = agents
+        .call(
+            0,
+            "create_comment",
+            sample_comment(agents.agent(0), post_hash.clone(), "First take"),
+        )
+        .await;
+    let original_hash = record.action_address().clone();
+    let listed: Vec<CommentListing> = agents
+        .call(0, "get_comment_listings_for_post", post_hash.clone())
+        .await;
+
+    let updated: Record = agents
+        .call(
+            0,
+            "update_comment",
+            UpdateCommentInput {
+                previous_comment_hash: original_hash.clone(),
+                updated_comment: Comment {
+                    content: "Second take".to_string(),
+                    ..comment_of(&record)
+                },
+                edit_reason: None,
+                minor_edit: true,
+            },
+        )
+        .await;
+    // The comment is listed with its latest revision but keeps its place among the comments
+    let listings: Vec<CommentListing> = agents
+        .call(0, "get_comment_listings_for_post", post_hash.clone())
+        .await;
+    assert_eq!(listings.len(), 1);
+    assert_eq!(listings[0].tag, CommentTag::new(&comment_of(&updated)));
+    assert_eq!(listings[0].timestamp, listed[0].timestamp);
+    // A revision tag can't describe a revision that the comment was edited from
+    let input = CreateRawLinkInput {
+        base: post_hash.into(),
+        target: original_hash.clone().into(),
+        link_type: LinkTypes::CommentRevisionTags,
+        tag: encode_link_tag(CommentTag::new(&comment_of(&record)))
+            .unwrap()
+            .into_inner(),
+    };
+    assert_error_code(
+        agents
+            .call_test_zome_fallible::<_, ActionHash>(0, "create_raw_link", input)
+            .await,
+        "INVALID",
+    );
+
+    let revisions: Vec<Record> = agents
+        .call(0, "get_all_revisions_for_comment", original_hash.clone())
+        .await;
+    assert_eq!(revisions.len(), 2);
+
+    let diff: Vec<DiffChunk> = agents
+        .call(
+            0,
+            "diff_comment_revisions",
+            DiffRevisionsInput {
+                old_revision_hash: original_hash.clone(),
+                new_revision_hash: updated.action_address().clone(),
+                granularity: DiffGranularity::Line,
+            },
+        )
+        .await;
+    assert_eq!(diff.len(), 2);
+
+    let reverted: Record = agents
+        .call(
+            0,
+            "revert_comment",
+            RevertCommentInput {
+                original_comment_hash: original_hash.clone(),
+                revision_hash: original_hash.clone(),
+                edit_reason: Some("Changed my mind".to_string()),
+            },
+        )
+        .await;
+    assert_eq!(comment_of(&reverted).content, "First take");
+
+    let history: Vec<RevisionSummary> = agents
+        .call(0, "get_edit_history_for_comment", original_hash)
+        .await;
+    assert_eq!(history.len(), 3);
+    assert!(history[1].edit.as_ref().is_some_and(|edit| edit.minor));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn delete_comment() {
+    let agents = Agents::setup(1).await;
+    let post_hash = create_post(&agents, 0).await;
+    let record: Record = agents
+        .call(
+            0,
+            "create_comment",
+            sample_comment(agents.agent(0), post_hash.clone(), "Oops"),
+        )
+        .await;
+    let comment_hash = record.action_address().clone();
+
+    let delete_hash: ActionHash = agents.call(0, "delete_comment", comment_hash.clone()).await;
+    let deletes: Option<Vec<SignedActionHashed>> = agents
+        .call(0, "get_all_deletes_for_comment", comment_hash.clone())
+        .await;
+    assert_eq!(deletes.unwrap().len(), 1);
+    let oldest: Option<SignedActionHashed> = agents
+        .call(0, "get_oldest_delete_for_comment", comment_hash)
+        .await;
+    assert_eq!(oldest.unwrap().action_address(), &delete_hash);
+
+    let deleted: Vec<(SignedActionHashed, Vec<SignedActionHashed>)> = agents
+        .call(0, "get_deleted_comments_for_post", post_hash.clone())
+        .await;
+    assert_eq!(deleted.len(), 1);
+    let count: LinkCount = agents.call(0, "count_comments_for_post", post_hash).await;
+    assert_eq!(
+        count,
+        LinkCount {
+            live: 0,
+            deleted: 1
+        }
+    );
+
+    assert_error_code(
+        agents
+            .call_fallible::<_, ActionHash>(
+                0,
+                "delete_comment",
+                ActionHash::from_raw_36(vec![0; 36]),
+            )
+            .await,
+        "COMMENT_NOT_FOUND",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_stale_comment_lock_does_not_bypass_the_comment_lock() {
+    let agents = Agents::setup(2).await;
+    let post_hash = create_post(&agents, 0).await;
+    agents.sync().await;
+    let _: Record = agents
+        .call(
+            1,
+            "create_comment",
+            sample_comment(agents.agent(1), post_hash.clone(), "Before the lock"),
+        )
+        .await;
+    let _lock: Record = agents.call(0, "lock_comments", post_hash.clone()).await;
+    agents.sync().await;
+
+    // A comment that records no Comment Lock, as if the lock hadn't reached its author
+    let stale_comment = sample_comment(agents.agent(1), post_hash.clone(), "After the lock");
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(1, "create_comment", stale_comment.clone())
+            .await,
+        "COMMENTS_LOCKED",
+    );
+
+    // Committed directly, the comment validates against the stale lock but stays out of the lists
+    let comment_hash: ActionHash = agents
+        .call_test_zome_fallible(
+            1,
+            "create_raw_entry",
+            EntryTypes::Comment(stale_comment.clone()),
+        )
+        .await
+        .unwrap();
+    let _: ActionHash = agents
+        .call_test_zome_fallible(
+            1,
+            "create_raw_link",
+            CreateRawLinkInput {
+                base: post_hash.clone().into(),
+                target: comment_hash.into(),
+                link_type: LinkTypes::PostToComments,
+                tag: encode_link_tag(CommentTag::new(&stale_comment))
+                    .unwrap()
+                    .into_inner(),
+            },
+        )
+        .await
+        .unwrap();
+    agents.sync().await;
+    let links: Vec<Link> = agents
+        .call(0, "get_comments_for_post", post_hash.clone())
+        .await;
+    assert_eq!(links.len(), 1);
+    let listings: Vec<CommentListing> = agents
+        .call(0, "get_comment_listings_for_post", post_hash.clone())
+        .await;
+    assert_eq!(listings.len(), 1);
+    assert_eq!(listings[0].tag.excerpt, "Before the lock");
+    let count: LinkCount = agents
+        .call(0, "count_comments_for_post", post_hash.clone())
+        .await;
+    assert_eq!(count.live, 1);
+
+    // Comments linked once the post is unlocked record the unlock and are listed again
+    let unlock: Record = agents.call(0, "unlock_comments", post_hash.clone()).await;
+    agents.sync().await;
+    let record: Record = agents
+        .call(
+            1,
+            "create_comment",
+            sample_comment(agents.agent(1), post_hash.clone(), "After the unlock"),
+        )
+        .await;
+    assert_eq!(
+        comment_of(&record).comment_lock_hash.as_ref(),
+        Some(unlock.action_address())
+    );
+    agents.sync().await;
+    let links: Vec<Link> = agents
+        .call(0, "get_comments_for_post", post_hash.clone())
+        .await;
+    assert_eq!(links.len(), 2);
+    let count: LinkCount = agents.call(0, "count_comments_for_post", post_hash).await;
+    assert_eq!(count.live, 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_moderator_can_lock_the_comments_of_a_post() {
+    let agents = Agents::setup_with_properties(3, |agents| DnaProperties {
+        progenitor: Some(agents[0].clone().into()),
+        ..Default::default()
+    })
+    .await;
+    let post_hash = create_post(&agents, 1).await;
+    agents.sync().await;
+
+    let _lock: Record = agents.call(0, "lock_comments", post_hash.clone()).await;
+    agents.sync().await;
+    let locked: bool = agents.call(2, "is_post_locked", post_hash.clone()).await;
+    assert!(locked);
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(
+                2,
+                "create_comment",
+                sample_comment(agents.agent(2), post_hash.clone(), "Locked"),
+            )
+            .await,
+        "COMMENTS_LOCKED",
+    );
+
+    // The author of the Post can re-open a thread that a moderator locked
+    let _unlock: Record = agents.call(1, "unlock_comments", post_hash.clone()).await;
+    agents.sync().await;
+    let _: Record = agents
+        .call(
+            2,
+            "create_comment",
+            sample_comment(agents.agent(2), post_hash, "Open again"),
+        )
+        .await;
+}
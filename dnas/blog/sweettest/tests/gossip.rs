@@ -0,0 +1,78 @@
+use blog::counts::LinkCount;
+use blog::post::UpdatePostInput;
+use blog_integrity::Post;
+use blog_sweettest::*;
+use holochain::prelude::*;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn every_agent_converges_on_the_same_blog() {
+    let agents = Agents::setup(3).await;
+
+    let mut post_hashes = vec![];
+    for index in 0..3 {
+        let record: Record = agents
+            .call(
+                index,
+                "create_post",
+                sample_post(agents.agent(index), &format!("Post {index}")),
+            )
+            .await;
+        post_hashes.push(record.action_address().clone());
+    }
+    agents.sync().await;
+
+    // Every agent comments on every post
+    for index in 0..3 {
+        for post_hash in &post_hashes {
+            let _record: Record = agents
+                .call(
+                    index,
+                    "create_comment",
+                    sample_comment(agents.agent(index), post_hash.clone(), "Agreed"),
+                )
+                .await;
+        }
+    }
+    // The first post is edited and the last one deleted by their authors
+    let first: Option<Record> = agents
+        .call(0, "get_latest_post", post_hashes[0].clone())
+        .await;
+    let _updated: Record = agents
+        .call(
+            0,
+            "update_post",
+            UpdatePostInput {
+                original_post_hash: post_hashes[0].clone(),
+                previous_post_hash: post_hashes[0].clone(),
+                updated_post: Post {
+                    content: "Edited".to_string(),
+                    ..post_of(&first.unwrap())
+                },
+                edit_reason: None,
+                minor_edit: false,
+            },
+        )
+        .await;
+    let _delete: ActionHash = agents.call(2, "delete_post", post_hashes[2].clone()).await;
+    agents.sync().await;
+
+    for index in 0..3 {
+        let all_posts: Vec<Link> = agents.call(index, "get_all_posts", ()).await;
+        assert_eq!(all_posts.len(), 2, "agent {index} sees the live posts");
+
+        let latest: Option<Record> = agents
+            .call(index, "get_latest_post", post_hashes[0].clone())
+            .await;
+        assert_eq!(post_of(&latest.unwrap()).content, "Edited");
+
+        let counts: Vec<LinkCount> = agents
+            .call(index, "count_comments_for_posts", post_hashes.clone())
+            .await;
+        assert!(counts.iter().all(|count| count.live == 3));
+
+        let deletes: Option<Vec<SignedActionHashed>> = agents
+            .call(index, "get_all_deletes_for_post", post_hashes[2].clone())
+            .await;
+        assert_eq!(deletes.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,287 @@
+use std::time::Duration;
+
+use blog::diff::{DiffGranularity, DiffRevisionsInput, RevisionSummary};
+use blog::file_storage::DownloadedFile;
+use blog::pinned_posts::PinPostInput;
+use blog::post::{PostDiff, PostListing, RecentlyDeletedPost, RevertPostInput, UpdatePostInput};
+use blog_integrity::{DnaProperties, Post, PostTag};
+use blog_sweettest::*;
+use holochain::prelude::*;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn create_and_read_post() {
+    let agents = Agents::setup(2).await;
+    let post = sample_post(agents.agent(0), "First");
+    let record: Record = agents.call(0, "create_post", post.clone()).await;
+    let post_hash = record.action_address().clone();
+    agents.sync().await;
+
+    let latest: Option<Record> = agents.call(1, "get_latest_post", post_hash.clone()).await;
+    assert_eq!(post_of(&latest.unwrap()), post);
+    let original: Option<Record> = agents.call(1, "get_original_post", post_hash.clone()).await;
+    assert_eq!(original.unwrap().action_address(), &post_hash);
+
+    let links: Vec<Link> = agents
+        .call(1, "get_posts_for_author", agents.agent(0))
+        .await;
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].target, post_hash.clone().into());
+
+    // The listing is read from the link tag, so it shows the post without getting it
+    let listings: Vec<PostListing> = agents.call(1, "get_all_post_listings", ()).await;
+    assert_eq!(listings.len(), 1);
+    assert_eq!(listings[0].post_hash, post_hash);
+    assert_eq!(listings[0].tag, PostTag::new(&post));
+    let listings: Vec<PostListing> = agents
+        .call(1, "get_post_listings_for_author", agents.agent(0))
+        .await;
+    assert_eq!(listings.len(), 1);
+    assert_eq!(listings[0].post_hash, post_hash);
+    assert_eq!(listings[0].tag, PostTag::new(&post));
+    let listings: Vec<PostListing> = agents
+        .call(1, "get_post_listings_for_author", agents.agent(1))
+        .await;
+    assert!(listings.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn update_revert_and_diff_post() {
+    let agents = Agents::setup(1).await;
+    let post = sample_post(agents.agent(0), "Draft");
+    let record: Record = agents.call(0, "create_post", post.clone()).await;
+    let original_hash = record.action_address().clone();
+    let listed: Vec<PostListing> = agents.call(0, "get_all_post_listings", ()).await;
+
+    let updated: Record = agents
+        .call(
+            0,
+            "update_post",
+            UpdatePostInput {
+                original_post_hash: original_hash.clone(),
+                previous_post_hash: original_hash.clone(),
+                updated_post: Post {
+                    name: "Published".to_string(),
+                    content: "The final content".to_string(),
+                    ..post.clone()
+                },
+                edit_reason: Some("Ready".to_string()),
+                minor_edit: false,
+            },
+        )
+        .await;
+    let latest: Option<Record> = agents
+        .call(0, "get_latest_post", original_hash.clone())
+        .await;
+    assert_eq!(latest.unwrap().action_address(), updated.action_address());
+
+    // The post is listed with its latest revision but keeps its place in the listings, and editing it
+    // doesn't make it look deleted
+    let listings: Vec<PostListing> = agents.call(0, "get_all_post_listings", ()).await;
+    assert_eq!(listings.len(), 1);
+    assert_eq!(listings[0].tag, PostTag::new(&post_of(&updated)));
+    assert_eq!(listings[0].timestamp, listed[0].timestamp);
+    let author_listings: Vec<PostListing> = agents
+        .call(0, "get_post_listings_for_author", agents.agent(0))
+        .await;
+    assert_eq!(author_listings[0].tag, PostTag::new(&post_of(&updated)));
+    let deleted: Vec<(SignedActionHashed, Vec<SignedActionHashed>)> = agents
+        .call(0, "get_deleted_posts_for_author", agents.agent(0))
+        .await;
+    assert!(deleted.is_empty());
+
+    let revisions: Vec<Record> = agents
+        .call(0, "get_all_revisions_for_post", original_hash.clone())
+        .await;
+    assert_eq!(revisions.len(), 2);
+
+    let diff: PostDiff = agents
+        .call(
+            0,
+            "diff_post_revisions",
+            DiffRevisionsInput {
+                old_revision_hash: original_hash.clone(),
+                new_revision_hash: updated.action_address().clone(),
+                granularity: DiffGranularity::Word,
+            },
+        )
+        .await;
+    assert!(!diff.name.is_empty());
+    assert!(!diff.content.is_empty());
+
+    let reverted: Record = agents
+        .call(
+            0,
+            "revert_post",
+            RevertPostInput {
+                original_post_hash: original_hash.clone(),
+                revision_hash: original_hash.clone(),
+                edit_reason: None,
+            },
+        )
+        .await;
+    let reverted_post = post_of(&reverted);
+    assert_eq!(reverted_post.name, post.name);
+    assert_eq!(reverted_post.reverted_from, Some(original_hash.clone()));
+
+    let history: Vec<RevisionSummary> = agents
+        .call(0, "get_edit_history_for_post", original_hash)
+        .await;
+    assert_eq!(history.len(), 3);
+    assert!(history[1].name_changed);
+    assert_eq!(
+        history[1]
+            .edit
+            .as_ref()
+            .and_then(|edit| edit.reason.clone()),
+        Some("Ready".to_string())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn delete_and_restore_post() {
+    let agents = Agents::setup(1).await;
+    let record: Record = agents
+        .call(0, "create_post", sample_post(agents.agent(0), "Doomed"))
+        .await;
+    let post_hash = record.action_address().clone();
+
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "restore_post", post_hash.clone())
+            .await,
+        "POST_NOT_DELETED",
+    );
+
+    let delete_hash: ActionHash = agents.call(0, "delete_post", post_hash.clone()).await;
+    let deletes: Option<Vec<SignedActionHashed>> = agents
+        .call(0, "get_all_deletes_for_post", post_hash.clone())
+        .await;
+    assert_eq!(deletes.unwrap().len(), 1);
+    let oldest: Option<SignedActionHashed> = agents
+        .call(0, "get_oldest_delete_for_post", post_hash.clone())
+        .await;
+    assert_eq!(oldest.unwrap().action_address(), &delete_hash);
+
+    let deleted: Vec<(SignedActionHashed, Vec<SignedActionHashed>)> = agents
+        .call(0, "get_deleted_posts_for_author", agents.agent(0))
+        .await;
+    assert_eq!(deleted.len(), 1);
+    let recently_deleted: Vec<RecentlyDeletedPost> = agents
+        .call(0, "get_recently_deleted_posts_for_author", agents.agent(0))
+        .await;
+    assert_eq!(recently_deleted[0].original_post_hash, post_hash);
+
+    let restored: Record = agents.call(0, "restore_post", post_hash.clone()).await;
+    let restored_hash: Option<ActionHash> =
+        agents.call(0, "get_restored_post", post_hash.clone()).await;
+    assert_eq!(restored_hash.as_ref(), Some(restored.action_address()));
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "restore_post", post_hash.clone())
+            .await,
+        "POST_ALREADY_RESTORED",
+    );
+    let recently_deleted: Vec<RecentlyDeletedPost> = agents
+        .call(0, "get_recently_deleted_posts_for_author", agents.agent(0))
+        .await;
+    assert!(recently_deleted.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn missing_records_and_forbidden_calls_are_reported_with_a_code() {
+    let agents = Agents::setup(1).await;
+    let missing = ActionHash::from_raw_36(vec![0; 36]);
+    let latest: Option<Record> = agents.call(0, "get_latest_post", missing.clone()).await;
+    assert!(latest.is_none());
+    assert_error_code(
+        agents
+            .call_fallible::<_, ActionHash>(0, "delete_post", missing.clone())
+            .await,
+        "POST_NOT_FOUND",
+    );
+    assert_error_code(
+        agents
+            .call_fallible::<_, ()>(0, "unlist_post", missing.clone())
+            .await,
+        "POST_NOT_FOUND",
+    );
+    assert_error_code(
+        agents
+            .call_fallible::<_, ActionHash>(0, "delete_series", missing.clone())
+            .await,
+        "SERIES_NOT_FOUND",
+    );
+    assert_error_code(
+        agents
+            .call_fallible::<_, ActionHash>(0, "revoke_delegation_grant", missing)
+            .await,
+        "DELEGATION_GRANT_NOT_FOUND",
+    );
+    assert_error_code(
+        agents
+            .call_fallible::<_, DownloadedFile>(
+                0,
+                "download_file",
+                EntryHash::from_raw_36(vec![0; 36]),
+            )
+            .await,
+        "FILE_MANIFEST_NOT_FOUND",
+    );
+    assert_error_code(
+        agents
+            .call_fallible::<_, PreflightResponse>(
+                0,
+                "start_co_authored_post",
+                sample_post(AgentPubKey::from_raw_36(vec![0; 36]), "Not mine"),
+            )
+            .await,
+        "UNAUTHORIZED",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pinned_posts_come_first_until_they_expire_or_are_unlisted() {
+    let agents = Agents::setup_with_properties(1, |agents| DnaProperties {
+        progenitor: Some(agents[0].clone().into()),
+        ..Default::default()
+    })
+    .await;
+    let mut post_hashes = vec![];
+    for name in ["Unlisted", "Pinned", "Expired"] {
+        let record: Record = agents
+            .call(0, "create_post", sample_post(agents.agent(0), name))
+            .await;
+        post_hashes.push(record.action_address().clone());
+    }
+    let pins = [
+        (&post_hashes[0], None),
+        (
+            &post_hashes[1],
+            Some((Timestamp::now() + Duration::from_secs(3600)).unwrap()),
+        ),
+        (&post_hashes[2], Some(Timestamp::from_micros(0))),
+    ];
+    for (post_hash, expires_at) in pins {
+        let _: ActionHash = agents
+            .call(
+                0,
+                "pin_post",
+                PinPostInput {
+                    post_hash: post_hash.clone(),
+                    expires_at,
+                },
+            )
+            .await;
+    }
+    let _: () = agents.call(0, "unlist_post", post_hashes[0].clone()).await;
+
+    // The unlisted Post stays pinned, but only the listed one that hasn't expired is shown first
+    let pinned: Vec<Link> = agents.call(0, "get_pinned_posts", ()).await;
+    assert_eq!(pinned.len(), 2);
+    let links: Vec<Link> = agents.call(0, "get_all_posts_pinned_first", ()).await;
+    let targets: Vec<AnyLinkableHash> = links.into_iter().map(|link| link.target).collect();
+    assert_eq!(
+        targets,
+        vec![post_hashes[1].clone().into(), post_hashes[2].clone().into()]
+    );
+}
@@ -0,0 +1,825 @@
+use blog::comment::UpdateCommentInput;
+use blog::migration::{MigrationExport, MigrationReport};
+use blog::pinned_posts::PinPostInput;
+use blog::post::UpdatePostInput;
+use blog_integrity::{
+    encode_link_tag, Comment, CommentTag, ContentFormat, DnaProperties, EditMetadata, EntryTypes,
+    FileChunk, FileManifest, LinkTypes, Post, PostEnvelope, PostTag, PostVersions, RestorationTag,
+    Series, WrappedKey, MAX_EDIT_REASON_LENGTH,
+};
+use blog_sweettest::*;
+use blog_test::{CreateRawEntryBytesInput, CreateRawLinkInput};
+use holochain::conductor::api::error::ConductorApiResult;
+use holochain::prelude::*;
+
+async fn create_post(agents: &Agents, index: usize, name: &str) -> Record {
+    agents
+        .call(index, "create_post", sample_post(agents.agent(index), name))
+        .await
+}
+
+async fn create_raw_entry(
+    agents: &Agents,
+    index: usize,
+    entry: EntryTypes,
+) -> ConductorApiResult<ActionHash> {
+    agents
+        .call_test_zome_fallible(index, "create_raw_entry", entry)
+        .await
+}
+
+async fn create_raw_link(
+    agents: &Agents,
+    index: usize,
+    base: impl Into<AnyLinkableHash>,
+    target: impl Into<AnyLinkableHash>,
+    link_type: LinkTypes,
+    tag: LinkTag,
+) -> ConductorApiResult<ActionHash> {
+    let input = CreateRawLinkInput {
+        base: base.into(),
+        target: target.into(),
+        link_type,
+        tag: tag.into_inner(),
+    };
+    agents
+        .call_test_zome_fallible(index, "create_raw_link", input)
+        .await
+}
+
+fn update_input(original: &Record, updated_post: Post) -> UpdatePostInput {
+    UpdatePostInput {
+        original_post_hash: original.action_address().clone(),
+        previous_post_hash: original.action_address().clone(),
+        updated_post,
+        edit_reason: None,
+        minor_edit: false,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn post_create_rules() {
+    let agents = Agents::setup(2).await;
+    let me = agents.agent(0);
+    let existing = create_post(&agents, 0, "Existing").await;
+
+    // Only updates can revert a Post
+    let post = Post {
+        reverted_from: Some(existing.action_address().clone()),
+        ..sample_post(me.clone(), "Reverted")
+    };
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "create_post", post)
+            .await,
+        "INVALID",
+    );
+
+    // Edit metadata can only be recorded on updates
+    let post = Post {
+        edit: EditMetadata::new(Some("Why".to_string()), false),
+        ..sample_post(me.clone(), "Edited")
+    };
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "create_post", post)
+            .await,
+        "INVALID",
+    );
+
+    // Markdown content can't carry raw HTML or script URLs
+    for content in [
+        "<script>alert(1)</script>",
+        "[click](javascript:alert(1))",
+        "[click](javascript&#58;alert(1))",
+    ] {
+        let post = Post {
+            content: content.to_string(),
+            format: ContentFormat::Markdown,
+            ..sample_post(me.clone(), "Markdown")
+        };
+        assert_error_code(
+            agents
+                .call_fallible::<_, Record>(0, "create_post", post)
+                .await,
+            "INVALID",
+        );
+    }
+
+    // A co-authored Post must be countersigned by all of its authors
+    let post = Post {
+        co_authors: vec![agents.agent(1)],
+        ..sample_post(me.clone(), "Co-authored")
+    };
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "create_post", post)
+            .await,
+        "INVALID",
+    );
+
+    // Attachments must reference File Manifest entries
+    let post = Post {
+        attachments: vec![existing.action().entry_hash().unwrap().clone()],
+        ..sample_post(me, "Attached")
+    };
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "create_post", post)
+            .await,
+        "INVALID",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn post_update_rules() {
+    let agents = Agents::setup(1).await;
+    let original = create_post(&agents, 0, "Original").await;
+    let other = create_post(&agents, 0, "Other").await;
+    let post = post_of(&original);
+
+    // Edit reasons are bounded
+    let mut input = update_input(&original, post.clone());
+    input.edit_reason = Some("x".repeat(MAX_EDIT_REASON_LENGTH + 1));
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "update_post", input)
+            .await,
+        "INVALID",
+    );
+
+    // A revert can only restore a revision of the same original entry
+    let input = update_input(
+        &original,
+        Post {
+            reverted_from: Some(other.action_address().clone()),
+            ..post_of(&other)
+        },
+    );
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "update_post", input)
+            .await,
+        "INVALID",
+    );
+
+    // A reverted Post must match the revision it restores
+    let input = update_input(
+        &original,
+        Post {
+            name: "Something else".to_string(),
+            reverted_from: Some(original.action_address().clone()),
+            ..post.clone()
+        },
+    );
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "update_post", input)
+            .await,
+        "INVALID",
+    );
+
+    // The co-authors of a Post cannot be changed
+    let input = update_input(
+        &original,
+        Post {
+            co_authors: vec![AgentPubKey::from_raw_36(vec![1; 36])],
+            ..post
+        },
+    );
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "update_post", input)
+            .await,
+        "IMMUTABLE",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_the_author_can_manage_a_post() {
+    let agents = Agents::setup(2).await;
+    let record = create_post(&agents, 0, "Alice's").await;
+    let post_hash = record.action_address().clone();
+    agents.sync().await;
+
+    // Removing the Post from AllPosts is the first thing that delete_post does
+    assert_error_code(
+        agents
+            .call_fallible::<_, ActionHash>(1, "delete_post", post_hash.clone())
+            .await,
+        "UNAUTHORIZED",
+    );
+    assert_error_code(
+        agents
+            .call_fallible::<_, ()>(1, "unlist_post", post_hash.clone())
+            .await,
+        "UNAUTHORIZED",
+    );
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(1, "lock_comments", post_hash)
+            .await,
+        "UNAUTHORIZED",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_the_author_can_list_a_post() {
+    let agents = Agents::setup(2).await;
+    let record = create_post(&agents, 0, "Alice's").await;
+    agents.sync().await;
+    let links: Vec<Link> = agents.call(1, "get_all_posts", ()).await;
+
+    // `create_post` only ever lists the Post of its caller, so the link is committed directly
+    let input = CreateRawLinkInput {
+        base: links[0].base.clone(),
+        target: record.action_address().clone().into(),
+        link_type: LinkTypes::AllPosts,
+        tag: encode_link_tag(PostTag::new(&post_of(&record)))
+            .unwrap()
+            .into_inner(),
+    };
+    assert_error_code(
+        agents
+            .call_test_zome_fallible::<_, ActionHash>(1, "create_raw_link", input)
+            .await,
+        "UNAUTHORIZED",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_post_is_listed_with_its_latest_revision() {
+    let agents = Agents::setup(1).await;
+    let original = create_post(&agents, 0, "Original").await;
+    let links: Vec<Link> = agents.call(0, "get_all_posts", ()).await;
+    let _: Record = agents
+        .call(
+            0,
+            "update_post",
+            update_input(&original, sample_post(agents.agent(0), "Updated")),
+        )
+        .await;
+
+    // Neither a listing nor a revision tag can describe a revision that the post was edited from
+    for link_type in [LinkTypes::AllPosts, LinkTypes::PostRevisionTags] {
+        let input = CreateRawLinkInput {
+            base: links[0].base.clone(),
+            target: original.action_address().clone().into(),
+            link_type,
+            tag: encode_link_tag(PostTag::new(&post_of(&original)))
+                .unwrap()
+                .into_inner(),
+        };
+        assert_error_code(
+            agents
+                .call_test_zome_fallible::<_, ActionHash>(0, "create_raw_link", input)
+                .await,
+            "INVALID",
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn comment_rules() {
+    let agents = Agents::setup(2).await;
+    let post_record = create_post(&agents, 0, "Commented").await;
+    let post_hash = post_record.action_address().clone();
+    let comment_record: Record = agents
+        .call(
+            0,
+            "create_comment",
+            sample_comment(agents.agent(0), post_hash.clone(), "Hello"),
+        )
+        .await;
+    let comment = comment_of(&comment_record);
+
+    // Only updates can revert a Comment
+    let reverted = Comment {
+        reverted_from: Some(comment_record.action_address().clone()),
+        ..sample_comment(agents.agent(0), post_hash.clone(), "Hello")
+    };
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "create_comment", reverted)
+            .await,
+        "INVALID",
+    );
+
+    // A Comment can only be reverted to a revision of itself
+    let input = UpdateCommentInput {
+        previous_comment_hash: comment_record.action_address().clone(),
+        updated_comment: Comment {
+            reverted_from: Some(post_hash.clone()),
+            ..comment.clone()
+        },
+        edit_reason: None,
+        minor_edit: false,
+    };
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "update_comment", input)
+            .await,
+        "INVALID",
+    );
+
+    // A reverted Comment must match the revision it restores
+    let input = UpdateCommentInput {
+        previous_comment_hash: comment_record.action_address().clone(),
+        updated_comment: Comment {
+            content: "Goodbye".to_string(),
+            reverted_from: Some(comment_record.action_address().clone()),
+            ..comment
+        },
+        edit_reason: None,
+        minor_edit: false,
+    };
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "update_comment", input)
+            .await,
+        "INVALID",
+    );
+
+    // Nobody can comment on a Post once its author locked its comments
+    let _lock: Record = agents.call(0, "lock_comments", post_hash.clone()).await;
+    agents.sync().await;
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(
+                1,
+                "create_comment",
+                sample_comment(agents.agent(1), post_hash.clone(), "Too late"),
+            )
+            .await,
+        "COMMENTS_LOCKED",
+    );
+    // Validators reject a comment that records the lock, whichever coordinator commits it
+    let history: Vec<Record> = agents
+        .call(1, "get_comment_lock_history", post_hash.clone())
+        .await;
+    let locked_comment = Comment {
+        comment_lock_hash: Some(history[0].action_address().clone()),
+        ..sample_comment(agents.agent(1), post_hash, "Too late")
+    };
+    assert_error_code(
+        create_raw_entry(&agents, 1, EntryTypes::Comment(locked_comment)).await,
+        "COMMENTS_LOCKED",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_the_author_can_index_a_post() {
+    let agents = Agents::setup(2).await;
+    let record = create_post(&agents, 0, "Alice's").await;
+    let tag = encode_link_tag(PostTag::new(&post_of(&record))).unwrap();
+    agents.sync().await;
+
+    assert_error_code(
+        create_raw_link(
+            &agents,
+            1,
+            agents.agent(0),
+            record.action_address().clone(),
+            LinkTypes::AuthorToPosts,
+            tag.clone(),
+        )
+        .await,
+        "UNAUTHORIZED",
+    );
+    // A Post can only be indexed under its author and its co-authors
+    assert_error_code(
+        create_raw_link(
+            &agents,
+            0,
+            agents.agent(1),
+            record.action_address().clone(),
+            LinkTypes::AuthorToPosts,
+            tag,
+        )
+        .await,
+        "INVALID",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_comment_is_linked_with_its_own_tag() {
+    let agents = Agents::setup(1).await;
+    let post_hash = create_post(&agents, 0, "Commented")
+        .await
+        .action_address()
+        .clone();
+    let mut comments = vec![];
+    for content in ["First", "Second"] {
+        let record: Record = agents
+            .call(
+                0,
+                "create_comment",
+                sample_comment(agents.agent(0), post_hash.clone(), content),
+            )
+            .await;
+        comments.push(record);
+    }
+
+    assert_error_code(
+        create_raw_link(
+            &agents,
+            0,
+            post_hash,
+            comments[0].action_address().clone(),
+            LinkTypes::PostToComments,
+            encode_link_tag(CommentTag::new(&comment_of(&comments[1]))).unwrap(),
+        )
+        .await,
+        "INVALID",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_the_author_can_unlist_a_post() {
+    let agents = Agents::setup(2).await;
+    let record = create_post(&agents, 0, "Alice's").await;
+    agents.sync().await;
+
+    // `unlist_post` fails earlier for anyone else, so the link is committed directly
+    assert_error_code(
+        create_raw_link(
+            &agents,
+            1,
+            agents.agent(1),
+            record.action_address().clone(),
+            LinkTypes::AuthorToUnlistedPosts,
+            ().into(),
+        )
+        .await,
+        "UNAUTHORIZED",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_moderators_can_pin_a_post_with_its_expiry() {
+    let agents = Agents::setup_with_properties(2, |agents| DnaProperties {
+        progenitor: Some(agents[0].clone().into()),
+        ..Default::default()
+    })
+    .await;
+    let post_hash = create_post(&agents, 0, "Pinned")
+        .await
+        .action_address()
+        .clone();
+    let input = PinPostInput {
+        post_hash: post_hash.clone(),
+        expires_at: None,
+    };
+    let _: ActionHash = agents.call(0, "pin_post", input).await;
+    let pinned: Vec<Link> = agents.call(0, "get_pinned_posts", ()).await;
+    agents.sync().await;
+
+    let input = PinPostInput {
+        post_hash: post_hash.clone(),
+        expires_at: None,
+    };
+    assert_error_code(
+        agents
+            .call_fallible::<_, ActionHash>(1, "pin_post", input)
+            .await,
+        "UNAUTHORIZED",
+    );
+    assert_error_code(
+        create_raw_link(
+            &agents,
+            0,
+            pinned[0].base.clone(),
+            post_hash,
+            LinkTypes::PinnedPosts,
+            ().into(),
+        )
+        .await,
+        "INVALID",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn series_rules() {
+    let agents = Agents::setup(2).await;
+    let alices = create_post(&agents, 0, "Alice's")
+        .await
+        .action_address()
+        .clone();
+    let bobs = create_post(&agents, 1, "Bob's")
+        .await
+        .action_address()
+        .clone();
+    agents.sync().await;
+    let series = |author: AgentPubKey, posts: Vec<ActionHash>| Series {
+        title: "Series".to_string(),
+        description: String::new(),
+        posts,
+        author,
+    };
+
+    // A Series can only be created by its author
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(0, "create_series", series(agents.agent(1), vec![]))
+            .await,
+        "UNAUTHORIZED",
+    );
+    // A Post can only appear once in a Series
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(
+                0,
+                "create_series",
+                series(agents.agent(0), vec![alices.clone(), alices.clone()]),
+            )
+            .await,
+        "INVALID",
+    );
+    // Every Post in a Series must be by its author
+    assert_error_code(
+        agents
+            .call_fallible::<_, Record>(
+                0,
+                "create_series",
+                series(agents.agent(0), vec![bobs.clone()]),
+            )
+            .await,
+        "UNAUTHORIZED",
+    );
+
+    let record: Record = agents
+        .call(0, "create_series", series(agents.agent(0), vec![alices]))
+        .await;
+    agents.sync().await;
+    // Only the owner of a Series can link it from themselves or add Posts to it
+    assert_error_code(
+        create_raw_link(
+            &agents,
+            1,
+            agents.agent(0),
+            record.action_address().clone(),
+            LinkTypes::AuthorToSeries,
+            ().into(),
+        )
+        .await,
+        "UNAUTHORIZED",
+    );
+    assert_error_code(
+        create_raw_link(
+            &agents,
+            1,
+            bobs,
+            record.action_address().clone(),
+            LinkTypes::PostToSeries,
+            ().into(),
+        )
+        .await,
+        "UNAUTHORIZED",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn file_rules() {
+    let agents = Agents::setup(1).await;
+
+    // File chunks can't be empty
+    assert_error_code(
+        agents
+            .call_fallible::<_, EntryHash>(0, "create_file_chunk", FileChunk { data: vec![] })
+            .await,
+        "INVALID",
+    );
+
+    let data = vec![1, 2, 3];
+    let chunk_hash: EntryHash = agents
+        .call(0, "create_file_chunk", FileChunk { data: data.clone() })
+        .await;
+    let manifest = |size: usize, sha256: Vec<u8>| FileManifest {
+        name: "file.bin".to_string(),
+        mime_type: "application/octet-stream".to_string(),
+        size,
+        sha256,
+        chunks: vec![chunk_hash.clone()],
+    };
+    // The size and the hash of a File Manifest must match its chunks
+    assert_error_code(
+        agents
+            .call_fallible::<_, EntryHash>(0, "create_file_manifest", manifest(4, vec![0; 32]))
+            .await,
+        "INVALID",
+    );
+    assert_error_code(
+        agents
+            .call_fallible::<_, EntryHash>(
+                0,
+                "create_file_manifest",
+                manifest(data.len(), vec![0; 32]),
+            )
+            .await,
+        "INVALID",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn private_post_envelope_rules() {
+    let agents = Agents::setup(2).await;
+    let alices_key: Record = agents.call(0, "publish_encryption_key", ()).await;
+    let bobs_key: Record = agents.call(1, "publish_encryption_key", ()).await;
+    agents.sync().await;
+    let encrypted = |data: Vec<u8>| XSalsa20Poly1305EncryptedData::new([0; 24].into(), data);
+    let private_post = |content: &str, sender_key_hash: &ActionHash| Post {
+        content: content.to_string(),
+        envelope: Some(PostEnvelope {
+            sender_key_hash: sender_key_hash.clone(),
+            encrypted_content: encrypted(vec![1, 2, 3]),
+            recipients: vec![WrappedKey {
+                recipient: agents.agent(0),
+                recipient_key_hash: alices_key.action_address().clone(),
+                encrypted_key: encrypted(vec![4]),
+            }],
+        }),
+        ..sample_post(agents.agent(0), "Private")
+    };
+
+    // `create_private_post` always encrypts the content, so the entries are committed directly
+    assert_error_code(
+        create_raw_entry(
+            &agents,
+            0,
+            EntryTypes::Post(private_post("Leaked", alices_key.action_address())),
+        )
+        .await,
+        "INVALID",
+    );
+    assert_error_code(
+        create_raw_entry(
+            &agents,
+            0,
+            EntryTypes::Post(private_post("", bobs_key.action_address())),
+        )
+        .await,
+        "UNAUTHORIZED",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn authors_cannot_delegate_to_themselves() {
+    let agents = Agents::setup(1).await;
+
+    // `create_post` sets the delegate from the capability of the call, so the entry is committed directly
+    let post = Post {
+        delegate: Some(agents.agent(0)),
+        ..sample_post(agents.agent(0), "Delegated")
+    };
+    assert_error_code(
+        create_raw_entry(&agents, 0, EntryTypes::Post(post)).await,
+        "INVALID",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_post_is_restored_from_one_of_its_deletes_by_its_author() {
+    let agents = Agents::setup(2).await;
+    let deleted = create_post(&agents, 0, "Deleted")
+        .await
+        .action_address()
+        .clone();
+    let copy = create_post(&agents, 0, "Copy")
+        .await
+        .action_address()
+        .clone();
+    agents.sync().await;
+    let tag = encode_link_tag(RestorationTag {
+        delete_hash: deleted.clone(),
+    })
+    .unwrap();
+
+    // The tagged action must be a delete of the restored Post
+    assert_error_code(
+        create_raw_link(
+            &agents,
+            0,
+            deleted.clone(),
+            copy.clone(),
+            LinkTypes::PostRestorations,
+            tag.clone(),
+        )
+        .await,
+        "INVALID",
+    );
+    assert_error_code(
+        create_raw_link(
+            &agents,
+            0,
+            deleted.clone(),
+            copy.clone(),
+            LinkTypes::PostRestorations,
+            ().into(),
+        )
+        .await,
+        "INVALID",
+    );
+    assert_error_code(
+        create_raw_link(&agents, 1, deleted, copy, LinkTypes::PostRestorations, tag).await,
+        "UNAUTHORIZED",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn new_posts_use_the_latest_schema_version() {
+    let agents = Agents::setup(1).await;
+    let post = sample_post(agents.agent(0), "Legacy");
+    let PostVersions::V2(latest) = PostVersions::from(post.clone()) else {
+        panic!("Posts are written with the latest schema version");
+    };
+
+    let input = CreateRawEntryBytesInput {
+        entry_of_type: EntryTypes::Post(post),
+        bytes: ExternIO::encode(latest.post).unwrap().0,
+    };
+    assert_error_code(
+        agents
+            .call_test_zome_fallible::<_, ActionHash>(0, "create_raw_entry_bytes", input)
+            .await,
+        "INVALID",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn listing_tags_must_be_decodable_and_current() {
+    let agents = Agents::setup(1).await;
+    let record = create_post(&agents, 0, "Listed").await;
+    let links: Vec<Link> = agents.call(0, "get_all_posts", ()).await;
+
+    assert_error_code(
+        create_raw_link(
+            &agents,
+            0,
+            links[0].base.clone(),
+            record.action_address().clone(),
+            LinkTypes::AllPosts,
+            ().into(),
+        )
+        .await,
+        "MALFORMED_LINK_TAG",
+    );
+    let future_tag = PostTag {
+        version: 2,
+        ..PostTag::new(&post_of(&record))
+    };
+    assert_error_code(
+        create_raw_link(
+            &agents,
+            0,
+            links[0].base.clone(),
+            record.action_address().clone(),
+            LinkTypes::AllPosts,
+            encode_link_tag(future_tag).unwrap(),
+        )
+        .await,
+        "INVALID",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn provenance_links_are_made_by_the_importer_from_the_related_actions() {
+    let agents = Agents::setup(2).await;
+    let original_hash = create_post(&agents, 0, "Migrated")
+        .await
+        .action_address()
+        .clone();
+    // The successor DNA is the same one here, so the agent imports into the cell it exports from
+    let export: MigrationExport = agents.call(0, "export_for_migration", ()).await;
+    let report: MigrationReport = agents.call(0, "import_for_migration", export).await;
+    let imported_hash = report.imported[0].imported_hash.clone();
+    let provenance: Option<Record> = agents
+        .call(0, "get_provenance", imported_hash.clone())
+        .await;
+    let provenance_hash = provenance.unwrap().action_address().clone();
+    agents.sync().await;
+
+    assert_error_code(
+        create_raw_link(
+            &agents,
+            0,
+            imported_hash,
+            provenance_hash.clone(),
+            LinkTypes::OriginalToProvenance,
+            ().into(),
+        )
+        .await,
+        "INVALID",
+    );
+    assert_error_code(
+        create_raw_link(
+            &agents,
+            1,
+            original_hash,
+            provenance_hash,
+            LinkTypes::OriginalToProvenance,
+            ().into(),
+        )
+        .await,
+        "UNAUTHORIZED",
+    );
+}
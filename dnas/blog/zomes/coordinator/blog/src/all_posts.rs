@@ -1,9 +1,47 @@
 use blog_integrity::*;
 use hdk::prelude::*;
 
+use crate::pinned_posts::get_pinned_posts;
+use crate::post::{describe_post, PostListing};
+use crate::revision_tags::with_revision_tags;
+
 // get_all_posts here
 #[hdk_extern]
 pub fn get_all_posts() -> ExternResult<Vec<Link>> {
     let path = Path::from("all_posts");
     get_links(GetLinksInputBuilder::try_new(path.path_entry_hash()?, LinkTypes::AllPosts)?.build())
 }
+
+// Lists every post without getting any of them
+#[hdk_extern]
+pub fn get_all_post_listings() -> ExternResult<Vec<PostListing>> {
+    let path = Path::from("all_posts");
+    let links = get_all_posts(())?;
+    with_revision_tags(path.path_entry_hash()?, LinkTypes::PostRevisionTags, links)?
+        .into_iter()
+        .map(|link| PostListing::from_link(link, describe_post))
+        .filter_map(Result::transpose)
+        .collect()
+}
+
+// Returns the currently pinned posts first, followed by the rest of the posts
+#[hdk_extern]
+pub fn get_all_posts_pinned_first() -> ExternResult<Vec<Link>> {
+    let all_posts = get_all_posts(())?;
+    let mut links: Vec<Link> = vec![];
+    for link in get_pinned_posts(())? {
+        // Unlisting or deleting a post doesn't unpin it, but only listed posts are shown
+        let is_listed = all_posts.iter().any(|listed| listed.target == link.target);
+        if is_listed && !links.iter().any(|pinned| pinned.target == link.target) {
+            links.push(link);
+        }
+    }
+    let pinned_targets: Vec<AnyLinkableHash> =
+        links.iter().map(|link| link.target.clone()).collect();
+    links.extend(
+        all_posts
+            .into_iter()
+            .filter(|link| !pinned_targets.contains(&link.target)),
+    );
+    Ok(links)
+}
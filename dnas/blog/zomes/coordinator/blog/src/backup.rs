@@ -0,0 +1,442 @@
+use blog_integrity::*;
+use hdk::prelude::holo_hash::{ActionHashB64, AgentPubKeyB64, EntryHashB64};
+use hdk::prelude::*;
+
+use crate::comment::{
+    create_comment, delete_comment, get_all_revisions_for_comment, get_oldest_delete_for_comment,
+    update_comment, UpdateCommentInput,
+};
+use crate::migration::query_created;
+use crate::post::{
+    create_post, delete_post, get_all_revisions_for_post, get_oldest_delete_for_post, update_post,
+    UpdatePostInput,
+};
+
+// Identifies the documents produced by `export_my_blog`
+pub const BLOG_EXPORT_FORMAT: &str = "holochain-blog-export";
+// Bumped whenever the layout of the exported document changes
+pub const BLOG_EXPORT_VERSION: u32 = 1;
+
+/// A JSON document with every revision and deletion of the posts and comments of an agent.
+/// Hashes are base64 encoded and timestamps are microseconds since the UNIX epoch.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlogExport {
+    pub format: String,
+    pub version: u32,
+    pub author: AgentPubKeyB64,
+    pub exported_at: Timestamp,
+    pub posts: Vec<ExportedPost>,
+    pub comments: Vec<ExportedComment>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportedDeletion {
+    pub action_hash: ActionHashB64,
+    pub timestamp: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportedPostRevision {
+    pub action_hash: ActionHashB64,
+    pub timestamp: Timestamp,
+    pub name: String,
+    // Empty for private posts, whose content is only readable by their recipients
+    pub content: String,
+    pub format: ContentFormat,
+    pub attachments: Vec<EntryHashB64>,
+    pub edit: Option<EditMetadata>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportedPost {
+    pub original_hash: ActionHashB64,
+    pub co_authors: Vec<AgentPubKeyB64>,
+    pub private: bool,
+    // Ordered from the original to the latest revision
+    pub revisions: Vec<ExportedPostRevision>,
+    pub deletion: Option<ExportedDeletion>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportedCommentRevision {
+    pub action_hash: ActionHashB64,
+    pub timestamp: Timestamp,
+    pub content: String,
+    pub edit: Option<EditMetadata>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportedComment {
+    pub original_hash: ActionHashB64,
+    pub post_hash: ActionHashB64,
+    // Ordered from the original to the latest revision
+    pub revisions: Vec<ExportedCommentRevision>,
+    pub deletion: Option<ExportedDeletion>,
+}
+
+fn sorted_by_timestamp(mut records: Vec<Record>) -> Vec<Record> {
+    records.sort_by_key(|record| record.action().timestamp());
+    records
+}
+
+fn export_deletion(delete: Option<SignedActionHashed>) -> Option<ExportedDeletion> {
+    delete.map(|delete| ExportedDeletion {
+        action_hash: delete.action_address().clone().into(),
+        timestamp: delete.action().timestamp(),
+    })
+}
+
+fn export_post(original_hash: ActionHash) -> ExternResult<ExportedPost> {
+    let mut revisions = vec![];
+    let mut original_post = None;
+    for record in sorted_by_timestamp(get_all_revisions_for_post(original_hash.clone())?) {
+        let post = Post::try_from(&record)?;
+        revisions.push(ExportedPostRevision {
+            action_hash: record.action_address().clone().into(),
+            timestamp: record.action().timestamp(),
+            name: post.name.clone(),
+            content: post.content.clone(),
+            format: post.format,
+            attachments: post.attachments.iter().cloned().map(Into::into).collect(),
+            edit: post.edit.clone(),
+        });
+        original_post.get_or_insert(post);
+    }
+    let original_post = original_post.ok_or(BlogError::PostNotFound)?;
+    Ok(ExportedPost {
+        original_hash: original_hash.clone().into(),
+        co_authors: original_post
+            .co_authors
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        private: original_post.envelope.is_some(),
+        revisions,
+        deletion: export_deletion(get_oldest_delete_for_post(original_hash)?),
+    })
+}
+
+fn export_comment(original_hash: ActionHash) -> ExternResult<ExportedComment> {
+    let mut revisions = vec![];
+    let mut post_hash = None;
+    for record in sorted_by_timestamp(get_all_revisions_for_comment(original_hash.clone())?) {
+        let comment = Comment::try_from(&record)?;
+        revisions.push(ExportedCommentRevision {
+            action_hash: record.action_address().clone().into(),
+            timestamp: record.action().timestamp(),
+            content: comment.content,
+            edit: comment.edit,
+        });
+        post_hash.get_or_insert(comment.post_hash);
+    }
+    let post_hash = post_hash.ok_or(BlogError::CommentNotFound)?;
+    Ok(ExportedComment {
+        original_hash: original_hash.clone().into(),
+        post_hash: post_hash.into(),
+        revisions,
+        deletion: export_deletion(get_oldest_delete_for_comment(original_hash)?),
+    })
+}
+
+/// Exports every post and comment that this agent authored, including their edits and deletions,
+/// as a self-describing JSON document
+#[hdk_extern]
+pub fn export_my_blog() -> ExternResult<String> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let mut posts = vec![];
+    for record in query_created(UnitEntryTypes::Post)? {
+        // Co-authors also commit the posts they countersigned, which are exported by their author
+        if Post::try_from(&record)?.author != me {
+            continue;
+        }
+        posts.push(export_post(record.action_address().clone())?);
+    }
+    let mut comments = vec![];
+    for record in query_created(UnitEntryTypes::Comment)? {
+        comments.push(export_comment(record.action_address().clone())?);
+    }
+    let export = BlogExport {
+        format: BLOG_EXPORT_FORMAT.to_string(),
+        version: BLOG_EXPORT_VERSION,
+        author: me.into(),
+        exported_at: sys_time()?,
+        posts,
+        comments,
+    };
+    serde_json::to_string_pretty(&export).map_err(|e| {
+        BlogError::Invalid(format!(
+            "The blog export could not be written as a document: {e}"
+        ))
+        .into()
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImportedBlogRecord {
+    pub exported_hash: ActionHash,
+    pub imported_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ImportBlogReport {
+    pub imported: Vec<ImportedBlogRecord>,
+    // Records that already exist in this cell, either because they were exported from it
+    // or because they were already imported from the same exported record
+    pub existing: Vec<ImportedBlogRecord>,
+    // Records that can't be replayed: co-authored and private posts,
+    // and comments on posts that don't exist in this cell
+    pub skipped: Vec<ActionHash>,
+}
+
+fn parse_export(json: &str) -> ExternResult<BlogExport> {
+    let export: BlogExport = serde_json::from_str(json)
+        .map_err(|e| BlogError::Invalid(format!("The blog export is not a valid document: {e}")))?;
+    if export.format != BLOG_EXPORT_FORMAT {
+        return Err(BlogError::Invalid(format!(
+            "Unknown blog export format \"{}\"",
+            export.format
+        ))
+        .into());
+    }
+    if export.version > BLOG_EXPORT_VERSION {
+        return Err(BlogError::Invalid(format!(
+            "Unsupported blog export version {}",
+            export.version
+        ))
+        .into());
+    }
+    Ok(export)
+}
+
+fn exists(action_hash: &ActionHash) -> ExternResult<bool> {
+    Ok(get(action_hash.clone(), GetOptions::default())?.is_some())
+}
+
+// Whether the exported record is visible from this cell and was committed by this agent,
+// in which case the export is being imported back into the network it came from
+fn is_own_record(action_hash: &ActionHash, me: &AgentPubKey) -> ExternResult<bool> {
+    Ok(get(action_hash.clone(), GetOptions::default())?
+        .is_some_and(|record| record.action().author() == me))
+}
+
+// Only keeps the attachments whose manifests can be found from this cell
+fn available_attachments(attachments: &[EntryHashB64]) -> ExternResult<Vec<EntryHash>> {
+    let mut available = vec![];
+    for attachment in attachments {
+        let attachment = EntryHash::from(attachment.clone());
+        if get(attachment.clone(), GetOptions::default())?.is_some() {
+            available.push(attachment);
+        }
+    }
+    Ok(available)
+}
+
+fn revision_post(
+    me: &AgentPubKey,
+    revision: &ExportedPostRevision,
+    attachments: Vec<EntryHash>,
+) -> Post {
+    Post {
+        name: revision.name.clone(),
+        content: revision.content.clone(),
+        author: me.clone(),
+        co_authors: vec![],
+        delegate: None,
+        envelope: None,
+        format: revision.format,
+        attachments,
+        reverted_from: None,
+        edit: None,
+    }
+}
+
+// Finds the record that this agent already replayed from the exported one
+fn find_imported(exported_hash: &ActionHash, me: &AgentPubKey) -> ExternResult<Option<ActionHash>> {
+    let links = get_links(
+        GetLinksInputBuilder::try_new(exported_hash.clone(), LinkTypes::ExportedToImported)?
+            .author(me.clone())
+            .build(),
+    )?;
+    Ok(links
+        .into_iter()
+        .min_by_key(|link| link.timestamp)
+        .and_then(|link| link.target.into_action_hash()))
+}
+
+fn record_imported(exported_hash: ActionHash, imported_hash: ActionHash) -> ExternResult<()> {
+    create_link(
+        exported_hash,
+        imported_hash,
+        LinkTypes::ExportedToImported,
+        (),
+    )?;
+    Ok(())
+}
+
+fn edit_input(edit: &Option<EditMetadata>) -> (Option<String>, bool) {
+    match edit {
+        Some(edit) => (edit.reason.clone(), edit.minor),
+        None => (None, false),
+    }
+}
+
+fn import_post(me: &AgentPubKey, post: &ExportedPost) -> ExternResult<ActionHash> {
+    let (original, updates) = post
+        .revisions
+        .split_first()
+        .ok_or(BlogError::RevisionNotFound)?;
+    let original_hash = create_post(revision_post(
+        me,
+        original,
+        available_attachments(&original.attachments)?,
+    ))?
+    .action_address()
+    .clone();
+    let mut previous_hash = original_hash.clone();
+    for update in updates {
+        let (edit_reason, minor_edit) = edit_input(&update.edit);
+        previous_hash = update_post(UpdatePostInput {
+            original_post_hash: original_hash.clone(),
+            previous_post_hash: previous_hash,
+            updated_post: revision_post(me, update, available_attachments(&update.attachments)?),
+            edit_reason,
+            minor_edit,
+        })?
+        .action_address()
+        .clone();
+    }
+    Ok(original_hash)
+}
+
+fn import_comment(
+    post_hash: ActionHash,
+    me: &AgentPubKey,
+    comment: &ExportedComment,
+) -> ExternResult<ActionHash> {
+    let (original, updates) = comment
+        .revisions
+        .split_first()
+        .ok_or(BlogError::RevisionNotFound)?;
+    let revision_comment = |revision: &ExportedCommentRevision| Comment {
+        content: revision.content.clone(),
+        post_hash: post_hash.clone(),
+        author: me.clone(),
+        reverted_from: None,
+        edit: None,
+        comment_lock_hash: None,
+    };
+    let original_hash = create_comment(revision_comment(original))?
+        .action_address()
+        .clone();
+    let mut previous_hash = original_hash.clone();
+    for update in updates {
+        let (edit_reason, minor_edit) = edit_input(&update.edit);
+        previous_hash = update_comment(UpdateCommentInput {
+            previous_comment_hash: previous_hash,
+            updated_comment: revision_comment(update),
+            edit_reason,
+            minor_edit,
+        })?
+        .action_address()
+        .clone();
+    }
+    Ok(original_hash)
+}
+
+/// Replays a document produced by `export_my_blog` into this cell as the calling agent.
+/// Records that already exist are not duplicated, but their exported deletion is still applied.
+#[hdk_extern]
+pub fn import_blog(json: String) -> ExternResult<ImportBlogReport> {
+    let export = parse_export(&json)?;
+    let me = agent_info()?.agent_initial_pubkey;
+    let mut report = ImportBlogReport::default();
+    let mut post_hashes: Vec<(ActionHash, ActionHash)> = vec![];
+    // Posts are only deleted once their comments have been replayed
+    let mut deleted_posts: Vec<ActionHash> = vec![];
+
+    for post in &export.posts {
+        let exported_hash = ActionHash::from(post.original_hash.clone());
+        let (Some(_), false, false) = (
+            post.revisions.first(),
+            post.private,
+            !post.co_authors.is_empty(),
+        ) else {
+            report.skipped.push(exported_hash);
+            continue;
+        };
+        let existing_hash = match is_own_record(&exported_hash, &me)? {
+            true => Some(exported_hash.clone()),
+            false => find_imported(&exported_hash, &me)?,
+        };
+        let imported_hash = match existing_hash {
+            Some(existing_hash) => {
+                report.existing.push(ImportedBlogRecord {
+                    exported_hash: exported_hash.clone(),
+                    imported_hash: existing_hash.clone(),
+                });
+                existing_hash
+            }
+            None => {
+                let imported_hash = import_post(&me, post)?;
+                record_imported(exported_hash.clone(), imported_hash.clone())?;
+                report.imported.push(ImportedBlogRecord {
+                    exported_hash: exported_hash.clone(),
+                    imported_hash: imported_hash.clone(),
+                });
+                imported_hash
+            }
+        };
+        if post.deletion.is_some() && get_oldest_delete_for_post(imported_hash.clone())?.is_none() {
+            deleted_posts.push(imported_hash.clone());
+        }
+        post_hashes.push((exported_hash, imported_hash));
+    }
+
+    for comment in &export.comments {
+        let exported_hash = ActionHash::from(comment.original_hash.clone());
+        let exported_post_hash = ActionHash::from(comment.post_hash.clone());
+        let post_hash = match post_hashes
+            .iter()
+            .find(|(exported, _)| exported == &exported_post_hash)
+        {
+            Some((_, imported)) => Some(imported.clone()),
+            // Comments on the posts of other agents are kept if those posts can be found
+            None => exists(&exported_post_hash)?.then_some(exported_post_hash),
+        };
+        let (Some(post_hash), Some(_)) = (post_hash, comment.revisions.first()) else {
+            report.skipped.push(exported_hash);
+            continue;
+        };
+        let existing_hash = match is_own_record(&exported_hash, &me)? {
+            true => Some(exported_hash.clone()),
+            false => find_imported(&exported_hash, &me)?,
+        };
+        let imported_hash = match existing_hash {
+            Some(existing_hash) => {
+                report.existing.push(ImportedBlogRecord {
+                    exported_hash,
+                    imported_hash: existing_hash.clone(),
+                });
+                existing_hash
+            }
+            None => {
+                let imported_hash = import_comment(post_hash, &me, comment)?;
+                record_imported(exported_hash.clone(), imported_hash.clone())?;
+                report.imported.push(ImportedBlogRecord {
+                    exported_hash,
+                    imported_hash: imported_hash.clone(),
+                });
+                imported_hash
+            }
+        };
+        if comment.deletion.is_some()
+            && get_oldest_delete_for_comment(imported_hash.clone())?.is_none()
+        {
+            delete_comment(imported_hash)?;
+        }
+    }
+    for post_hash in deleted_posts {
+        delete_post(post_hash)?;
+    }
+    Ok(report)
+}
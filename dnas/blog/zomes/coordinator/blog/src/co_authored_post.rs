@@ -0,0 +1,152 @@
+use blog_integrity::*;
+use hdk::prelude::*;
+
+// How long the co-authors have to accept and commit a co-authored post
+const CO_AUTHORING_SESSION_MILLIS: u64 = 5 * 60 * 1000;
+
+fn accept_preflight_request(request: PreflightRequest) -> ExternResult<PreflightResponse> {
+    match accept_countersigning_preflight_request(request)? {
+        PreflightRequestAcceptance::Accepted(response) => Ok(response),
+        PreflightRequestAcceptance::UnacceptableFutureStart => {
+            Err(BlogError::CountersigningFailed(
+                "The co-authoring session starts too far in the future".to_string(),
+            )
+            .into())
+        }
+        PreflightRequestAcceptance::UnacceptableAgentNotFound => {
+            Err(BlogError::Unauthorized("You are not an author of this Post".to_string()).into())
+        }
+        PreflightRequestAcceptance::AnotherSessionIsInProgress => {
+            Err(BlogError::CountersigningFailed(
+                "Another countersigning session is in progress".to_string(),
+            )
+            .into())
+        }
+        PreflightRequestAcceptance::Invalid(reason) => Err(BlogError::CountersigningFailed(
+            format!("Invalid co-authoring session: {reason}"),
+        )
+        .into()),
+    }
+}
+
+/// Starts a countersigning session for a post with co-authors, returning the response of its author
+/// that has to be sent to every co-author together with the post
+#[hdk_extern]
+pub fn start_co_authored_post(post: Post) -> ExternResult<PreflightResponse> {
+    if post.author != agent_info()?.agent_initial_pubkey {
+        return Err(BlogError::Unauthorized(
+            "Only the author of a Post can start co-authoring it".to_string(),
+        )
+        .into());
+    }
+    if post.co_authors.is_empty() {
+        return Err(BlogError::Invalid(
+            "A co-authored Post needs at least one co-author".to_string(),
+        )
+        .into());
+    }
+    let signing_agents = std::iter::once(post.author.clone())
+        .chain(post.co_authors.iter().cloned())
+        .map(|agent| (agent, vec![]))
+        .collect();
+    let request = PreflightRequest::try_new(
+        hash_entry(&post)?,
+        signing_agents,
+        vec![],
+        0,
+        false,
+        session_times_from_millis(CO_AUTHORING_SESSION_MILLIS)?,
+        ActionBase::Create(CreateBase::new(UnitEntryTypes::Post.try_into()?)),
+        PreflightBytes(vec![]),
+    )
+    .map_err(|e| BlogError::CountersigningFailed(e.to_string()))?;
+    accept_preflight_request(request)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AcceptCoAuthoredPostInput {
+    pub post: Post,
+    pub request: PreflightRequest,
+}
+
+/// Joins the countersigning session of a post as one of its co-authors
+#[hdk_extern]
+pub fn accept_co_authored_post(
+    input: AcceptCoAuthoredPostInput,
+) -> ExternResult<PreflightResponse> {
+    if input.request.app_entry_hash != hash_entry(&input.post)? {
+        return Err(BlogError::Invalid(
+            "The co-authoring session is not for this Post".to_string(),
+        )
+        .into());
+    }
+    if !input
+        .post
+        .co_authors
+        .contains(&agent_info()?.agent_initial_pubkey)
+    {
+        return Err(
+            BlogError::Unauthorized("You are not a co-author of this Post".to_string()).into(),
+        );
+    }
+    accept_preflight_request(input.request)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommitCoAuthoredPostInput {
+    pub post: Post,
+    pub responses: Vec<PreflightResponse>,
+}
+
+/// Commits the countersigned post once every author has accepted the session,
+/// which each author must do with the same responses
+#[hdk_extern]
+pub fn commit_co_authored_post(input: CommitCoAuthoredPostInput) -> ExternResult<ActionHash> {
+    let mut responses = input.responses;
+    responses.sort_by_key(|response| *response.agent_state.agent_index());
+    let session_data = CounterSigningSessionData::try_from_responses(responses, vec![])
+        .map_err(|e| BlogError::CountersigningFailed(e.to_string()))?;
+    let entry_types = EntryTypes::Post(input.post);
+    let ScopedEntryDefIndex {
+        zome_index,
+        zome_type,
+    } = (&entry_types).try_into()?;
+    let entry_visibility = EntryVisibility::from(&entry_types);
+    let EntryTypes::Post(post) = entry_types else {
+        unreachable!()
+    };
+    let entry_bytes =
+        AppEntryBytes::try_from(SerializedBytes::try_from(post).map_err(|e| wasm_error!(e))?)
+            .map_err(|e| BlogError::Invalid(e.to_string()))?;
+    create(CreateInput::new(
+        EntryDefLocation::app(zome_index, zome_type),
+        entry_visibility,
+        Entry::CounterSign(Box::new(session_data), entry_bytes),
+        ChainTopOrdering::Strict,
+    ))
+}
+
+/// Lists a co-authored post and indexes it under each of its authors,
+/// once the countersigning session has completed
+#[hdk_extern]
+pub fn index_co_authored_post(post_hash: ActionHash) -> ExternResult<()> {
+    let record = get(post_hash.clone(), GetOptions::default())?.ok_or(BlogError::PostNotFound)?;
+    let post = Post::try_from(&record)?;
+    let tag = encode_link_tag(PostTag::new(&post))?;
+    let path = Path::from("all_posts");
+    create_link(
+        path.path_entry_hash()?,
+        post_hash.clone(),
+        LinkTypes::AllPosts,
+        tag.clone(),
+    )?;
+    for author in std::iter::once(post.author).chain(post.co_authors) {
+        create_link(
+            author,
+            post_hash.clone(),
+            LinkTypes::AuthorToPosts,
+            tag.clone(),
+        )?;
+    }
+    Ok(())
+}
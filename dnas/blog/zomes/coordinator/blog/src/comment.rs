@@ -1,19 +1,33 @@
 use blog_integrity::*;
 use hdk::prelude::*;
 
+use crate::comment_lock::{get_latest_comment_lock, get_lock_timeline, was_locked_at};
+use crate::counts::*;
+use crate::diff::*;
+use crate::revision_tags::*;
+
 #[hdk_extern]
 pub fn create_comment(comment: Comment) -> ExternResult<Record> {
+    let comment_lock_record = get_latest_comment_lock(comment.post_hash.clone())?;
+    if let Some(comment_lock_record) = &comment_lock_record {
+        if CommentLock::try_from(comment_lock_record)?.locked {
+            return Err(BlogError::CommentsLocked.into());
+        }
+    }
+    let comment = Comment {
+        comment_lock_hash: comment_lock_record.map(|record| record.action_address().clone()),
+        ..comment
+    };
     let comment_hash = create_entry(&EntryTypes::Comment(comment.clone()))?;
     //create link from post to comment here
     create_link(
         comment.post_hash.clone(),
         comment_hash.clone(),
         LinkTypes::PostToComments,
-        (),
+        encode_link_tag(CommentTag::new(&comment))?,
     )?;
-    let record = get(comment_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
-        WasmErrorInner::Guest("Could not find the newly created Comment".to_string())
-    ))?;
+    let record =
+        get(comment_hash.clone(), GetOptions::default())?.ok_or(BlogError::CommentNotFound)?;
     Ok(record)
 }
 
@@ -24,9 +38,7 @@ pub fn get_original_comment(original_comment_hash: ActionHash) -> ExternResult<O
     };
     match details {
         Details::Record(details) => Ok(Some(details.record)),
-        _ => Err(wasm_error!(WasmErrorInner::Guest(
-            "Malformed get details response".to_string()
-        ))),
+        _ => Err(BlogError::MalformedDetails.into()),
     }
 }
 
@@ -37,9 +49,7 @@ pub fn get_latest_comment(original_comment_hash: ActionHash) -> ExternResult<Opt
         return Ok(None);
     };
     let record_details = match details {
-        Details::Entry(_) => Err(wasm_error!(WasmErrorInner::Guest(
-            "Malformed details".into()
-        ))),
+        Details::Entry(_) => Err(BlogError::MalformedDetails),
         Details::Record(record_details) => Ok(record_details),
     }?;
     match record_details.updates.last() {
@@ -51,21 +61,13 @@ pub fn get_latest_comment(original_comment_hash: ActionHash) -> ExternResult<Opt
 // delete_comment here
 #[hdk_extern]
 pub fn delete_comment(original_comment_hash: ActionHash) -> ExternResult<ActionHash> {
-    let details = get_details(original_comment_hash.clone(), GetOptions::default())?.ok_or(
-        wasm_error!(WasmErrorInner::Guest("Comment not found".to_string())),
-    )?;
+    let details = get_details(original_comment_hash.clone(), GetOptions::default())?
+        .ok_or(BlogError::CommentNotFound)?;
     let record = match details {
         Details::Record(details) => Ok(details.record),
-        _ => Err(wasm_error!(WasmErrorInner::Guest(
-            "Malformed get details response".to_string()
-        ))),
+        _ => Err(BlogError::MalformedDetails),
     }?;
-    let entry = record
-        .entry()
-        .as_option()
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Comment record has no entry".to_string()
-        )))?;
+    let entry = record.entry().as_option().ok_or(BlogError::MissingEntry)?;
     let comment = <Comment>::try_from(entry)?;
     let links = get_links(
         GetLinksInputBuilder::try_new(comment.post_hash.clone(), LinkTypes::PostToComments)?
@@ -84,9 +86,78 @@ pub fn delete_comment(original_comment_hash: ActionHash) -> ExternResult<ActionH
 // add get_comments_for_post here
 #[hdk_extern]
 pub fn get_comments_for_post(post_hash: ActionHash) -> ExternResult<Vec<Link>> {
-    get_links(GetLinksInputBuilder::try_new(post_hash, LinkTypes::PostToComments)?.build())
+    let lock_timeline = get_lock_timeline(post_hash.clone())?;
+    get_listed_comments(post_hash, &lock_timeline)
+}
+
+// A comment that records a lock older than the latest one when it was linked still validates,
+// so it is left out of the lists, as it is of the counts
+fn get_listed_comments(
+    post_hash: ActionHash,
+    lock_timeline: &[(Timestamp, bool)],
+) -> ExternResult<Vec<Link>> {
+    let links =
+        get_links(GetLinksInputBuilder::try_new(post_hash, LinkTypes::PostToComments)?.build())?;
+    Ok(links
+        .into_iter()
+        .filter(|link| !was_locked_at(lock_timeline, link.timestamp))
+        .collect())
+}
+
+/// A comment on a post, described by the tag of the link from the post or by that of its latest revision
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommentListing {
+    pub comment_hash: ActionHash,
+    pub timestamp: Timestamp,
+    pub tag: CommentTag,
 }
 
+impl CommentListing {
+    /// Lists a comment from the link from its post, falling back to `describe` when the tag of the
+    /// link can't be decoded, as with the empty tags of the links written before listing tags
+    pub fn from_link(
+        link: Link,
+        describe: impl FnOnce(&ActionHash) -> ExternResult<Option<CommentTag>>,
+    ) -> ExternResult<Option<CommentListing>> {
+        let comment_hash = link
+            .target
+            .into_action_hash()
+            .ok_or(BlogError::MalformedLink)?;
+        let tag = match decode_link_tag(link.tag) {
+            Ok(tag) => tag,
+            Err(_) => match describe(&comment_hash)? {
+                Some(tag) => tag,
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(CommentListing {
+            comment_hash,
+            timestamp: link.timestamp,
+            tag,
+        }))
+    }
+}
+
+// Describes a comment from its latest revision, for the links whose tag doesn't describe it
+fn describe_comment(comment_hash: &ActionHash) -> ExternResult<Option<CommentTag>> {
+    let Some(record) = get_latest_comment(comment_hash.clone())? else {
+        return Ok(None);
+    };
+    Ok(Comment::try_from(&record)
+        .ok()
+        .map(|comment| CommentTag::new(&comment)))
+}
+
+// Lists the comments on a post without getting any of them
+#[hdk_extern]
+pub fn get_comment_listings_for_post(post_hash: ActionHash) -> ExternResult<Vec<CommentListing>> {
+    let links = get_comments_for_post(post_hash.clone())?;
+    with_revision_tags(post_hash, LinkTypes::CommentRevisionTags, links)?
+        .into_iter()
+        .map(|link| CommentListing::from_link(link, describe_comment))
+        .filter_map(Result::transpose)
+        .collect()
+}
 
 #[hdk_extern]
 pub fn get_all_revisions_for_comment(
@@ -108,17 +179,73 @@ pub fn get_all_revisions_for_comment(
 pub struct UpdateCommentInput {
     pub previous_comment_hash: ActionHash,
     pub updated_comment: Comment,
+    #[serde(default)]
+    pub edit_reason: Option<String>,
+    #[serde(default)]
+    pub minor_edit: bool,
 }
 
 #[hdk_extern]
 pub fn update_comment(input: UpdateCommentInput) -> ExternResult<Record> {
-    let updated_comment_hash = update_entry(input.previous_comment_hash, &input.updated_comment)?;
-    let record = get(updated_comment_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
-        WasmErrorInner::Guest("Could not find the newly updated Comment".to_string())
-    ))?;
+    let updated_comment = Comment {
+        edit: EditMetadata::new(input.edit_reason, input.minor_edit),
+        ..input.updated_comment
+    };
+    let original_comment_hash = get_original_action_hash(input.previous_comment_hash.clone())?;
+    let updated_comment_hash = update_entry(input.previous_comment_hash, &updated_comment)?;
+    retag_revision(&original_comment_hash, &updated_comment)?;
+    let record = get(updated_comment_hash.clone(), GetOptions::default())?
+        .ok_or(BlogError::CommentNotFound)?;
     Ok(record)
 }
 
+// Tags the comment with the given revision under its post if this agent listed it there, leaving the
+// link that lists it, and so the order of the comments, as it is
+fn retag_revision(original_comment_hash: &ActionHash, comment: &Comment) -> ExternResult<()> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let links = get_links(
+        GetLinksInputBuilder::try_new(comment.post_hash.clone(), LinkTypes::PostToComments)?
+            .build(),
+    )?;
+    let is_listed = links.iter().any(|link| {
+        link.author == me
+            && link.target.clone().into_action_hash().as_ref() == Some(original_comment_hash)
+    });
+    if !is_listed {
+        return Ok(());
+    }
+    retag(
+        comment.post_hash.clone(),
+        LinkTypes::CommentRevisionTags,
+        original_comment_hash,
+        encode_link_tag(CommentTag::new(comment))?,
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevertCommentInput {
+    pub original_comment_hash: ActionHash,
+    pub revision_hash: ActionHash,
+    #[serde(default)]
+    pub edit_reason: Option<String>,
+}
+
+#[hdk_extern]
+pub fn revert_comment(input: RevertCommentInput) -> ExternResult<Record> {
+    let revision_comment = get_comment_revision(input.revision_hash.clone())?;
+    let latest_record =
+        get_latest_comment(input.original_comment_hash)?.ok_or(BlogError::CommentNotFound)?;
+    update_comment(UpdateCommentInput {
+        previous_comment_hash: latest_record.action_address().clone(),
+        updated_comment: Comment {
+            reverted_from: Some(input.revision_hash),
+            ..revision_comment
+        },
+        edit_reason: input.edit_reason,
+        minor_edit: false,
+    })
+}
+
 #[hdk_extern]
 pub fn get_all_deletes_for_comment(
     original_comment_hash: ActionHash,
@@ -127,9 +254,7 @@ pub fn get_all_deletes_for_comment(
         return Ok(None);
     };
     match details {
-        Details::Entry(_) => Err(wasm_error!(WasmErrorInner::Guest(
-            "Malformed details".into()
-        ))),
+        Details::Entry(_) => Err(BlogError::MalformedDetails.into()),
         Details::Record(record_details) => Ok(Some(record_details.deletes)),
     }
 }
@@ -150,7 +275,6 @@ pub fn get_oldest_delete_for_comment(
     Ok(deletes.first().cloned())
 }
 
-
 #[hdk_extern]
 pub fn get_deleted_comments_for_post(
     post_hash: ActionHash,
@@ -189,3 +313,96 @@ pub fn get_deleted_comments_for_author(
         .filter(|(_link, deletes)| !deletes.is_empty())
         .collect())
 }
+
+fn get_comment_revision(revision_hash: ActionHash) -> ExternResult<Comment> {
+    let record = get(revision_hash, GetOptions::default())?.ok_or(BlogError::RevisionNotFound)?;
+    Comment::try_from(&record)
+}
+
+#[hdk_extern]
+pub fn diff_comment_revisions(input: DiffRevisionsInput) -> ExternResult<Vec<DiffChunk>> {
+    let old_comment = get_comment_revision(input.old_revision_hash)?;
+    let new_comment = get_comment_revision(input.new_revision_hash)?;
+    Ok(diff_text(
+        &old_comment.content,
+        &new_comment.content,
+        input.granularity,
+    ))
+}
+
+#[hdk_extern]
+pub fn get_edit_history_for_comment(
+    original_comment_hash: ActionHash,
+) -> ExternResult<Vec<RevisionSummary>> {
+    let mut revisions = get_all_revisions_for_comment(original_comment_hash)?;
+    revisions.sort_by_key(|record| record.action().timestamp());
+    let mut history = vec![];
+    let mut previous_content = String::new();
+    for record in revisions {
+        let comment = Comment::try_from(&record)?;
+        history.push(RevisionSummary {
+            revision_hash: record.action_address().clone(),
+            author: record.action().author().clone(),
+            timestamp: record.action().timestamp(),
+            name_changed: false,
+            change_size: change_size(&previous_content, &comment.content),
+            edit: comment.edit.clone(),
+        });
+        previous_content = comment.content;
+    }
+    Ok(history)
+}
+
+/// Counts the live and the deleted comments on a post, leaving out those that its lists leave out
+///
+/// Comments are the only thing that can be counted on a post: this DNA has no reactions
+#[hdk_extern]
+pub fn count_comments_for_post(post_hash: ActionHash) -> ExternResult<LinkCount> {
+    let lock_timeline = get_lock_timeline(post_hash.clone())?;
+    count_links_for_base(post_hash, LinkTypes::PostToComments, |timestamp| {
+        !was_locked_at(&lock_timeline, timestamp)
+    })
+}
+
+#[hdk_extern]
+pub fn count_comments_for_posts(post_hashes: Vec<ActionHash>) -> ExternResult<Vec<LinkCount>> {
+    post_hashes
+        .into_iter()
+        .map(count_comments_for_post)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_legacy_link_is_listed_from_its_comment() {
+        let author = AgentPubKey::from_raw_36(vec![1; 36]);
+        let tag = CommentTag::new(&Comment {
+            content: "Fetched".to_string(),
+            post_hash: ActionHash::from_raw_36(vec![2; 36]),
+            author: author.clone(),
+            reverted_from: None,
+            edit: None,
+            comment_lock_hash: None,
+        });
+        let link = Link {
+            author,
+            base: AnyLinkableHash::from(ActionHash::from_raw_36(vec![2; 36])),
+            target: AnyLinkableHash::from(ActionHash::from_raw_36(vec![3; 36])),
+            timestamp: Timestamp::from_micros(1_000_000),
+            zome_index: 0.into(),
+            link_type: 0.into(),
+            tag: ().into(),
+            create_link_hash: ActionHash::from_raw_36(vec![4; 36]),
+        };
+        let listing = CommentListing::from_link(link.clone(), |_| Ok(Some(tag.clone())))
+            .unwrap()
+            .unwrap();
+        assert_eq!(listing.tag, tag);
+        assert!(CommentListing::from_link(link, |_| Ok(None))
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,87 @@
+use blog_integrity::*;
+use hdk::prelude::*;
+
+fn set_comment_lock(post_hash: ActionHash, locked: bool) -> ExternResult<Record> {
+    let comment_lock = CommentLock {
+        post_hash: post_hash.clone(),
+        locked,
+    };
+    let comment_lock_hash = create_entry(&EntryTypes::CommentLock(comment_lock))?;
+    create_link(
+        post_hash,
+        comment_lock_hash.clone(),
+        LinkTypes::PostToCommentLocks,
+        (),
+    )?;
+    let record =
+        get(comment_lock_hash, GetOptions::default())?.ok_or(BlogError::CommentLockNotFound)?;
+    Ok(record)
+}
+
+#[hdk_extern]
+pub fn lock_comments(post_hash: ActionHash) -> ExternResult<Record> {
+    set_comment_lock(post_hash, true)
+}
+
+#[hdk_extern]
+pub fn unlock_comments(post_hash: ActionHash) -> ExternResult<Record> {
+    set_comment_lock(post_hash, false)
+}
+
+#[hdk_extern]
+pub fn get_comment_lock_history(post_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let links = get_links(
+        GetLinksInputBuilder::try_new(post_hash, LinkTypes::PostToCommentLocks)?.build(),
+    )?;
+    let get_input: Vec<GetInput> = links
+        .into_iter()
+        .map(|link| {
+            Ok(GetInput::new(
+                link.target
+                    .into_action_hash()
+                    .ok_or(BlogError::MalformedLink)?
+                    .into(),
+                GetOptions::default(),
+            ))
+        })
+        .collect::<ExternResult<Vec<GetInput>>>()?;
+    let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+    let mut records: Vec<Record> = records.into_iter().flatten().collect();
+    records.sort_by_key(|record| record.action().timestamp());
+    Ok(records)
+}
+
+/// Returns the latest Comment Lock of the post, which new comments record
+pub fn get_latest_comment_lock(post_hash: ActionHash) -> ExternResult<Option<Record>> {
+    Ok(get_comment_lock_history(post_hash)?.pop())
+}
+
+#[hdk_extern]
+pub fn is_post_locked(post_hash: ActionHash) -> ExternResult<bool> {
+    let Some(latest_record) = get_latest_comment_lock(post_hash)? else {
+        return Ok(false);
+    };
+    Ok(CommentLock::try_from(&latest_record)?.locked)
+}
+
+/// The times at which the comments of the post were locked or unlocked, oldest first
+pub fn get_lock_timeline(post_hash: ActionHash) -> ExternResult<Vec<(Timestamp, bool)>> {
+    get_comment_lock_history(post_hash)?
+        .iter()
+        .map(|record| {
+            Ok((
+                record.action().timestamp(),
+                CommentLock::try_from(record)?.locked,
+            ))
+        })
+        .collect()
+}
+
+/// Whether the comments were locked at the given time, according to a timeline from `get_lock_timeline`
+pub fn was_locked_at(lock_timeline: &[(Timestamp, bool)], timestamp: Timestamp) -> bool {
+    lock_timeline
+        .iter()
+        .rev()
+        .find(|(lock_timestamp, _)| *lock_timestamp <= timestamp)
+        .is_some_and(|(_, locked)| *locked)
+}
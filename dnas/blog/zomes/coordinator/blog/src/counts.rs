@@ -0,0 +1,33 @@
+use blog_integrity::*;
+use hdk::prelude::*;
+
+/// The number of links from a base that are still live and that have been deleted
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct LinkCount {
+    pub live: usize,
+    pub deleted: usize,
+}
+
+/// Counts the live and the deleted links of the given type from the base, from the details of the
+/// links without getting their targets
+///
+/// Only the links created at a time for which `counted` holds are counted
+pub fn count_links_for_base(
+    base: impl Into<AnyLinkableHash>,
+    link_type: LinkTypes,
+    counted: impl Fn(Timestamp) -> bool,
+) -> ExternResult<LinkCount> {
+    let details = get_link_details(base, link_type, None, GetOptions::default())?;
+    let mut count = LinkCount::default();
+    for (create_link, deletes) in details.into_inner() {
+        if !counted(create_link.action().timestamp()) {
+            continue;
+        }
+        if deletes.is_empty() {
+            count.live += 1;
+        } else {
+            count.deleted += 1;
+        }
+    }
+    Ok(count)
+}
@@ -0,0 +1,166 @@
+use blog_integrity::*;
+use hdk::prelude::*;
+
+// The only functions that a delegate is allowed to call on behalf of its principal
+const DELEGATED_FUNCTIONS: [&str; 2] = ["create_post", "update_post"];
+
+/// Returns the agent that made the current call on behalf of the agent running this cell, if any
+pub fn get_call_delegate() -> ExternResult<Option<AgentPubKey>> {
+    let provenance = call_info()?.provenance;
+    if provenance == agent_info()?.agent_initial_pubkey {
+        return Ok(None);
+    }
+    Ok(Some(provenance))
+}
+
+fn is_delegation_grant(grant: &CapGrantEntry) -> ExternResult<bool> {
+    let zome_name = zome_info()?.name;
+    Ok(match &grant.functions {
+        GrantedFunctions::All => false,
+        GrantedFunctions::Listed(functions) => functions.iter().all(|(zome, function)| {
+            zome == &zome_name && DELEGATED_FUNCTIONS.contains(&function.0.as_str())
+        }),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IssueDelegationGrantInput {
+    pub tag: String,
+    // Assigns the grant to this agent, otherwise anyone holding the secret can use it
+    #[serde(default)]
+    pub delegate: Option<AgentPubKey>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DelegationGrant {
+    pub grant_hash: ActionHash,
+    pub cap_secret: CapSecret,
+}
+
+#[hdk_extern]
+pub fn issue_delegation_grant(input: IssueDelegationGrantInput) -> ExternResult<DelegationGrant> {
+    let cap_secret = generate_cap_secret()?;
+    let access = match input.delegate {
+        Some(delegate) => CapAccess::Assigned {
+            secret: cap_secret,
+            assignees: BTreeSet::from([delegate]),
+        },
+        None => CapAccess::Transferable { secret: cap_secret },
+    };
+    let zome_name = zome_info()?.name;
+    let functions = GrantedFunctions::Listed(
+        DELEGATED_FUNCTIONS
+            .iter()
+            .map(|function| (zome_name.clone(), FunctionName::from(*function)))
+            .collect(),
+    );
+    let grant_hash = create_cap_grant(CapGrantEntry::new(input.tag, access, functions))?;
+    Ok(DelegationGrant {
+        grant_hash,
+        cap_secret,
+    })
+}
+
+/// Lists the delegation grants issued by this agent that haven't been revoked
+#[hdk_extern]
+pub fn get_delegation_grants() -> ExternResult<Vec<Record>> {
+    let revoked: HashSet<ActionHash> =
+        query(ChainQueryFilter::new().action_type(ActionType::Delete))?
+            .into_iter()
+            .filter_map(|record| match record.action() {
+                Action::Delete(delete) => Some(delete.deletes_address.clone()),
+                _ => None,
+            })
+            .collect();
+    let grants = query(
+        ChainQueryFilter::new()
+            .entry_type(EntryType::CapGrant)
+            .include_entries(true),
+    )?;
+    let mut delegation_grants = vec![];
+    for record in grants {
+        if revoked.contains(record.action_address()) {
+            continue;
+        }
+        let Some(Entry::CapGrant(grant)) = record.entry().as_option() else {
+            continue;
+        };
+        if is_delegation_grant(grant)? {
+            delegation_grants.push(record);
+        }
+    }
+    Ok(delegation_grants)
+}
+
+#[hdk_extern]
+pub fn revoke_delegation_grant(grant_hash: ActionHash) -> ExternResult<ActionHash> {
+    let is_delegation_grant = get_delegation_grants(())?
+        .iter()
+        .any(|record| record.action_address() == &grant_hash);
+    if !is_delegation_grant {
+        return Err(BlogError::DelegationGrantNotFound.into());
+    }
+    delete_cap_grant(grant_hash)
+}
+
+fn call_principal<I, O>(
+    principal: AgentPubKey,
+    cap_secret: CapSecret,
+    fn_name: &str,
+    payload: I,
+) -> ExternResult<O>
+where
+    I: serde::Serialize + std::fmt::Debug,
+    O: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let response = call_remote(
+        principal,
+        zome_info()?.name,
+        fn_name.into(),
+        Some(cap_secret),
+        payload,
+    )?;
+    match response {
+        ZomeCallResponse::Ok(output) => output.decode().map_err(|e| wasm_error!(e)),
+        ZomeCallResponse::Unauthorized(..) => Err(BlogError::Unauthorized(
+            "Not authorized to post on behalf of this agent".to_string(),
+        )
+        .into()),
+        ZomeCallResponse::NetworkError(error) => {
+            Err(BlogError::NetworkError(format!("Could not reach the agent: {error}")).into())
+        }
+        ZomeCallResponse::CountersigningSession(error) => {
+            Err(BlogError::CountersigningFailed(error).into())
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreatePostAsInput {
+    pub principal: AgentPubKey,
+    pub cap_secret: CapSecret,
+    pub post: Post,
+}
+
+/// Creates a post authored by the principal, who must be online to commit it to their source chain
+#[hdk_extern]
+pub fn create_post_as(input: CreatePostAsInput) -> ExternResult<Record> {
+    call_principal(input.principal, input.cap_secret, "create_post", input.post)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdatePostAsInput {
+    pub principal: AgentPubKey,
+    pub cap_secret: CapSecret,
+    pub update: crate::post::UpdatePostInput,
+}
+
+#[hdk_extern]
+pub fn update_post_as(input: UpdatePostAsInput) -> ExternResult<Record> {
+    call_principal(
+        input.principal,
+        input.cap_secret,
+        "update_post",
+        input.update,
+    )
+}
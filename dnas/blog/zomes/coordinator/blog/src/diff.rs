@@ -0,0 +1,277 @@
+use std::ops::Range;
+
+use blog_integrity::EditMetadata;
+use hdk::prelude::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum DiffGranularity {
+    Line,
+    Word,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DiffChunk {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+// Splits the text into tokens that concatenate back to the original text,
+// so that the chunks of a diff can be rendered without losing whitespace
+fn tokenize(text: &str, granularity: DiffGranularity) -> Vec<&str> {
+    match granularity {
+        DiffGranularity::Line => text.split_inclusive('\n').collect(),
+        DiffGranularity::Word => {
+            let mut tokens = vec![];
+            let mut start = 0;
+            let mut previous_is_whitespace = None;
+            for (index, c) in text.char_indices() {
+                let is_whitespace = c.is_whitespace();
+                if previous_is_whitespace.is_some_and(|previous| previous != is_whitespace) {
+                    tokens.push(&text[start..index]);
+                    start = index;
+                }
+                previous_is_whitespace = Some(is_whitespace);
+            }
+            if start < text.len() {
+                tokens.push(&text[start..]);
+            }
+            tokens
+        }
+    }
+}
+
+fn push_chunk(chunks: &mut Vec<DiffChunk>, op: DiffOp, text: &str) {
+    match chunks.last_mut() {
+        Some(last) if last.op == op => last.text.push_str(text),
+        _ => chunks.push(DiffChunk {
+            op,
+            text: text.to_string(),
+        }),
+    }
+}
+
+/// Computes the diff between two texts as a list of chunks, with Myers' algorithm on their tokens
+///
+/// Takes O((n + m) * d) time and O(n + m) memory for n and m tokens and d differences,
+/// so that long revisions with few changes stay cheap to diff inside the wasm memory limits
+pub fn diff_text(old: &str, new: &str, granularity: DiffGranularity) -> Vec<DiffChunk> {
+    let old_tokens = tokenize(old, granularity);
+    let new_tokens = tokenize(new, granularity);
+    let mut diff = MyersDiff {
+        old: &old_tokens,
+        new: &new_tokens,
+        forward: Furthest::new(old_tokens.len(), new_tokens.len()),
+        backward: Furthest::new(old_tokens.len(), new_tokens.len()),
+        chunks: vec![],
+    };
+    diff.conquer(0..old_tokens.len(), 0..new_tokens.len());
+    diff.chunks
+}
+
+// Bounds the number of differences that the forward and backward searches each need to explore
+fn max_d(n: usize, m: usize) -> usize {
+    (n + m).div_ceil(2) + 1
+}
+
+// The furthest x reached on each diagonal k = x - y of the edit graph, indexed from -max_d to max_d
+struct Furthest {
+    offset: isize,
+    x: Vec<usize>,
+}
+
+impl Furthest {
+    fn new(n: usize, m: usize) -> Furthest {
+        let max_d = max_d(n, m);
+        Furthest {
+            offset: max_d as isize,
+            x: vec![0; 2 * max_d + 1],
+        }
+    }
+}
+
+impl std::ops::Index<isize> for Furthest {
+    type Output = usize;
+
+    fn index(&self, k: isize) -> &usize {
+        &self.x[(k + self.offset) as usize]
+    }
+}
+
+impl std::ops::IndexMut<isize> for Furthest {
+    fn index_mut(&mut self, k: isize) -> &mut usize {
+        &mut self.x[(k + self.offset) as usize]
+    }
+}
+
+struct MyersDiff<'a> {
+    old: &'a [&'a str],
+    new: &'a [&'a str],
+    forward: Furthest,
+    backward: Furthest,
+    chunks: Vec<DiffChunk>,
+}
+
+impl MyersDiff<'_> {
+    fn push(&mut self, op: DiffOp, tokens: &[&str]) {
+        for token in tokens {
+            push_chunk(&mut self.chunks, op, token);
+        }
+    }
+
+    fn common_prefix_len(&self, old: Range<usize>, new: Range<usize>) -> usize {
+        self.old[old]
+            .iter()
+            .zip(&self.new[new])
+            .take_while(|(old, new)| old == new)
+            .count()
+    }
+
+    fn common_suffix_len(&self, old: Range<usize>, new: Range<usize>) -> usize {
+        self.old[old]
+            .iter()
+            .rev()
+            .zip(self.new[new].iter().rev())
+            .take_while(|(old, new)| old == new)
+            .count()
+    }
+
+    // Diffs the ranges by splitting them at the middle of a shortest edit script, recursively
+    fn conquer(&mut self, mut old: Range<usize>, mut new: Range<usize>) {
+        let (old_tokens, new_tokens) = (self.old, self.new);
+        let prefix_len = self.common_prefix_len(old.clone(), new.clone());
+        self.push(
+            DiffOp::Equal,
+            &old_tokens[old.start..old.start + prefix_len],
+        );
+        old.start += prefix_len;
+        new.start += prefix_len;
+
+        let suffix_len = self.common_suffix_len(old.clone(), new.clone());
+        let suffix_start = old.end - suffix_len;
+        old.end -= suffix_len;
+        new.end -= suffix_len;
+
+        if old.is_empty() {
+            self.push(DiffOp::Insert, &new_tokens[new]);
+        } else if new.is_empty() {
+            self.push(DiffOp::Delete, &old_tokens[old]);
+        } else if let Some((x, y)) = self.find_middle_snake(old.clone(), new.clone()) {
+            self.conquer(old.start..x, new.start..y);
+            self.conquer(x..old.end, y..new.end);
+        } else {
+            self.push(DiffOp::Delete, &old_tokens[old]);
+            self.push(DiffOp::Insert, &new_tokens[new]);
+        }
+
+        self.push(
+            DiffOp::Equal,
+            &old_tokens[suffix_start..suffix_start + suffix_len],
+        );
+    }
+
+    // Searches forward from the start and backward from the end of the ranges until the paths overlap,
+    // returning the point where the overlapping snake starts
+    fn find_middle_snake(
+        &mut self,
+        old: Range<usize>,
+        new: Range<usize>,
+    ) -> Option<(usize, usize)> {
+        let (n, m) = (old.len(), new.len());
+        let delta = n as isize - m as isize;
+        let odd = delta & 1 == 1;
+        self.forward[1] = 0;
+        self.backward[1] = 0;
+        let max_d = max_d(n, m) as isize;
+        for d in 0..max_d {
+            for k in (-d..=d).rev().step_by(2) {
+                let mut x = if k == -d || (k != d && self.forward[k - 1] < self.forward[k + 1]) {
+                    self.forward[k + 1]
+                } else {
+                    self.forward[k - 1] + 1
+                };
+                let y = (x as isize - k) as usize;
+                let (x0, y0) = (x, y);
+                if x < n && y < m {
+                    x += self.common_prefix_len(old.start + x..old.end, new.start + y..new.end);
+                }
+                self.forward[k] = x;
+                if odd
+                    && (k - delta).abs() < d
+                    && self.forward[k] + self.backward[-(k - delta)] >= n
+                {
+                    return Some((old.start + x0, new.start + y0));
+                }
+            }
+            for k in (-d..=d).rev().step_by(2) {
+                let mut x = if k == -d || (k != d && self.backward[k - 1] < self.backward[k + 1]) {
+                    self.backward[k + 1]
+                } else {
+                    self.backward[k - 1] + 1
+                };
+                let mut y = (x as isize - k) as usize;
+                if x < n && y < m {
+                    let advance = self.common_suffix_len(
+                        old.start..old.start + n - x,
+                        new.start..new.start + m - y,
+                    );
+                    x += advance;
+                    y += advance;
+                }
+                self.backward[k] = x;
+                if !odd
+                    && (k - delta).abs() <= d
+                    && self.backward[k] + self.forward[-(k - delta)] >= n
+                {
+                    return Some((old.start + n - x, new.start + m - y));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct ChangeSize {
+    pub words_added: usize,
+    pub words_removed: usize,
+}
+
+/// Counts the words inserted and deleted between two texts
+pub fn change_size(old: &str, new: &str) -> ChangeSize {
+    let count_words = |text: &str| text.split_whitespace().count();
+    diff_text(old, new, DiffGranularity::Word).iter().fold(
+        ChangeSize::default(),
+        |mut size, chunk| {
+            match chunk.op {
+                DiffOp::Insert => size.words_added += count_words(&chunk.text),
+                DiffOp::Delete => size.words_removed += count_words(&chunk.text),
+                DiffOp::Equal => {}
+            }
+            size
+        },
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DiffRevisionsInput {
+    pub old_revision_hash: ActionHash,
+    pub new_revision_hash: ActionHash,
+    pub granularity: DiffGranularity,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RevisionSummary {
+    pub revision_hash: ActionHash,
+    pub author: AgentPubKey,
+    pub timestamp: Timestamp,
+    pub name_changed: bool,
+    pub change_size: ChangeSize,
+    pub edit: Option<EditMetadata>,
+}
@@ -0,0 +1,324 @@
+use blog_integrity::*;
+use hdk::prelude::holo_hash::{ActionHashB64, AgentPubKeyB64};
+use hdk::prelude::*;
+
+use crate::all_posts::get_all_posts;
+use crate::post::{get_latest_post, get_posts_for_author};
+use crate::render::{escape_html, render_content_html};
+use crate::visibility::get_unlisted_posts_for_author;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum FeedSource {
+    AllPosts,
+    Author(AgentPubKey),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum FeedFormat {
+    Atom,
+    Rss,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FeedInput {
+    pub source: FeedSource,
+    pub format: FeedFormat,
+    pub limit: usize,
+    pub title: String,
+    // The public address of the bridge serving the feed, used to link to the feed and its posts
+    pub base_url: String,
+}
+
+struct FeedEntry {
+    original_post_hash: ActionHash,
+    author: AgentPubKey,
+    published: Timestamp,
+    updated: Timestamp,
+    post: Post,
+}
+
+// Escapes text for XML, dropping the control characters that XML 1.0 can't represent at all
+fn escape_xml(text: &str) -> String {
+    let text: String = text
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .collect();
+    escape_html(&text)
+}
+
+// Feed readers identify entries by these, so they only depend on the original action hash
+fn post_id(original_post_hash: &ActionHash) -> String {
+    format!(
+        "urn:holochain:post:{}",
+        ActionHashB64::from(original_post_hash.clone())
+    )
+}
+
+fn post_url(base_url: &str, original_post_hash: &ActionHash) -> String {
+    format!(
+        "{}/posts/{}",
+        base_url.trim_end_matches('/'),
+        ActionHashB64::from(original_post_hash.clone())
+    )
+}
+
+fn feed_id(source: &FeedSource) -> String {
+    match source {
+        FeedSource::AllPosts => "urn:holochain:feed:all_posts".to_string(),
+        FeedSource::Author(author) => {
+            format!(
+                "urn:holochain:feed:author:{}",
+                AgentPubKeyB64::from(author.clone())
+            )
+        }
+    }
+}
+
+// Converts days since the UNIX epoch to a proleptic Gregorian (year, month, day)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+struct DateTime {
+    year: i64,
+    month: u32,
+    day: u32,
+    weekday: usize,
+    hour: i64,
+    minute: i64,
+    second: i64,
+}
+
+fn date_time(timestamp: Timestamp) -> DateTime {
+    let seconds = timestamp.as_micros().div_euclid(1_000_000);
+    let days = seconds.div_euclid(86_400);
+    let seconds_of_day = seconds.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    DateTime {
+        year,
+        month,
+        day,
+        // The UNIX epoch was a Thursday
+        weekday: (days + 4).rem_euclid(7) as usize,
+        hour: seconds_of_day / 3_600,
+        minute: seconds_of_day % 3_600 / 60,
+        second: seconds_of_day % 60,
+    }
+}
+
+/// Formats a timestamp as RFC 3339, as required by Atom
+pub fn format_rfc3339(timestamp: Timestamp) -> String {
+    let t = date_time(timestamp);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        t.year, t.month, t.day, t.hour, t.minute, t.second
+    )
+}
+
+/// Formats a timestamp as RFC 822, as required by RSS
+pub fn format_rfc822(timestamp: Timestamp) -> String {
+    const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let t = date_time(timestamp);
+    format!(
+        "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+        WEEKDAYS[t.weekday],
+        t.day,
+        MONTHS[t.month as usize - 1],
+        t.year,
+        t.hour,
+        t.minute,
+        t.second
+    )
+}
+
+fn feed_links(source: &FeedSource) -> ExternResult<Vec<Link>> {
+    match source {
+        FeedSource::AllPosts => get_all_posts(()),
+        FeedSource::Author(author) => {
+            let unlisted: Vec<AnyLinkableHash> = get_unlisted_posts_for_author(author.clone())?
+                .into_iter()
+                .map(|link| link.target)
+                .collect();
+            Ok(get_posts_for_author(author.clone())?
+                .into_iter()
+                .filter(|link| !unlisted.contains(&link.target))
+                .collect())
+        }
+    }
+}
+
+// Collects the latest `limit` posts of the source, newest first, skipping private and deleted ones
+fn get_feed_entries(source: &FeedSource, limit: usize) -> ExternResult<Vec<FeedEntry>> {
+    let mut links = feed_links(source)?;
+    links.sort_by_key(|link| std::cmp::Reverse(link.timestamp));
+    let mut entries: Vec<FeedEntry> = vec![];
+    for link in links {
+        if entries.len() == limit {
+            break;
+        }
+        let Some(original_post_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if entries
+            .iter()
+            .any(|entry| entry.original_post_hash == original_post_hash)
+        {
+            continue;
+        }
+        let Some(Details::Record(details)) =
+            get_details(original_post_hash.clone(), GetOptions::default())?
+        else {
+            continue;
+        };
+        if !details.deletes.is_empty() {
+            continue;
+        }
+        let Some(latest_record) = get_latest_post(original_post_hash.clone())? else {
+            continue;
+        };
+        let post = Post::try_from(&latest_record)?;
+        if post.envelope.is_some() {
+            continue;
+        }
+        entries.push(FeedEntry {
+            original_post_hash,
+            author: details.record.action().author().clone(),
+            published: details.record.action().timestamp(),
+            updated: latest_record.action().timestamp(),
+            post,
+        });
+    }
+    Ok(entries)
+}
+
+fn render_atom(input: &FeedInput, entries: &[FeedEntry]) -> String {
+    let updated = entries
+        .iter()
+        .map(|entry| entry.updated)
+        .max()
+        .unwrap_or(Timestamp::from_micros(0));
+    let base_url = input.base_url.trim_end_matches('/');
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!("  <id>{}</id>\n", feed_id(&input.source)));
+    xml.push_str(&format!("  <title>{}</title>\n", escape_xml(&input.title)));
+    xml.push_str(&format!(
+        "  <updated>{}</updated>\n",
+        format_rfc3339(updated)
+    ));
+    xml.push_str(&format!(
+        "  <link rel=\"alternate\" href=\"{}\" />\n",
+        escape_xml(base_url)
+    ));
+    for entry in entries {
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!(
+            "    <id>{}</id>\n",
+            post_id(&entry.original_post_hash)
+        ));
+        xml.push_str(&format!(
+            "    <title>{}</title>\n",
+            escape_xml(&entry.post.name)
+        ));
+        xml.push_str(&format!(
+            "    <link rel=\"alternate\" href=\"{}\" />\n",
+            escape_xml(&post_url(base_url, &entry.original_post_hash))
+        ));
+        xml.push_str(&format!(
+            "    <published>{}</published>\n",
+            format_rfc3339(entry.published)
+        ));
+        xml.push_str(&format!(
+            "    <updated>{}</updated>\n",
+            format_rfc3339(entry.updated)
+        ));
+        // There is no profiles zome in this DNA, so the author is identified by their public key
+        xml.push_str(&format!(
+            "    <author><name>{}</name></author>\n",
+            AgentPubKeyB64::from(entry.author.clone())
+        ));
+        xml.push_str(&format!(
+            "    <content type=\"html\">{}</content>\n",
+            escape_xml(&render_content_html(entry.post.format, &entry.post.content))
+        ));
+        xml.push_str("  </entry>\n");
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn render_rss(input: &FeedInput, entries: &[FeedEntry]) -> String {
+    let base_url = input.base_url.trim_end_matches('/');
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\">\n");
+    xml.push_str("  <channel>\n");
+    xml.push_str(&format!(
+        "    <title>{}</title>\n",
+        escape_xml(&input.title)
+    ));
+    xml.push_str(&format!("    <link>{}</link>\n", escape_xml(base_url)));
+    xml.push_str(&format!(
+        "    <description>{}</description>\n",
+        escape_xml(&input.title)
+    ));
+    if let Some(updated) = entries.iter().map(|entry| entry.updated).max() {
+        xml.push_str(&format!(
+            "    <lastBuildDate>{}</lastBuildDate>\n",
+            format_rfc822(updated)
+        ));
+    }
+    for entry in entries {
+        xml.push_str("    <item>\n");
+        xml.push_str(&format!(
+            "      <guid isPermaLink=\"false\">{}</guid>\n",
+            post_id(&entry.original_post_hash)
+        ));
+        xml.push_str(&format!(
+            "      <title>{}</title>\n",
+            escape_xml(&entry.post.name)
+        ));
+        xml.push_str(&format!(
+            "      <link>{}</link>\n",
+            escape_xml(&post_url(base_url, &entry.original_post_hash))
+        ));
+        xml.push_str(&format!(
+            "      <pubDate>{}</pubDate>\n",
+            format_rfc822(entry.published)
+        ));
+        xml.push_str(&format!(
+            "      <description>{}</description>\n",
+            escape_xml(&render_content_html(entry.post.format, &entry.post.content))
+        ));
+        xml.push_str("    </item>\n");
+    }
+    xml.push_str("  </channel>\n");
+    xml.push_str("</rss>\n");
+    xml
+}
+
+/// Renders the latest revisions of the newest posts of a source as an Atom 1.0 or RSS 2.0 document
+#[hdk_extern]
+pub fn render_feed(input: FeedInput) -> ExternResult<String> {
+    let entries = get_feed_entries(&input.source, input.limit)?;
+    Ok(match input.format {
+        FeedFormat::Atom => render_atom(&input, &entries),
+        FeedFormat::Rss => render_rss(&input, &entries),
+    })
+}
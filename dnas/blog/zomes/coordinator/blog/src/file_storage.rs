@@ -0,0 +1,87 @@
+use blog_integrity::*;
+use hdk::prelude::*;
+
+#[hdk_extern]
+pub fn create_file_chunk(file_chunk: FileChunk) -> ExternResult<EntryHash> {
+    create_entry(&EntryTypes::FileChunk(file_chunk.clone()))?;
+    hash_entry(&file_chunk)
+}
+
+#[hdk_extern]
+pub fn create_file_manifest(file_manifest: FileManifest) -> ExternResult<EntryHash> {
+    create_entry(&EntryTypes::FileManifest(file_manifest.clone()))?;
+    hash_entry(&file_manifest)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UploadFileInput {
+    pub name: String,
+    pub mime_type: String,
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
+// Splits the file into chunks and commits them along with their manifest,
+// returning the manifest hash to reference from the attachments of a post
+#[hdk_extern]
+pub fn upload_file(input: UploadFileInput) -> ExternResult<EntryHash> {
+    let chunks = input
+        .data
+        .chunks(FILE_CHUNK_SIZE)
+        .map(|data| {
+            create_file_chunk(FileChunk {
+                data: data.to_vec(),
+            })
+        })
+        .collect::<ExternResult<Vec<EntryHash>>>()?;
+    create_file_manifest(FileManifest {
+        name: input.name,
+        mime_type: input.mime_type,
+        size: input.data.len(),
+        sha256: hash_sha256(input.data)?,
+        chunks,
+    })
+}
+
+#[hdk_extern]
+pub fn get_file_manifest(file_manifest_hash: EntryHash) -> ExternResult<Option<FileManifest>> {
+    let Some(record) = get(file_manifest_hash, GetOptions::default())? else {
+        return Ok(None);
+    };
+    Ok(Some(FileManifest::try_from(&record)?))
+}
+
+#[hdk_extern]
+pub fn get_file_chunk(file_chunk_hash: EntryHash) -> ExternResult<Option<FileChunk>> {
+    let Some(record) = get(file_chunk_hash.clone(), GetOptions::default())? else {
+        return Ok(None);
+    };
+    let file_chunk = FileChunk::try_from(&record)?;
+    if hash_entry(&file_chunk)? != file_chunk_hash {
+        return Err(BlogError::Invalid("File Chunk does not match its hash".to_string()).into());
+    }
+    Ok(Some(file_chunk))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DownloadedFile {
+    pub manifest: FileManifest,
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
+#[hdk_extern]
+pub fn download_file(file_manifest_hash: EntryHash) -> ExternResult<DownloadedFile> {
+    let manifest = get_file_manifest(file_manifest_hash)?.ok_or(BlogError::FileManifestNotFound)?;
+    let mut data = Vec::with_capacity(manifest.size);
+    for chunk_hash in &manifest.chunks {
+        let file_chunk = get_file_chunk(chunk_hash.clone())?.ok_or(BlogError::FileChunkNotFound)?;
+        data.extend(file_chunk.data);
+    }
+    if data.len() != manifest.size || hash_sha256(data.clone())? != manifest.sha256 {
+        return Err(
+            BlogError::Invalid("Downloaded file does not match its manifest".to_string()).into(),
+        );
+    }
+    Ok(DownloadedFile { manifest, data })
+}
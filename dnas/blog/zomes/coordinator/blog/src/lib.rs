@@ -1,6 +1,22 @@
 pub mod all_posts;
+pub mod backup;
+pub mod co_authored_post;
 pub mod comment;
+pub mod comment_lock;
+pub mod counts;
+pub mod delegation;
+pub mod diff;
+pub mod feed;
+pub mod file_storage;
+pub mod migration;
+pub mod pinned_posts;
 pub mod post;
+pub mod post_summary;
+pub mod private_post;
+pub mod render;
+pub mod revision_tags;
+pub mod series;
+pub mod visibility;
 use blog_integrity::*;
 use hdk::prelude::*;
 
@@ -11,6 +27,7 @@ pub fn init() -> ExternResult<InitCallbackResult> {
 }
 
 // Don't modify this enum if you want the scaffolding tool to generate appropriate signals for your entries and links
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "type")]
 pub enum Signal {
@@ -61,11 +78,8 @@ fn signal_action(action: SignedActionHashed) -> ExternResult<()> {
             Ok(())
         }
         Action::DeleteLink(delete_link) => {
-            let record = get(delete_link.link_add_address.clone(), GetOptions::default())?.ok_or(
-                wasm_error!(WasmErrorInner::Guest(
-                    "Failed to fetch CreateLink action".to_string()
-                )),
-            )?;
+            let record = get(delete_link.link_add_address.clone(), GetOptions::default())?
+                .ok_or(BlogError::LinkNotFound)?;
             match record.action() {
                 Action::CreateLink(create_link) => {
                     if let Ok(Some(link_type)) =
@@ -79,9 +93,7 @@ fn signal_action(action: SignedActionHashed) -> ExternResult<()> {
                     }
                     Ok(())
                 }
-                _ => Err(wasm_error!(WasmErrorInner::Guest(
-                    "Create Link should exist".to_string()
-                ))),
+                _ => Err(BlogError::LinkNotFound.into()),
             }
         }
         Action::Create(_create) => {
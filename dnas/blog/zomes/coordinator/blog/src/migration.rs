@@ -0,0 +1,267 @@
+use blog_integrity::*;
+use hdk::prelude::*;
+
+use crate::comment::{create_comment, get_all_deletes_for_comment, get_latest_comment};
+use crate::post::{create_post, get_all_deletes_for_post, get_latest_post};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportedRecord {
+    pub original_hash: ActionHash,
+    // The latest revision, as signed by its author
+    pub revision: Record,
+    // The original action and the revisions that the latest revision descends from, oldest first
+    #[serde(default)]
+    pub lineage: Vec<Action>,
+}
+
+/// The posts and comments of an agent, exported from a DNA to be imported into its successor
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MigrationExport {
+    pub posts: Vec<ExportedRecord>,
+    pub comments: Vec<ExportedRecord>,
+}
+
+/// Returns the records of this agent's source chain that created an entry of the given type
+pub(crate) fn query_created(entry_type: UnitEntryTypes) -> ExternResult<Vec<Record>> {
+    query(
+        ChainQueryFilter::new()
+            .entry_type(entry_type.try_into()?)
+            .action_type(ActionType::Create),
+    )
+}
+
+/// Returns the original action and the revisions that the given revision updates after it, oldest
+/// first, or nothing if the revision is the original
+fn get_lineage(revision: &Record) -> ExternResult<Vec<Action>> {
+    let mut lineage = vec![];
+    let mut action = revision.action().clone();
+    while let Action::Update(update) = action {
+        let updated = get(update.original_action_address, GetOptions::default())?
+            .ok_or(BlogError::RevisionNotFound)?;
+        action = updated.action().clone();
+        lineage.push(action.clone());
+    }
+    lineage.reverse();
+    Ok(lineage)
+}
+
+/// Exports the latest revision of every post and comment that this agent authored and hasn't deleted
+#[hdk_extern]
+pub fn export_for_migration() -> ExternResult<MigrationExport> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let mut export = MigrationExport::default();
+    for record in query_created(UnitEntryTypes::Post)? {
+        let original_hash = record.action_address().clone();
+        if get_all_deletes_for_post(original_hash.clone())?
+            .is_some_and(|deletes| !deletes.is_empty())
+        {
+            continue;
+        }
+        let Some(revision) = get_latest_post(original_hash.clone())? else {
+            continue;
+        };
+        // Co-authors also commit the posts they countersigned, which are exported by their author
+        if Post::try_from(&revision)?.author != me {
+            continue;
+        }
+        export.posts.push(ExportedRecord {
+            original_hash,
+            lineage: get_lineage(&revision)?,
+            revision,
+        });
+    }
+    for record in query_created(UnitEntryTypes::Comment)? {
+        let original_hash = record.action_address().clone();
+        if get_all_deletes_for_comment(original_hash.clone())?
+            .is_some_and(|deletes| !deletes.is_empty())
+        {
+            continue;
+        }
+        let Some(revision) = get_latest_comment(original_hash.clone())? else {
+            continue;
+        };
+        export.comments.push(ExportedRecord {
+            original_hash,
+            lineage: get_lineage(&revision)?,
+            revision,
+        });
+    }
+    Ok(export)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImportedRecord {
+    pub original_hash: ActionHash,
+    pub imported_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MigrationReport {
+    pub imported: Vec<ImportedRecord>,
+    // Records that can't be re-created in this DNA: co-authored, private or with attachments
+    // posts, comments on posts that haven't been imported, and records that were already imported
+    pub skipped: Vec<ActionHash>,
+}
+
+fn record_provenance(exported: &ExportedRecord, imported_hash: ActionHash) -> ExternResult<()> {
+    let revision_entry = exported
+        .revision
+        .entry()
+        .as_option()
+        .cloned()
+        .ok_or(BlogError::MissingEntry)?;
+    let provenance_hash = create_entry(&EntryTypes::Provenance(Provenance {
+        original_hash: exported.original_hash.clone(),
+        revision: exported.revision.signed_action.clone(),
+        revision_entry,
+        lineage: exported.lineage.clone(),
+        imported_hash: imported_hash.clone(),
+    }))?;
+    create_link(
+        exported.original_hash.clone(),
+        provenance_hash.clone(),
+        LinkTypes::OriginalToProvenance,
+        (),
+    )?;
+    create_link(
+        imported_hash,
+        provenance_hash,
+        LinkTypes::ImportedToProvenance,
+        (),
+    )?;
+    Ok(())
+}
+
+/// Returns the author of the action that created the original of the revision in a provenance
+fn original_author(provenance: &Provenance) -> &AgentPubKey {
+    provenance
+        .lineage
+        .first()
+        .unwrap_or(provenance.revision.action())
+        .author()
+}
+
+/// Returns the action that re-created the given post or comment of a previous DNA in this one,
+/// only following the provenances imported by the author of the original
+#[hdk_extern]
+pub fn get_imported_hash(original_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+    let mut links = get_links(
+        GetLinksInputBuilder::try_new(original_hash, LinkTypes::OriginalToProvenance)?.build(),
+    )?;
+    links.sort_by_key(|link| link.timestamp);
+    for link in links {
+        let provenance_hash = link
+            .target
+            .into_action_hash()
+            .ok_or(BlogError::MalformedLink)?;
+        let Some(record) = get(provenance_hash, GetOptions::default())? else {
+            continue;
+        };
+        let provenance = Provenance::try_from(&record)?;
+        if original_author(&provenance) == &link.author {
+            return Ok(Some(provenance.imported_hash));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns the provenance of a post or comment that was imported from a previous DNA
+#[hdk_extern]
+pub fn get_provenance(imported_hash: ActionHash) -> ExternResult<Option<Record>> {
+    let links = get_links(
+        GetLinksInputBuilder::try_new(imported_hash, LinkTypes::ImportedToProvenance)?.build(),
+    )?;
+    let Some(link) = links.into_iter().min_by_key(|link| link.timestamp) else {
+        return Ok(None);
+    };
+    let provenance_hash = link
+        .target
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    get(provenance_hash, GetOptions::default())
+}
+
+/// Re-creates the exported posts and then the exported comments in this DNA,
+/// recording the provenance of each of them
+#[hdk_extern]
+pub fn import_for_migration(export: MigrationExport) -> ExternResult<MigrationReport> {
+    let mut report = MigrationReport::default();
+    for exported in export.posts {
+        let post = Post::try_from(&exported.revision)?;
+        let importable = post.co_authors.is_empty()
+            && post.envelope.is_none()
+            && post.attachments.is_empty()
+            && get_imported_hash(exported.original_hash.clone())?.is_none();
+        if !importable {
+            report.skipped.push(exported.original_hash);
+            continue;
+        }
+        let record = create_post(Post {
+            reverted_from: None,
+            edit: None,
+            ..post
+        })?;
+        let imported_hash = record.action_address().clone();
+        record_provenance(&exported, imported_hash.clone())?;
+        report.imported.push(ImportedRecord {
+            original_hash: exported.original_hash,
+            imported_hash,
+        });
+    }
+    for exported in export.comments {
+        let comment = Comment::try_from(&exported.revision)?;
+        let imported_post_hash = get_imported_hash(comment.post_hash.clone())?;
+        let (Some(post_hash), None) = (
+            imported_post_hash,
+            get_imported_hash(exported.original_hash.clone())?,
+        ) else {
+            report.skipped.push(exported.original_hash);
+            continue;
+        };
+        let record = create_comment(Comment {
+            post_hash,
+            reverted_from: None,
+            edit: None,
+            comment_lock_hash: None,
+            ..comment
+        })?;
+        let imported_hash = record.action_address().clone();
+        record_provenance(&exported, imported_hash.clone())?;
+        report.imported.push(ImportedRecord {
+            original_hash: exported.original_hash,
+            imported_hash,
+        });
+    }
+    Ok(report)
+}
+
+/// Exports this agent's posts and comments from a cell of a previous DNA and imports them into this one
+#[hdk_extern]
+pub fn migrate_from_cell(previous_cell_id: CellId) -> ExternResult<MigrationReport> {
+    let response = call(
+        CallTargetCell::OtherCell(previous_cell_id),
+        zome_info()?.name,
+        "export_for_migration".into(),
+        None,
+        (),
+    )?;
+    let export: MigrationExport = match response {
+        ZomeCallResponse::Ok(output) => output.decode().map_err(|e| wasm_error!(e))?,
+        ZomeCallResponse::Unauthorized(..) => {
+            return Err(BlogError::Unauthorized(
+                "Not authorized to export from the previous cell".to_string(),
+            )
+            .into())
+        }
+        ZomeCallResponse::NetworkError(error) => {
+            return Err(BlogError::NetworkError(format!(
+                "Could not export from the previous cell: {error}"
+            ))
+            .into())
+        }
+        ZomeCallResponse::CountersigningSession(error) => {
+            return Err(BlogError::CountersigningFailed(error).into())
+        }
+    };
+    import_for_migration(export)
+}
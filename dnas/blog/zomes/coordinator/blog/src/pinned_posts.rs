@@ -0,0 +1,66 @@
+use blog_integrity::*;
+use hdk::prelude::*;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PinPostInput {
+    pub post_hash: ActionHash,
+    pub expires_at: Option<Timestamp>,
+}
+
+#[hdk_extern]
+pub fn pin_post(input: PinPostInput) -> ExternResult<ActionHash> {
+    let path = Path::from("pinned_posts");
+    let tag = PinTag {
+        expires_at: input.expires_at,
+    };
+    create_link(
+        path.path_entry_hash()?,
+        input.post_hash,
+        LinkTypes::PinnedPosts,
+        LinkTag::new(
+            SerializedBytes::try_from(tag)
+                .map_err(|e| wasm_error!(e))?
+                .bytes()
+                .clone(),
+        ),
+    )
+}
+
+#[hdk_extern]
+pub fn unpin_post(post_hash: ActionHash) -> ExternResult<()> {
+    let path = Path::from("pinned_posts");
+    let links = get_links(
+        GetLinksInputBuilder::try_new(path.path_entry_hash()?, LinkTypes::PinnedPosts)?.build(),
+    )?;
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if hash == post_hash {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Returns the links to the posts that are currently pinned, most recently pinned first
+#[hdk_extern]
+pub fn get_pinned_posts() -> ExternResult<Vec<Link>> {
+    let path = Path::from("pinned_posts");
+    let links = get_links(
+        GetLinksInputBuilder::try_new(path.path_entry_hash()?, LinkTypes::PinnedPosts)?.build(),
+    )?;
+    let now = sys_time()?;
+    let mut pinned_links: Vec<Link> = links
+        .into_iter()
+        .filter(|link| {
+            let Ok(tag) = PinTag::try_from(SerializedBytes::from(UnsafeBytes::from(
+                link.tag.clone().into_inner(),
+            ))) else {
+                return false;
+            };
+            !matches!(tag.expires_at, Some(expires_at) if expires_at <= now)
+        })
+        .collect();
+    pinned_links.sort_by_key(|link| std::cmp::Reverse(link.timestamp));
+    Ok(pinned_links)
+}
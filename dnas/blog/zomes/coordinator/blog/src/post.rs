@@ -1,19 +1,31 @@
 use blog_integrity::*;
 use hdk::prelude::*;
 
+use crate::diff::*;
+use crate::revision_tags::*;
+
 #[hdk_extern]
 pub fn create_post(post: Post) -> ExternResult<Record> {
+    let post = Post {
+        delegate: crate::delegation::get_call_delegate()?,
+        ..post
+    };
     let post_hash = create_entry(&EntryTypes::Post(post.clone()))?;
-    let record = get(post_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
-        WasmErrorInner::Guest("Could not find the newly created Post".to_string())
-    ))?;
+    let record = get(post_hash.clone(), GetOptions::default())?.ok_or(BlogError::PostNotFound)?;
     //create link to all_posts here
+    let tag = encode_link_tag(PostTag::new(&post))?;
     let path = Path::from("all_posts");
     create_link(
         path.path_entry_hash()?,
         post_hash.clone(),
         LinkTypes::AllPosts,
-        (),
+        tag.clone(),
+    )?;
+    create_link(
+        post.author.clone(),
+        post_hash.clone(),
+        LinkTypes::AuthorToPosts,
+        tag,
     )?;
     Ok(record)
 }
@@ -28,14 +40,11 @@ pub fn get_latest_post(original_post_hash: ActionHash) -> ExternResult<Option<Re
         .into_iter()
         .max_by(|link_a, link_b| link_a.timestamp.cmp(&link_b.timestamp));
     let latest_post_hash = match latest_link {
-        Some(link) => {
-            link.target
-                .clone()
-                .into_action_hash()
-                .ok_or(wasm_error!(WasmErrorInner::Guest(
-                    "No action hash associated with link".to_string()
-                )))?
-        }
+        Some(link) => link
+            .target
+            .clone()
+            .into_action_hash()
+            .ok_or(BlogError::MalformedLink)?,
         None => original_post_hash.clone(),
     };
     get(latest_post_hash, GetOptions::default())
@@ -48,9 +57,7 @@ pub fn get_original_post(original_post_hash: ActionHash) -> ExternResult<Option<
     };
     match details {
         Details::Record(details) => Ok(Some(details.record)),
-        _ => Err(wasm_error!(WasmErrorInner::Guest(
-            "Malformed get details response".to_string()
-        ))),
+        _ => Err(BlogError::MalformedDetails.into()),
     }
 }
 
@@ -68,9 +75,7 @@ pub fn get_all_revisions_for_post(original_post_hash: ActionHash) -> ExternResul
             Ok(GetInput::new(
                 link.target
                     .into_action_hash()
-                    .ok_or(wasm_error!(WasmErrorInner::Guest(
-                        "No action hash associated with link".to_string()
-                    )))?
+                    .ok_or(BlogError::MalformedLink)?
                     .into(),
                 GetOptions::default(),
             ))
@@ -87,40 +92,96 @@ pub struct UpdatePostInput {
     pub original_post_hash: ActionHash,
     pub previous_post_hash: ActionHash,
     pub updated_post: Post,
+    #[serde(default)]
+    pub edit_reason: Option<String>,
+    #[serde(default)]
+    pub minor_edit: bool,
 }
 
 #[hdk_extern]
 pub fn update_post(input: UpdatePostInput) -> ExternResult<Record> {
-    let updated_post_hash = update_entry(input.previous_post_hash.clone(), &input.updated_post)?;
+    let updated_post = Post {
+        edit: EditMetadata::new(input.edit_reason, input.minor_edit),
+        delegate: crate::delegation::get_call_delegate()?,
+        ..input.updated_post
+    };
+    let updated_post_hash = update_entry(input.previous_post_hash.clone(), &updated_post)?;
     create_link(
         input.original_post_hash.clone(),
         updated_post_hash.clone(),
         LinkTypes::PostUpdates,
         (),
     )?;
-    let record = get(updated_post_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
-        WasmErrorInner::Guest("Could not find the newly updated Post".to_string())
-    ))?;
+    retag_revision(&input.original_post_hash, &updated_post)?;
+    let record =
+        get(updated_post_hash.clone(), GetOptions::default())?.ok_or(BlogError::PostNotFound)?;
     Ok(record)
 }
 
+// Tags the post with the given revision under each base that this agent listed it under, leaving the
+// listing links, and so the order of the listings, as they are
+fn retag_revision(original_post_hash: &ActionHash, post: &Post) -> ExternResult<()> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let tag = encode_link_tag(PostTag::new(post))?;
+    let mut bases: Vec<(AnyLinkableHash, LinkTypes)> = vec![(
+        Path::from("all_posts").path_entry_hash()?.into(),
+        LinkTypes::AllPosts,
+    )];
+    for author in std::iter::once(&post.author).chain(post.co_authors.iter()) {
+        bases.push((author.clone().into(), LinkTypes::AuthorToPosts));
+    }
+    for (base, link_type) in bases {
+        let links = get_links(GetLinksInputBuilder::try_new(base.clone(), link_type)?.build())?;
+        let is_listed = links.iter().any(|link| {
+            link.author == me
+                && link.target.clone().into_action_hash().as_ref() == Some(original_post_hash)
+        });
+        if is_listed {
+            retag(
+                base,
+                LinkTypes::PostRevisionTags,
+                original_post_hash,
+                tag.clone(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevertPostInput {
+    pub original_post_hash: ActionHash,
+    pub revision_hash: ActionHash,
+    #[serde(default)]
+    pub edit_reason: Option<String>,
+}
+
+#[hdk_extern]
+pub fn revert_post(input: RevertPostInput) -> ExternResult<Record> {
+    let revision_post = get_post_revision(input.revision_hash.clone())?;
+    let latest_record =
+        get_latest_post(input.original_post_hash.clone())?.ok_or(BlogError::PostNotFound)?;
+    update_post(UpdatePostInput {
+        original_post_hash: input.original_post_hash,
+        previous_post_hash: latest_record.action_address().clone(),
+        updated_post: Post {
+            reverted_from: Some(input.revision_hash),
+            ..revision_post
+        },
+        edit_reason: input.edit_reason,
+        minor_edit: false,
+    })
+}
+
 #[hdk_extern]
 pub fn delete_post(original_post_hash: ActionHash) -> ExternResult<ActionHash> {
-    let details = get_details(original_post_hash.clone(), GetOptions::default())?.ok_or(
-        wasm_error!(WasmErrorInner::Guest("Post not found".to_string())),
-    )?;
+    let details = get_details(original_post_hash.clone(), GetOptions::default())?
+        .ok_or(BlogError::PostNotFound)?;
     let record = match details {
         Details::Record(details) => Ok(details.record),
-        _ => Err(wasm_error!(WasmErrorInner::Guest(
-            "Malformed get details response".to_string()
-        ))),
+        _ => Err(BlogError::MalformedDetails),
     }?;
-    let entry = record
-        .entry()
-        .as_option()
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Post record has no entry".to_string()
-        )))?;
+    let entry = record.entry().as_option().ok_or(BlogError::MissingEntry)?;
     let post = <Post>::try_from(entry)?;
     let path = Path::from("all_posts");
     let links = get_links(
@@ -133,6 +194,26 @@ pub fn delete_post(original_post_hash: ActionHash) -> ExternResult<ActionHash> {
             }
         }
     }
+    let links = get_links(
+        GetLinksInputBuilder::try_new(post.author.clone(), LinkTypes::AuthorToPosts)?.build(),
+    )?;
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if hash == original_post_hash {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+    let links = get_links(
+        GetLinksInputBuilder::try_new(post.author, LinkTypes::AuthorToUnlistedPosts)?.build(),
+    )?;
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if hash == original_post_hash {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
     delete_entry(original_post_hash)
 }
 
@@ -144,9 +225,7 @@ pub fn get_all_deletes_for_post(
         return Ok(None);
     };
     match details {
-        Details::Entry(_) => Err(wasm_error!(WasmErrorInner::Guest(
-            "Malformed details".into()
-        ))),
+        Details::Entry(_) => Err(BlogError::MalformedDetails.into()),
         Details::Record(record_details) => Ok(Some(record_details.deletes)),
     }
 }
@@ -172,6 +251,60 @@ pub fn get_posts_for_author(author: AgentPubKey) -> ExternResult<Vec<Link>> {
     get_links(GetLinksInputBuilder::try_new(author, LinkTypes::AuthorToPosts)?.build())
 }
 
+/// A listed post, described by the tag of the link that lists it
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PostListing {
+    pub post_hash: ActionHash,
+    // When the post was listed
+    pub timestamp: Timestamp,
+    pub tag: PostTag,
+}
+
+impl PostListing {
+    /// Lists a post from the link that lists it, falling back to `describe` when the tag of the link
+    /// can't be decoded, as with the empty tags of the links written before listing tags
+    pub fn from_link(
+        link: Link,
+        describe: impl FnOnce(&ActionHash) -> ExternResult<Option<PostTag>>,
+    ) -> ExternResult<Option<PostListing>> {
+        let post_hash = link
+            .target
+            .into_action_hash()
+            .ok_or(BlogError::MalformedLink)?;
+        let tag = match decode_link_tag(link.tag) {
+            Ok(tag) => tag,
+            Err(_) => match describe(&post_hash)? {
+                Some(tag) => tag,
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(PostListing {
+            post_hash,
+            timestamp: link.timestamp,
+            tag,
+        }))
+    }
+}
+
+/// Describes a post from its latest revision, for the links whose tag doesn't describe it
+pub(crate) fn describe_post(post_hash: &ActionHash) -> ExternResult<Option<PostTag>> {
+    let Some(record) = get_latest_post(post_hash.clone())? else {
+        return Ok(None);
+    };
+    Ok(Post::try_from(&record).ok().map(|post| PostTag::new(&post)))
+}
+
+// Lists the posts of an author without getting any of them
+#[hdk_extern]
+pub fn get_post_listings_for_author(author: AgentPubKey) -> ExternResult<Vec<PostListing>> {
+    let links = get_posts_for_author(author.clone())?;
+    with_revision_tags(author, LinkTypes::PostRevisionTags, links)?
+        .into_iter()
+        .map(|link| PostListing::from_link(link, describe_post))
+        .filter_map(Result::transpose)
+        .collect()
+}
+
 #[hdk_extern]
 pub fn get_deleted_posts_for_author(
     author: AgentPubKey,
@@ -188,3 +321,207 @@ pub fn get_deleted_posts_for_author(
         .filter(|(_link, deletes)| !deletes.is_empty())
         .collect())
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PostDiff {
+    pub name: Vec<DiffChunk>,
+    pub content: Vec<DiffChunk>,
+}
+
+fn get_post_revision(revision_hash: ActionHash) -> ExternResult<Post> {
+    let record = get(revision_hash, GetOptions::default())?.ok_or(BlogError::RevisionNotFound)?;
+    Post::try_from(&record)
+}
+
+#[hdk_extern]
+pub fn diff_post_revisions(input: DiffRevisionsInput) -> ExternResult<PostDiff> {
+    let old_post = get_post_revision(input.old_revision_hash)?;
+    let new_post = get_post_revision(input.new_revision_hash)?;
+    Ok(PostDiff {
+        name: diff_text(&old_post.name, &new_post.name, input.granularity),
+        content: diff_text(&old_post.content, &new_post.content, input.granularity),
+    })
+}
+
+#[hdk_extern]
+pub fn get_edit_history_for_post(
+    original_post_hash: ActionHash,
+) -> ExternResult<Vec<RevisionSummary>> {
+    let mut revisions = get_all_revisions_for_post(original_post_hash)?;
+    revisions.sort_by_key(|record| record.action().timestamp());
+    let mut history = vec![];
+    let mut previous_post: Option<Post> = None;
+    for record in revisions {
+        let post = Post::try_from(&record)?;
+        let (name_changed, change_size) = match &previous_post {
+            Some(previous) => (
+                previous.name != post.name,
+                change_size(&previous.content, &post.content),
+            ),
+            None => (false, change_size("", &post.content)),
+        };
+        history.push(RevisionSummary {
+            revision_hash: record.action_address().clone(),
+            author: record.action().author().clone(),
+            timestamp: record.action().timestamp(),
+            name_changed,
+            change_size,
+            edit: post.edit.clone(),
+        });
+        previous_post = Some(post);
+    }
+    Ok(history)
+}
+
+#[hdk_extern]
+pub fn restore_post(original_post_hash: ActionHash) -> ExternResult<Record> {
+    let delete =
+        get_oldest_delete_for_post(original_post_hash.clone())?.ok_or(BlogError::PostNotDeleted)?;
+    let grace_period = DnaProperties::get()?.restore_grace_period();
+    if is_restore_grace_period_expired(delete.action().timestamp(), sys_time()?, grace_period) {
+        return Err(BlogError::RestoreGracePeriodExpired.into());
+    }
+    if get_restored_post(original_post_hash.clone())?.is_some() {
+        return Err(BlogError::PostAlreadyRestored.into());
+    }
+    let latest_record =
+        get_latest_post(original_post_hash.clone())?.ok_or(BlogError::PostNotFound)?;
+    let latest_post = Post::try_from(&latest_record)?;
+    // A plain copy can't be created for a post that all of its authors must countersign
+    if !latest_post.co_authors.is_empty() {
+        return Err(BlogError::Invalid("A co-authored Post cannot be restored".to_string()).into());
+    }
+    let record = create_post(Post {
+        reverted_from: None,
+        edit: None,
+        ..latest_post
+    })?;
+    let tag = RestorationTag {
+        delete_hash: delete.hashed.hash,
+    };
+    create_link(
+        original_post_hash,
+        record.action_address().clone(),
+        LinkTypes::PostRestorations,
+        LinkTag::new(
+            SerializedBytes::try_from(tag)
+                .map_err(|e| wasm_error!(e))?
+                .bytes()
+                .clone(),
+        ),
+    )?;
+    Ok(record)
+}
+
+#[hdk_extern]
+pub fn get_restored_post(original_post_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+    let links = get_links(
+        GetLinksInputBuilder::try_new(original_post_hash, LinkTypes::PostRestorations)?.build(),
+    )?;
+    Ok(links
+        .into_iter()
+        .min_by_key(|link| link.timestamp)
+        .and_then(|link| link.target.into_action_hash()))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecentlyDeletedPost {
+    pub original_post_hash: ActionHash,
+    pub deleted_at: Timestamp,
+    pub restorable_until: Timestamp,
+}
+
+#[hdk_extern]
+pub fn get_recently_deleted_posts_for_author(
+    author: AgentPubKey,
+) -> ExternResult<Vec<RecentlyDeletedPost>> {
+    let grace_period = DnaProperties::get()?.restore_grace_period();
+    let now = sys_time()?;
+    let mut recently_deleted = vec![];
+    for (create_link, deletes) in get_deleted_posts_for_author(author)? {
+        let Action::CreateLink(create_link) = create_link.action() else {
+            continue;
+        };
+        let Some(original_post_hash) = create_link.target_address.clone().into_action_hash() else {
+            continue;
+        };
+        let Some(deleted_at) = deletes
+            .iter()
+            .map(|delete| delete.action().timestamp())
+            .min()
+        else {
+            continue;
+        };
+        if is_restore_grace_period_expired(deleted_at, now, grace_period)
+            || get_restored_post(original_post_hash.clone())?.is_some()
+        {
+            continue;
+        }
+        recently_deleted.push(RecentlyDeletedPost {
+            original_post_hash,
+            deleted_at,
+            restorable_until: Timestamp::from_micros(
+                deleted_at
+                    .as_micros()
+                    .saturating_add(grace_period.saturating_mul(1_000_000)),
+            ),
+        });
+    }
+    Ok(recently_deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing_link(tag: LinkTag) -> Link {
+        Link {
+            author: AgentPubKey::from_raw_36(vec![1; 36]),
+            base: AnyLinkableHash::from(AgentPubKey::from_raw_36(vec![1; 36])),
+            target: AnyLinkableHash::from(ActionHash::from_raw_36(vec![2; 36])),
+            timestamp: Timestamp::from_micros(1_000_000),
+            zome_index: 0.into(),
+            link_type: 0.into(),
+            tag,
+            create_link_hash: ActionHash::from_raw_36(vec![3; 36]),
+        }
+    }
+
+    fn tag(name: &str) -> PostTag {
+        PostTag::new(&Post {
+            name: name.to_string(),
+            content: "Content".to_string(),
+            author: AgentPubKey::from_raw_36(vec![1; 36]),
+            co_authors: vec![],
+            delegate: None,
+            envelope: None,
+            format: ContentFormat::Plain,
+            attachments: vec![],
+            reverted_from: None,
+            edit: None,
+        })
+    }
+
+    #[test]
+    fn a_tagged_link_is_listed_from_its_tag() {
+        let link = listing_link(encode_link_tag(tag("Tagged")).unwrap());
+        let listing = PostListing::from_link(link, |_| panic!("The tag describes the post"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(listing.tag, tag("Tagged"));
+    }
+
+    #[test]
+    fn a_legacy_link_is_listed_from_its_post() {
+        let listing = PostListing::from_link(listing_link(().into()), |post_hash| {
+            assert_eq!(post_hash, &ActionHash::from_raw_36(vec![2; 36]));
+            Ok(Some(tag("Fetched")))
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(listing.tag, tag("Fetched"));
+
+        let missing = PostListing::from_link(listing_link(LinkTag::new(vec![0xc1])), |_| Ok(None));
+        assert!(missing.unwrap().is_none());
+    }
+}
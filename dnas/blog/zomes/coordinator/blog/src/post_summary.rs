@@ -0,0 +1,80 @@
+use blog_integrity::*;
+use hdk::prelude::*;
+
+use crate::comment::count_comments_for_post;
+use crate::counts::LinkCount;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PostSummary {
+    pub original_post_hash: ActionHash,
+    pub latest_record: Record,
+    // There is no profiles zome in this DNA, so the author is identified by their public key
+    pub author: AgentPubKey,
+    pub comment_count: LinkCount,
+    pub edited: bool,
+    pub deleted: bool,
+}
+
+// Hydrates every post needed by a list view with a fixed number of batched host calls, plus one
+// `count_links` per post for its comments since the host can't count links in batches,
+// returning `None` for the hashes that could not be found
+#[hdk_extern]
+pub fn get_post_summaries(
+    original_post_hashes: Vec<ActionHash>,
+) -> ExternResult<Vec<Option<PostSummary>>> {
+    let get_details_input: Vec<GetInput> = original_post_hashes
+        .iter()
+        .map(|hash| GetInput::new(hash.clone().into(), GetOptions::default()))
+        .collect();
+    let details = HDK.with(|hdk| hdk.borrow().get_details(get_details_input))?;
+
+    let get_links_input: Vec<GetLinksInput> = original_post_hashes
+        .iter()
+        .map(|hash| {
+            Ok(GetLinksInputBuilder::try_new(hash.clone(), LinkTypes::PostUpdates)?.build())
+        })
+        .collect::<ExternResult<Vec<GetLinksInput>>>()?;
+    let updates = HDK.with(|hdk| hdk.borrow().get_links(get_links_input))?;
+
+    let latest_hashes: Vec<Option<ActionHash>> = updates
+        .into_iter()
+        .map(|links| {
+            links
+                .into_iter()
+                .max_by_key(|link| link.timestamp)
+                .and_then(|link| link.target.into_action_hash())
+        })
+        .collect();
+    let get_input: Vec<GetInput> = latest_hashes
+        .iter()
+        .flatten()
+        .map(|hash| GetInput::new(hash.clone().into(), GetOptions::default()))
+        .collect();
+    let mut latest_records = HDK.with(|hdk| hdk.borrow().get(get_input))?.into_iter();
+
+    let mut summaries = vec![];
+    for ((original_post_hash, details), latest_hash) in original_post_hashes
+        .into_iter()
+        .zip(details)
+        .zip(latest_hashes)
+    {
+        let edited = latest_hash.is_some();
+        let latest_record = match latest_hash {
+            Some(_) => latest_records.next().flatten(),
+            None => None,
+        };
+        let Some(Details::Record(details)) = details else {
+            summaries.push(None);
+            continue;
+        };
+        summaries.push(Some(PostSummary {
+            author: details.record.action().author().clone(),
+            comment_count: count_comments_for_post(original_post_hash.clone())?,
+            edited,
+            deleted: !details.deletes.is_empty(),
+            latest_record: latest_record.unwrap_or(details.record),
+            original_post_hash,
+        }));
+    }
+    Ok(summaries)
+}
@@ -0,0 +1,131 @@
+use blog_integrity::*;
+use hdk::prelude::*;
+
+/// Creates a new X25519 key pair in the keystore of this agent and publishes its public key
+#[hdk_extern]
+pub fn publish_encryption_key() -> ExternResult<Record> {
+    let encryption_key = EncryptionKey {
+        key: create_x25519_keypair()?,
+    };
+    let encryption_key_hash = create_entry(&EntryTypes::EncryptionKey(encryption_key))?;
+    create_link(
+        agent_info()?.agent_initial_pubkey,
+        encryption_key_hash.clone(),
+        LinkTypes::AgentToEncryptionKeys,
+        (),
+    )?;
+    let record =
+        get(encryption_key_hash, GetOptions::default())?.ok_or(BlogError::EncryptionKeyNotFound)?;
+    Ok(record)
+}
+
+/// Returns the most recently published Encryption Key of the agent
+#[hdk_extern]
+pub fn get_encryption_key(agent: AgentPubKey) -> ExternResult<Option<Record>> {
+    let links =
+        get_links(GetLinksInputBuilder::try_new(agent, LinkTypes::AgentToEncryptionKeys)?.build())?;
+    let Some(latest_link) = links.into_iter().max_by_key(|link| link.timestamp) else {
+        return Ok(None);
+    };
+    let Some(action_hash) = latest_link.target.into_action_hash() else {
+        return Ok(None);
+    };
+    get(action_hash, GetOptions::default())
+}
+
+fn must_get_encryption_key(agent: AgentPubKey) -> ExternResult<(ActionHash, X25519PubKey)> {
+    // The agent hasn't published an Encryption Key
+    let record = get_encryption_key(agent)?.ok_or(BlogError::EncryptionKeyNotFound)?;
+    let encryption_key = EncryptionKey::try_from(&record)?;
+    Ok((record.action_address().clone(), encryption_key.key))
+}
+
+fn get_key(key_hash: ActionHash) -> ExternResult<X25519PubKey> {
+    let record = get(key_hash, GetOptions::default())?.ok_or(BlogError::EncryptionKeyNotFound)?;
+    Ok(EncryptionKey::try_from(&record)?.key)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreatePrivatePostInput {
+    pub name: String,
+    pub content: String,
+    #[serde(default)]
+    pub format: ContentFormat,
+    // The author is always added to the recipients
+    pub recipients: Vec<AgentPubKey>,
+}
+
+/// Creates a post whose content is encrypted with a new symmetric key, wrapped for every recipient
+#[hdk_extern]
+pub fn create_private_post(input: CreatePrivatePostInput) -> ExternResult<Record> {
+    let author = agent_info()?.agent_initial_pubkey;
+    check_content_format(input.format, &input.content)?;
+    let (sender_key_hash, sender_key) = must_get_encryption_key(author.clone())?;
+    let key_ref = x_salsa20_poly1305_shared_secret_create_random(None)?;
+    let encrypted_content =
+        x_salsa20_poly1305_encrypt(key_ref.clone(), input.content.into_bytes().into())?;
+
+    let mut recipients = vec![author.clone()];
+    for recipient in input.recipients {
+        if !recipients.contains(&recipient) {
+            recipients.push(recipient);
+        }
+    }
+    let mut wrapped_keys = vec![];
+    for recipient in recipients {
+        let (recipient_key_hash, recipient_key) = must_get_encryption_key(recipient.clone())?;
+        let encrypted_key =
+            x_salsa20_poly1305_shared_secret_export(sender_key, recipient_key, key_ref.clone())?;
+        wrapped_keys.push(WrappedKey {
+            recipient,
+            recipient_key_hash,
+            encrypted_key,
+        });
+    }
+
+    crate::post::create_post(Post {
+        name: input.name,
+        content: String::new(),
+        author,
+        co_authors: vec![],
+        delegate: None,
+        envelope: Some(PostEnvelope {
+            sender_key_hash,
+            encrypted_content,
+            recipients: wrapped_keys,
+        }),
+        format: input.format,
+        attachments: vec![],
+        reverted_from: None,
+        edit: None,
+    })
+}
+
+/// Decrypts the content of a private post revision, failing if this agent isn't one of its recipients
+#[hdk_extern]
+pub fn decrypt_post(post_hash: ActionHash) -> ExternResult<String> {
+    let record = get(post_hash, GetOptions::default())?.ok_or(BlogError::PostNotFound)?;
+    let post = Post::try_from(&record)?;
+    let Some(envelope) = post.envelope else {
+        return Ok(post.content);
+    };
+    let me = agent_info()?.agent_initial_pubkey;
+    let wrapped_key = envelope
+        .recipients
+        .into_iter()
+        .find(|wrapped_key| wrapped_key.recipient == me)
+        .ok_or(BlogError::Unauthorized(
+            "You are not a recipient of this Post".to_string(),
+        ))?;
+    let key_ref = x_salsa20_poly1305_shared_secret_ingest(
+        get_key(wrapped_key.recipient_key_hash)?,
+        get_key(envelope.sender_key_hash)?,
+        wrapped_key.encrypted_key,
+        None,
+    )?;
+    let content = x_salsa20_poly1305_decrypt(key_ref, envelope.encrypted_content)?.ok_or(
+        BlogError::Invalid("Could not decrypt the content of the Post".to_string()),
+    )?;
+    String::from_utf8(content.as_ref().to_vec())
+        .map_err(|e| BlogError::Invalid(e.to_string()).into())
+}
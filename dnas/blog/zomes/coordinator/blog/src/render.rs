@@ -0,0 +1,84 @@
+use blog_integrity::*;
+use hdk::prelude::*;
+use pulldown_cmark::{html, CowStr, Event, Parser, Tag};
+
+use crate::post::get_latest_post;
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn sanitize_url(url: CowStr) -> CowStr {
+    match is_unsafe_url(&url) {
+        true => CowStr::Borrowed("#"),
+        false => url,
+    }
+}
+
+/// Renders the content of a post to HTML, escaping any raw HTML and neutralizing script URLs
+/// even though validation already rejects them, so that every client renders identically
+pub fn render_content_html(format: ContentFormat, content: &str) -> String {
+    match format {
+        ContentFormat::Plain => content
+            .split("\n\n")
+            .filter(|paragraph| !paragraph.trim().is_empty())
+            .map(|paragraph| {
+                format!(
+                    "<p>{}</p>\n",
+                    escape_html(paragraph).replace('\n', "<br />")
+                )
+            })
+            .collect(),
+        ContentFormat::Markdown => {
+            let parser = Parser::new_ext(content, MARKDOWN_OPTIONS).map(|event| match event {
+                Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+                Event::Start(Tag::Link {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }) => Event::Start(Tag::Link {
+                    link_type,
+                    dest_url: sanitize_url(dest_url),
+                    title,
+                    id,
+                }),
+                Event::Start(Tag::Image {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }) => Event::Start(Tag::Image {
+                    link_type,
+                    dest_url: sanitize_url(dest_url),
+                    title,
+                    id,
+                }),
+                event => event,
+            });
+            let mut rendered = String::new();
+            html::push_html(&mut rendered, parser);
+            rendered
+        }
+    }
+}
+
+#[hdk_extern]
+pub fn render_post_html(original_post_hash: ActionHash) -> ExternResult<Option<String>> {
+    let Some(record) = get_latest_post(original_post_hash)? else {
+        return Ok(None);
+    };
+    let post = Post::try_from(&record)?;
+    Ok(Some(render_content_html(post.format, &post.content)))
+}
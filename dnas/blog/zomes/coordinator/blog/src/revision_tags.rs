@@ -0,0 +1,50 @@
+use blog_integrity::*;
+use hdk::prelude::*;
+
+/// Tags the target with its latest revision under the base, replacing the tags of its earlier
+/// revisions that this agent wrote there, without touching the links that list the target
+pub fn retag(
+    base: impl Into<AnyLinkableHash>,
+    link_type: LinkTypes,
+    target: &ActionHash,
+    tag: LinkTag,
+) -> ExternResult<()> {
+    let base: AnyLinkableHash = base.into();
+    let me = agent_info()?.agent_initial_pubkey;
+    let links = get_links(GetLinksInputBuilder::try_new(base.clone(), link_type)?.build())?;
+    for link in links {
+        if link.author == me && link.target.clone().into_action_hash().as_ref() == Some(target) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    create_link(base, target.clone(), link_type, tag)?;
+    Ok(())
+}
+
+/// Replaces the tag of each listing link with the tag of the latest revision of its target that
+/// the author of the link tagged under the same base after listing it, so that listings describe
+/// edited entries while keeping the time that they were listed
+pub fn with_revision_tags(
+    base: impl Into<AnyLinkableHash>,
+    link_type: LinkTypes,
+    links: Vec<Link>,
+) -> ExternResult<Vec<Link>> {
+    let mut revision_tags = get_links(GetLinksInputBuilder::try_new(base, link_type)?.build())?;
+    revision_tags.sort_by_key(|link| std::cmp::Reverse(link.timestamp));
+    Ok(links
+        .into_iter()
+        .map(|link| {
+            match revision_tags.iter().find(|revision_tag| {
+                revision_tag.target == link.target
+                    && revision_tag.author == link.author
+                    && revision_tag.timestamp > link.timestamp
+            }) {
+                Some(revision_tag) => Link {
+                    tag: revision_tag.tag.clone(),
+                    ..link
+                },
+                None => link,
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,144 @@
+use blog_integrity::*;
+use hdk::prelude::*;
+
+#[hdk_extern]
+pub fn create_series(series: Series) -> ExternResult<Record> {
+    let series_hash = create_entry(&EntryTypes::Series(series.clone()))?;
+    create_link(
+        series.author.clone(),
+        series_hash.clone(),
+        LinkTypes::AuthorToSeries,
+        (),
+    )?;
+    for post_hash in series.posts {
+        create_link(post_hash, series_hash.clone(), LinkTypes::PostToSeries, ())?;
+    }
+    let record =
+        get(series_hash.clone(), GetOptions::default())?.ok_or(BlogError::SeriesNotFound)?;
+    Ok(record)
+}
+
+#[hdk_extern]
+pub fn get_latest_series(original_series_hash: ActionHash) -> ExternResult<Option<Record>> {
+    let Some(details) = get_details(original_series_hash, GetOptions::default())? else {
+        return Ok(None);
+    };
+    let record_details = match details {
+        Details::Entry(_) => Err(BlogError::MalformedDetails),
+        Details::Record(record_details) => Ok(record_details),
+    }?;
+    match record_details.updates.last() {
+        Some(update) => get_latest_series(update.action_address().clone()),
+        None => Ok(Some(record_details.record)),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateSeriesInput {
+    pub original_series_hash: ActionHash,
+    pub previous_series_hash: ActionHash,
+    pub updated_series: Series,
+}
+
+#[hdk_extern]
+pub fn update_series(input: UpdateSeriesInput) -> ExternResult<Record> {
+    let previous_record = get(input.previous_series_hash.clone(), GetOptions::default())?
+        .ok_or(BlogError::SeriesNotFound)?;
+    let previous_series = Series::try_from(&previous_record)?;
+    let updated_series_hash = update_entry(input.previous_series_hash, &input.updated_series)?;
+
+    // Keep the PostToSeries links in sync with the posts in the series
+    let removed_posts: Vec<&ActionHash> = previous_series
+        .posts
+        .iter()
+        .filter(|post_hash| !input.updated_series.posts.contains(post_hash))
+        .collect();
+    for post_hash in removed_posts {
+        let links = get_links(
+            GetLinksInputBuilder::try_new(post_hash.clone(), LinkTypes::PostToSeries)?.build(),
+        )?;
+        for link in links {
+            if link.target.into_action_hash().as_ref() == Some(&input.original_series_hash) {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+    for post_hash in &input.updated_series.posts {
+        if !previous_series.posts.contains(post_hash) {
+            create_link(
+                post_hash.clone(),
+                input.original_series_hash.clone(),
+                LinkTypes::PostToSeries,
+                (),
+            )?;
+        }
+    }
+
+    let record =
+        get(updated_series_hash, GetOptions::default())?.ok_or(BlogError::SeriesNotFound)?;
+    Ok(record)
+}
+
+#[hdk_extern]
+pub fn delete_series(original_series_hash: ActionHash) -> ExternResult<ActionHash> {
+    let record =
+        get_latest_series(original_series_hash.clone())?.ok_or(BlogError::SeriesNotFound)?;
+    let series = Series::try_from(&record)?;
+    let links = get_links(
+        GetLinksInputBuilder::try_new(series.author, LinkTypes::AuthorToSeries)?.build(),
+    )?;
+    for link in links {
+        if link.target.into_action_hash().as_ref() == Some(&original_series_hash) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    for post_hash in series.posts {
+        let links =
+            get_links(GetLinksInputBuilder::try_new(post_hash, LinkTypes::PostToSeries)?.build())?;
+        for link in links {
+            if link.target.into_action_hash().as_ref() == Some(&original_series_hash) {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+    delete_entry(original_series_hash)
+}
+
+#[hdk_extern]
+pub fn get_series_for_author(author: AgentPubKey) -> ExternResult<Vec<Link>> {
+    get_links(GetLinksInputBuilder::try_new(author, LinkTypes::AuthorToSeries)?.build())
+}
+
+#[hdk_extern]
+pub fn get_series_for_post(post_hash: ActionHash) -> ExternResult<Vec<Link>> {
+    get_links(GetLinksInputBuilder::try_new(post_hash, LinkTypes::PostToSeries)?.build())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AdjacentPostsInput {
+    pub original_series_hash: ActionHash,
+    pub post_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AdjacentPosts {
+    pub previous: Option<ActionHash>,
+    pub next: Option<ActionHash>,
+}
+
+#[hdk_extern]
+pub fn get_adjacent_posts_in_series(input: AdjacentPostsInput) -> ExternResult<AdjacentPosts> {
+    let record = get_latest_series(input.original_series_hash)?.ok_or(BlogError::SeriesNotFound)?;
+    let series = Series::try_from(&record)?;
+    let index = series
+        .posts
+        .iter()
+        .position(|post_hash| post_hash == &input.post_hash)
+        .ok_or(BlogError::PostNotInSeries)?;
+    Ok(AdjacentPosts {
+        previous: index
+            .checked_sub(1)
+            .and_then(|previous| series.posts.get(previous).cloned()),
+        next: series.posts.get(index + 1).cloned(),
+    })
+}
@@ -0,0 +1,73 @@
+use blog_integrity::*;
+use hdk::prelude::*;
+
+use crate::post::get_latest_post;
+
+fn get_post_author(original_post_hash: ActionHash) -> ExternResult<AgentPubKey> {
+    let record = get(original_post_hash, GetOptions::default())?.ok_or(BlogError::PostNotFound)?;
+    Ok(record.action().author().clone())
+}
+
+fn get_unlisted_link(original_post_hash: &ActionHash) -> ExternResult<Option<Link>> {
+    let author = get_post_author(original_post_hash.clone())?;
+    let links = get_links(
+        GetLinksInputBuilder::try_new(author, LinkTypes::AuthorToUnlistedPosts)?.build(),
+    )?;
+    Ok(links
+        .into_iter()
+        .find(|link| link.target.clone().into_action_hash().as_ref() == Some(original_post_hash)))
+}
+
+// Removes the post from the AllPosts index while keeping it fetchable by its hash
+#[hdk_extern]
+pub fn unlist_post(original_post_hash: ActionHash) -> ExternResult<()> {
+    if get_unlisted_link(&original_post_hash)?.is_some() {
+        return Ok(());
+    }
+    let path = Path::from("all_posts");
+    let links = get_links(
+        GetLinksInputBuilder::try_new(path.path_entry_hash()?, LinkTypes::AllPosts)?.build(),
+    )?;
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if hash == original_post_hash {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+    create_link(
+        agent_info()?.agent_initial_pubkey,
+        original_post_hash,
+        LinkTypes::AuthorToUnlistedPosts,
+        (),
+    )?;
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn relist_post(original_post_hash: ActionHash) -> ExternResult<()> {
+    let Some(unlisted_link) = get_unlisted_link(&original_post_hash)? else {
+        return Ok(());
+    };
+    delete_link(unlisted_link.create_link_hash)?;
+    let record = get_latest_post(original_post_hash.clone())?.ok_or(BlogError::PostNotFound)?;
+    let post = Post::try_from(&record)?;
+    let path = Path::from("all_posts");
+    create_link(
+        path.path_entry_hash()?,
+        original_post_hash,
+        LinkTypes::AllPosts,
+        encode_link_tag(PostTag::new(&post))?,
+    )?;
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn is_post_unlisted(original_post_hash: ActionHash) -> ExternResult<bool> {
+    Ok(get_unlisted_link(&original_post_hash)?.is_some())
+}
+
+#[hdk_extern]
+pub fn get_unlisted_posts_for_author(author: AgentPubKey) -> ExternResult<Vec<Link>> {
+    get_links(GetLinksInputBuilder::try_new(author, LinkTypes::AuthorToUnlistedPosts)?.build())
+}
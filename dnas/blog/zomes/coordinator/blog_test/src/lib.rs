@@ -0,0 +1,68 @@
+//! Coordinator that commits entries and links exactly as it is given them, bypassing the checks and
+//! derived fields of the `blog` coordinator.
+//!
+//! It is not part of the DNA manifest: the sweettest suite installs it next to `blog` so that every
+//! validation rule can be tested against the ops that a modified coordinator could author.
+
+use blog_integrity::*;
+use hdk::prelude::*;
+
+#[hdk_extern]
+pub fn create_raw_entry(entry: EntryTypes) -> ExternResult<ActionHash> {
+    create_entry(&entry)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateRawEntryBytesInput {
+    // Any entry of the type that the bytes are committed as
+    pub entry_of_type: EntryTypes,
+    pub bytes: Vec<u8>,
+}
+
+/// Commits bytes that the entry types of `blog_integrity` would never serialize to, e.g. an older
+/// schema version
+#[hdk_extern]
+pub fn create_raw_entry_bytes(input: CreateRawEntryBytesInput) -> ExternResult<ActionHash> {
+    create(CreateInput::new(
+        ScopedEntryDefIndex::try_from(&input.entry_of_type)?,
+        EntryVisibility::Public,
+        Entry::App(AppEntryBytes(SerializedBytes::from(UnsafeBytes::from(
+            input.bytes,
+        )))),
+        ChainTopOrdering::default(),
+    ))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateRawEntryInput {
+    pub original_action_hash: ActionHash,
+    pub entry: EntryTypes,
+}
+
+#[hdk_extern]
+pub fn update_raw_entry(input: UpdateRawEntryInput) -> ExternResult<ActionHash> {
+    update_entry(input.original_action_hash, &input.entry)
+}
+
+#[hdk_extern]
+pub fn delete_raw_entry(action_hash: ActionHash) -> ExternResult<ActionHash> {
+    delete_entry(action_hash)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateRawLinkInput {
+    pub base: AnyLinkableHash,
+    pub target: AnyLinkableHash,
+    pub link_type: LinkTypes,
+    pub tag: Vec<u8>,
+}
+
+#[hdk_extern]
+pub fn create_raw_link(input: CreateRawLinkInput) -> ExternResult<ActionHash> {
+    create_link(input.base, input.target, input.link_type, input.tag)
+}
+
+#[hdk_extern]
+pub fn delete_raw_link(create_link_hash: ActionHash) -> ExternResult<ActionHash> {
+    delete_link(create_link_hash)
+}
@@ -0,0 +1,86 @@
+use hdi::prelude::*;
+
+use crate::{BlogError, Comment, Post, RuleResult};
+
+/// Checks that the post or comment that replays an exported record is linked from the hash it was
+/// exported with by its own author
+pub fn check_exported_to_imported_link(
+    action: &CreateLink,
+    imported_record: &Record,
+) -> RuleResult {
+    if imported_record.action().author() != &action.author {
+        return Err(BlogError::Unauthorized(
+            "Only the importer of a record can link it from its exported hash".to_string(),
+        ));
+    }
+    if Post::try_from(imported_record).is_err() && Comment::try_from(imported_record).is_err() {
+        return Err(BlogError::Invalid(
+            "Only Posts and Comments can be imported from a blog export".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_create_link_exported_to_imported(
+    action: CreateLink,
+    _base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    let imported_record = must_get_valid_record(action_hash)?;
+    Ok(crate::validation_result(check_exported_to_imported_link(
+        &action,
+        &imported_record,
+    )))
+}
+
+pub fn validate_delete_link_exported_to_imported(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(BlogError::Immutable("ExportedToImported links cannot be deleted".to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn only_posts_and_comments_are_imported() {
+        let link = create_link(agent(1), 2_000_000);
+        assert_eq!(
+            check_exported_to_imported_link(&link, &post_record(post(agent(1)), 3)),
+            Ok(())
+        );
+        let comment_record = record(
+            Action::Create(create(agent(1), 3, 1_000_000)),
+            Some(Entry::try_from(comment(agent(1), action_hash(3))).unwrap()),
+        );
+        assert_eq!(
+            check_exported_to_imported_link(&link, &comment_record),
+            Ok(())
+        );
+        let entryless_record = record(Action::Create(create(agent(1), 3, 1_000_000)), None);
+        assert!(check_exported_to_imported_link(&link, &entryless_record).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn imported_records_are_linked_by_their_author(importer in arb_agent(), linker in arb_agent()) {
+            let result = check_exported_to_imported_link(
+                &create_link(linker.clone(), 2_000_000),
+                &post_record(post(importer.clone()), 3),
+            );
+            prop_assert_eq!(result.is_ok(), importer == linker);
+        }
+    }
+}
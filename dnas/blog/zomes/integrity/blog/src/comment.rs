@@ -1,38 +1,169 @@
 use hdi::prelude::*;
-use crate::UnitEntryTypes;
 
+use crate::{BlogError, EditMetadata, RevertedRevision, RuleResult};
+
+// The schema version that every new Comment must be written with
+pub const COMMENT_SCHEMA_VERSION: u32 = 2;
+
+/// The in-memory representation of a comment, whatever schema version it was written with
 #[derive(Clone, PartialEq)]
 #[hdk_entry_helper]
+#[serde(from = "CommentVersions", into = "CommentVersions")]
 pub struct Comment {
     pub content: String,
     pub post_hash: ActionHash,
     pub author: AgentPubKey,
+    // The revision that this update restores, if it was created by reverting the comment
+    pub reverted_from: Option<ActionHash>,
+    // Why and how this revision was edited, only present on updates
+    pub edit: Option<EditMetadata>,
+    // The latest Comment Lock of the post when the comment was made, if its comments were ever locked
+    pub comment_lock_hash: Option<ActionHash>,
+}
+
+/// The unversioned layout that comments were written with before schema versions were introduced
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommentV1 {
+    pub content: String,
+    pub post_hash: ActionHash,
+    pub author: AgentPubKey,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverted_from: Option<ActionHash>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edit: Option<EditMetadata>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment_lock_hash: Option<ActionHash>,
+}
+
+/// The layout of `CommentV1` with an explicit schema version
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommentV2 {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub comment: CommentV1,
+}
+
+/// Every layout that a Comment entry can be stored with, newest first
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum CommentVersions {
+    V2(CommentV2),
+    V1(CommentV1),
+}
+
+impl CommentVersions {
+    pub fn schema_version(&self) -> u32 {
+        match self {
+            CommentVersions::V2(comment) => comment.schema_version,
+            CommentVersions::V1(_) => 1,
+        }
+    }
+}
+
+impl From<CommentVersions> for Comment {
+    fn from(versions: CommentVersions) -> Self {
+        let comment = match versions {
+            CommentVersions::V2(comment) => comment.comment,
+            CommentVersions::V1(comment) => comment,
+        };
+        Comment {
+            content: comment.content,
+            post_hash: comment.post_hash,
+            author: comment.author,
+            reverted_from: comment.reverted_from,
+            edit: comment.edit,
+            comment_lock_hash: comment.comment_lock_hash,
+        }
+    }
+}
+
+impl From<Comment> for CommentVersions {
+    fn from(comment: Comment) -> Self {
+        CommentVersions::V2(CommentV2 {
+            schema_version: COMMENT_SCHEMA_VERSION,
+            comment: CommentV1 {
+                content: comment.content,
+                post_hash: comment.post_hash,
+                author: comment.author,
+                reverted_from: comment.reverted_from,
+                edit: comment.edit,
+                comment_lock_hash: comment.comment_lock_hash,
+            },
+        })
+    }
+}
+
+/// The rules of a new Comment revision that only depend on the entry itself
+pub fn check_create_comment(action: &EntryCreationAction, comment: &Comment) -> RuleResult {
+    if let (EntryCreationAction::Create(_), Some(_)) = (action, &comment.reverted_from) {
+        return Err(BlogError::Invalid(
+            "Only updates can revert a Comment".to_string(),
+        ));
+    }
+    crate::check_edit_metadata(action, &comment.edit)
 }
 
 pub fn validate_create_comment(
-    _action: EntryCreationAction,
+    action: EntryCreationAction,
     comment: Comment,
 ) -> ExternResult<ValidateCallbackResult> {
+    if let Err(error) = check_create_comment(&action, &comment) {
+        return Ok(error.into());
+    }
     let record = must_get_valid_record(comment.post_hash.clone())?;
     let _post: crate::Post = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
-            "Dependant action must be accompanied by an entry"
-        ))))?;
+        .ok_or(BlogError::MissingEntry)?;
+    if let (EntryCreationAction::Create(create), Some(comment_lock_hash)) =
+        (&action, &comment.comment_lock_hash)
+    {
+        let comment_lock_record = must_get_valid_record(comment_lock_hash.clone())?;
+        if let Err(error) = crate::check_comment_lock(create, &comment, &comment_lock_record) {
+            return Ok(error.into());
+        }
+    }
     // TODO: add the appropriate validation rules
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// The rules of a Comment update, given the revision it restores if it is a revert
+pub fn check_update_comment(comment: &Comment, reverted: Option<&RevertedRevision>) -> RuleResult {
+    let Some(reverted) = reverted else {
+        return Ok(());
+    };
+    crate::check_reverted_revision(reverted)?;
+    let Ok(revision_comment) = Comment::try_from(&reverted.revision) else {
+        return Err(BlogError::Invalid(
+            "A Comment can only be reverted to a Comment revision".to_string(),
+        ));
+    };
+    if revision_comment.content != comment.content
+        || revision_comment.post_hash != comment.post_hash
+    {
+        return Err(BlogError::Invalid(
+            "A reverted Comment must match the revision it restores".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 pub fn validate_update_comment(
-    _action: Update,
-    _comment: Comment,
+    action: Update,
+    comment: Comment,
     _original_action: EntryCreationAction,
     _original_comment: Comment,
 ) -> ExternResult<ValidateCallbackResult> {
+    let reverted = match &comment.reverted_from {
+        Some(reverted_from) => Some(crate::must_get_reverted_revision(&action, reverted_from)?),
+        None => None,
+    };
     // TODO: add the appropriate validation rules
-    Ok(ValidateCallbackResult::Valid)
+    Ok(crate::validation_result(check_update_comment(
+        &comment,
+        reverted.as_ref(),
+    )))
 }
 
 pub fn validate_delete_comment(
@@ -48,37 +179,29 @@ pub fn validate_create_link_post_to_comments(
     _action: CreateLink,
     base_address: AnyLinkableHash,
     target_address: AnyLinkableHash,
-    _tag: LinkTag,
+    tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
     let action_hash = base_address
         .into_action_hash()
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "No action hash associated with link".to_string()
-        )))?;
+        .ok_or(BlogError::MalformedLink)?;
     let record = must_get_valid_record(action_hash)?;
     let _post: crate::Post = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Linked action must reference an entry".to_string()
-        )))?;
-    let action_hash =
-        target_address
-            .into_action_hash()
-            .ok_or(wasm_error!(WasmErrorInner::Guest(
-                "No action hash associated with link".to_string()
-            )))?;
+        .ok_or(BlogError::MissingEntry)?;
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
     let record = must_get_valid_record(action_hash)?;
-    let _comment: crate::Comment = record
+    let comment: crate::Comment = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Linked action must reference an entry".to_string()
-        )))?;
-    // TODO: add the appropriate validation rules
-    Ok(ValidateCallbackResult::Valid)
+        .ok_or(BlogError::MissingEntry)?;
+    Ok(crate::validation_result(crate::check_comment_tag(
+        tag, &comment,
+    )))
 }
 
 pub fn validate_delete_link_post_to_comments(
@@ -98,20 +221,15 @@ pub fn validate_create_link_author_to_comments(
     target_address: AnyLinkableHash,
     _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    let action_hash =
-        target_address
-            .into_action_hash()
-            .ok_or(wasm_error!(WasmErrorInner::Guest(
-                "No action hash associated with link".to_string()
-            )))?;
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
     let record = must_get_valid_record(action_hash)?;
     let _comment: crate::Comment = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Linked action must reference an entry".to_string()
-        )))?;
+        .ok_or(BlogError::MissingEntry)?;
     // TODO: add the appropriate validation rules
     Ok(ValidateCallbackResult::Valid)
 }
@@ -126,3 +244,211 @@ pub fn validate_delete_link_author_to_comments(
     // TODO: add the appropriate validation rules
     Ok(ValidateCallbackResult::Valid)
 }
+
+pub fn validate_create_link_comment_revision_tags(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    let record = must_get_valid_record(action_hash)?;
+    let comment: crate::Comment = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BlogError::MissingEntry)?;
+    if let Err(error) =
+        check_create_link_comment_revision_tags(&action, &base_address, &record, &comment)
+    {
+        return Ok(error.into());
+    }
+    let tagged_comment = must_get_tagged_revision(&action, record.action_address(), comment)?;
+    Ok(crate::validation_result(crate::check_comment_tag(
+        tag,
+        &tagged_comment,
+    )))
+}
+
+pub fn check_create_link_comment_revision_tags(
+    action: &CreateLink,
+    base_address: &AnyLinkableHash,
+    comment_record: &Record,
+    comment: &Comment,
+) -> RuleResult {
+    if comment_record.action().author() != &action.author {
+        return Err(BlogError::Unauthorized(
+            "Only the author of a Comment can retag it".to_string(),
+        ));
+    }
+    if base_address != &AnyLinkableHash::from(comment.post_hash.clone()) {
+        return Err(BlogError::Invalid(
+            "A Comment can only be retagged under the Post that it comments on".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// The latest revision of a comment that its author had committed when tagging it, which the tag of
+/// the link must describe
+fn must_get_tagged_revision(
+    action: &CreateLink,
+    comment_hash: &ActionHash,
+    comment: Comment,
+) -> ExternResult<Comment> {
+    let activity = must_get_agent_activity(
+        action.author.clone(),
+        ChainFilter::new(action.prev_action.clone()).until(comment_hash.clone()),
+    )?;
+    match crate::latest_revision_entry_hash(comment_hash, &activity) {
+        Some(entry_hash) => Ok(Comment::try_from(must_get_entry(entry_hash)?.content)?),
+        None => Ok(comment),
+    }
+}
+
+pub fn validate_delete_link_comment_revision_tags(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(BlogError::Unauthorized(
+            "Only the author of a Comment can remove the tags of its revisions".to_string(),
+        )
+        .into());
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn comment_record(comment: Comment) -> Record {
+        let author = comment.author.clone();
+        record(
+            Action::Create(create(author, 4, 1_000_000)),
+            Some(Entry::try_from(comment).unwrap()),
+        )
+    }
+
+    // A comment that sets the fields that are skipped when they are empty
+    fn full_comment() -> Comment {
+        Comment {
+            reverted_from: Some(action_hash(2)),
+            edit: EditMetadata::new(Some("Typo".to_string()), false),
+            ..comment(agent(2), action_hash(1))
+        }
+    }
+
+    fn layout_v1(comment: &Comment) -> CommentV1 {
+        match CommentVersions::from(comment.clone()) {
+            CommentVersions::V2(comment) => comment.comment,
+            CommentVersions::V1(comment) => comment,
+        }
+    }
+
+    #[test]
+    fn a_v1_comment_is_read_as_a_comment() {
+        let comment = full_comment();
+        let bytes = ExternIO::encode(layout_v1(&comment)).unwrap();
+        let versions: CommentVersions = bytes.decode().unwrap();
+        assert!(matches!(versions, CommentVersions::V1(_)));
+        assert_eq!(versions.schema_version(), 1);
+        assert_eq!(bytes.decode::<Comment>().unwrap(), comment);
+    }
+
+    #[test]
+    fn a_v2_comment_is_not_read_as_v1() {
+        let comment = full_comment();
+        let bytes = ExternIO::encode(CommentV2 {
+            schema_version: 2,
+            comment: layout_v1(&comment),
+        })
+        .unwrap();
+        let versions: CommentVersions = bytes.decode().unwrap();
+        assert!(matches!(versions, CommentVersions::V2(_)));
+        assert_eq!(versions.schema_version(), 2);
+        assert_eq!(bytes.decode::<Comment>().unwrap(), comment);
+    }
+
+    #[test]
+    fn a_comment_is_written_as_v2() {
+        let comment = full_comment();
+        let bytes = ExternIO::encode(comment.clone()).unwrap();
+        let versions: CommentVersions = bytes.decode().unwrap();
+        assert!(matches!(versions, CommentVersions::V2(_)));
+        assert_eq!(versions.schema_version(), COMMENT_SCHEMA_VERSION);
+        assert_eq!(Comment::from(versions), comment);
+    }
+
+    #[test]
+    fn only_the_author_retags_a_comment_under_its_post() {
+        let comment = comment(agent(2), action_hash(1));
+        let record = comment_record(comment.clone());
+        let post: AnyLinkableHash = action_hash(1).into();
+        let action = create_link(agent(2), 2_000_000);
+        assert_eq!(
+            check_create_link_comment_revision_tags(&action, &post, &record, &comment),
+            Ok(())
+        );
+        let other_post: AnyLinkableHash = action_hash(5).into();
+        assert_eq!(
+            check_create_link_comment_revision_tags(&action, &other_post, &record, &comment)
+                .unwrap_err()
+                .code(),
+            "INVALID"
+        );
+        let action = create_link(agent(3), 2_000_000);
+        assert_eq!(
+            check_create_link_comment_revision_tags(&action, &post, &record, &comment)
+                .unwrap_err()
+                .code(),
+            "UNAUTHORIZED"
+        );
+        let original = create_link(agent(2), 2_000_000);
+        let tag = LinkTag::new(vec![]);
+        assert!(matches!(
+            validate_delete_link_comment_revision_tags(
+                delete_link(agent(3)),
+                original,
+                post.clone(),
+                post,
+                tag
+            ),
+            Ok(ValidateCallbackResult::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn only_updates_can_revert_a_comment() {
+        let mut comment = comment(agent(2), action_hash(1));
+        comment.reverted_from = Some(action_hash(3));
+        let action = EntryCreationAction::Create(create(agent(2), 4, 3_000_000));
+        assert!(check_create_comment(&action, &comment).is_err());
+    }
+
+    #[test]
+    fn reverts_must_restore_a_matching_comment_revision() {
+        let comment = comment(agent(2), action_hash(1));
+        let reverted = |revision: Record| RevertedRevision {
+            original_action_hash: action_hash(3),
+            revision_original_action_hash: action_hash(3),
+            revision,
+        };
+        assert_eq!(check_update_comment(&comment, None), Ok(()));
+        let matching = reverted(comment_record(comment.clone()));
+        assert_eq!(check_update_comment(&comment, Some(&matching)), Ok(()));
+        let mut other = comment.clone();
+        other.content = "Something else".to_string();
+        let different = reverted(comment_record(other));
+        assert!(check_update_comment(&comment, Some(&different)).is_err());
+        let not_a_comment = reverted(post_record(post(agent(2)), 4));
+        assert!(check_update_comment(&comment, Some(&not_a_comment)).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use hdi::prelude::*;
+
+use crate::{BlogError, Comment, DnaProperties, RuleResult};
+
+#[derive(Clone, PartialEq)]
+#[hdk_entry_helper]
+pub struct CommentLock {
+    pub post_hash: ActionHash,
+    pub locked: bool,
+}
+
+/// Only the author of the post and the moderators can lock or unlock its comments
+pub fn check_create_comment_lock(
+    action: &EntryCreationAction,
+    post_record: &Record,
+    properties: &DnaProperties,
+) -> RuleResult {
+    if post_record.action().author() != action.author() && !properties.is_moderator(action.author())
+    {
+        return Err(BlogError::Unauthorized(
+            "Only the author of a Post or a moderator can lock its comments".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_create_comment_lock(
+    action: EntryCreationAction,
+    comment_lock: CommentLock,
+) -> ExternResult<ValidateCallbackResult> {
+    let record = must_get_valid_record(comment_lock.post_hash.clone())?;
+    let _post: crate::Post = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BlogError::MissingEntry)?;
+    Ok(crate::validation_result(check_create_comment_lock(
+        &action,
+        &record,
+        &DnaProperties::get()?,
+    )))
+}
+
+pub fn validate_update_comment_lock(
+    _action: Update,
+    _comment_lock: CommentLock,
+    _original_action: EntryCreationAction,
+    _original_comment_lock: CommentLock,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(BlogError::Immutable("Comment Locks cannot be updated".to_string()).into())
+}
+
+pub fn validate_delete_comment_lock(
+    _action: Delete,
+    _original_action: EntryCreationAction,
+    _original_comment_lock: CommentLock,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(BlogError::Immutable("Comment Locks cannot be deleted".to_string()).into())
+}
+
+/// Checks that the Comment Lock recorded by a new comment is a lock of its post, made before the
+/// comment, that left its comments open
+pub fn check_comment_lock(
+    create: &Create,
+    comment: &Comment,
+    comment_lock_record: &Record,
+) -> RuleResult {
+    let Ok(comment_lock) = CommentLock::try_from(comment_lock_record) else {
+        return Err(BlogError::Invalid(
+            "A Comment must record a Comment Lock of its Post".to_string(),
+        ));
+    };
+    if comment_lock.post_hash != comment.post_hash
+        || comment_lock_record.action().timestamp() > create.timestamp
+    {
+        return Err(BlogError::Invalid(
+            "A Comment must record a Comment Lock of its Post made before it".to_string(),
+        ));
+    }
+    if comment_lock.locked {
+        return Err(BlogError::CommentsLocked);
+    }
+    Ok(())
+}
+
+pub fn validate_create_link_post_to_comment_locks(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    let record = must_get_valid_record(action_hash)?;
+    let comment_lock: crate::CommentLock = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BlogError::MissingEntry)?;
+    Ok(crate::validation_result(
+        check_create_link_post_to_comment_locks(&action, &base_address, &record, &comment_lock),
+    ))
+}
+
+pub fn check_create_link_post_to_comment_locks(
+    action: &CreateLink,
+    base_address: &AnyLinkableHash,
+    comment_lock_record: &Record,
+    comment_lock: &CommentLock,
+) -> RuleResult {
+    if base_address != &AnyLinkableHash::from(comment_lock.post_hash.clone()) {
+        return Err(BlogError::Invalid(
+            "A Comment Lock must be linked from the Post it locks".to_string(),
+        ));
+    }
+    if comment_lock_record.action().author() != &action.author {
+        return Err(BlogError::Unauthorized(
+            "Only the author of a Comment Lock can link it".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_delete_link_post_to_comment_locks(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(BlogError::Immutable("PostToCommentLocks links cannot be deleted".to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn comment_lock_record(author: AgentPubKey, post_hash: ActionHash) -> (Record, CommentLock) {
+        let comment_lock = CommentLock {
+            post_hash,
+            locked: true,
+        };
+        let record = record(
+            Action::Create(create(author, 5, 2_000_000)),
+            Some(Entry::try_from(comment_lock.clone()).unwrap()),
+        );
+        (record, comment_lock)
+    }
+
+    #[test]
+    fn the_post_author_and_moderators_lock_its_comments() {
+        let post_record = post_record(post(agent(1)), 3);
+        let properties = DnaProperties {
+            moderators: vec![AgentPubKeyB64::from(agent(3))],
+            ..Default::default()
+        };
+        for author in [agent(1), agent(3)] {
+            let action = EntryCreationAction::Create(create(author, 5, 2_000_000));
+            assert_eq!(
+                check_create_comment_lock(&action, &post_record, &properties),
+                Ok(())
+            );
+        }
+        let action = EntryCreationAction::Create(create(agent(2), 5, 2_000_000));
+        assert_eq!(
+            check_create_comment_lock(&action, &post_record, &properties)
+                .unwrap_err()
+                .code(),
+            "UNAUTHORIZED"
+        );
+    }
+
+    #[test]
+    fn comments_record_an_open_comment_lock_of_their_post() {
+        let comment = comment(agent(2), action_hash(1));
+        let comment_create = create(agent(2), 4, 3_000_000);
+        let (locked_record, _) = comment_lock_record(agent(1), action_hash(1));
+        assert_eq!(
+            check_comment_lock(&comment_create, &comment, &locked_record),
+            Err(BlogError::CommentsLocked)
+        );
+        let open = CommentLock {
+            post_hash: action_hash(1),
+            locked: false,
+        };
+        let open_record = record(
+            Action::Create(create(agent(3), 5, 2_000_000)),
+            Some(Entry::try_from(open.clone()).unwrap()),
+        );
+        assert_eq!(
+            check_comment_lock(&comment_create, &comment, &open_record),
+            Ok(())
+        );
+        // Only a lock of the same post, made before the comment, tells whether it was open
+        let later_record = record(
+            Action::Create(create(agent(3), 5, 4_000_000)),
+            Some(Entry::try_from(open).unwrap()),
+        );
+        assert!(check_comment_lock(&comment_create, &comment, &later_record).is_err());
+        let other_post = CommentLock {
+            post_hash: action_hash(2),
+            locked: false,
+        };
+        let other_post_record = record(
+            Action::Create(create(agent(3), 5, 2_000_000)),
+            Some(Entry::try_from(other_post).unwrap()),
+        );
+        assert!(check_comment_lock(&comment_create, &comment, &other_post_record).is_err());
+        let not_a_lock = post_record(post(agent(1)), 3);
+        assert!(check_comment_lock(&comment_create, &comment, &not_a_lock).is_err());
+    }
+
+    #[test]
+    fn comment_locks_are_linked_from_their_post_by_their_author() {
+        let (record, comment_lock) = comment_lock_record(agent(1), action_hash(1));
+        let action = create_link(agent(1), 3_000_000);
+        let post_hash: AnyLinkableHash = action_hash(1).into();
+        assert_eq!(
+            check_create_link_post_to_comment_locks(&action, &post_hash, &record, &comment_lock),
+            Ok(())
+        );
+        let other_post: AnyLinkableHash = action_hash(2).into();
+        assert!(check_create_link_post_to_comment_locks(
+            &action,
+            &other_post,
+            &record,
+            &comment_lock
+        )
+        .is_err());
+        let action = create_link(agent(2), 3_000_000);
+        assert!(check_create_link_post_to_comment_locks(
+            &action,
+            &post_hash,
+            &record,
+            &comment_lock
+        )
+        .is_err());
+    }
+}
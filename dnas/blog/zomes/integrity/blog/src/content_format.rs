@@ -0,0 +1,171 @@
+use hdi::prelude::*;
+use pulldown_cmark::{Event, Options, Parser, Tag};
+
+use crate::{BlogError, RuleResult};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum ContentFormat {
+    #[default]
+    Plain,
+    Markdown,
+}
+
+impl ContentFormat {
+    pub fn is_plain(&self) -> bool {
+        matches!(self, ContentFormat::Plain)
+    }
+}
+
+// URL schemes that can run code when a rendered link or image is followed
+const UNSAFE_URL_SCHEMES: [&str; 3] = ["javascript:", "vbscript:", "data:"];
+
+/// Whether the URL of a link or image uses a scheme that can run code
+pub fn is_unsafe_url(url: &str) -> bool {
+    let url: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_lowercase();
+    UNSAFE_URL_SCHEMES
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+}
+
+/// The markdown extensions that content is rendered with, so that validation parses it identically
+pub const MARKDOWN_OPTIONS: Options = Options::ENABLE_STRIKETHROUGH.union(Options::ENABLE_TABLES);
+
+/// Returns the reason why the markdown content is rejected, if it contains raw HTML or script injection
+///
+/// The content is parsed like it is rendered, so code spans and blocks can show markup, and the URLs
+/// are checked once their entities and escapes are decoded
+pub fn check_markdown_content(content: &str) -> Option<String> {
+    for event in Parser::new_ext(content, MARKDOWN_OPTIONS) {
+        match event {
+            Event::Html(_) | Event::InlineHtml(_) => {
+                return Some("Markdown content must not contain raw HTML".to_string());
+            }
+            Event::Start(Tag::Link { dest_url, .. })
+            | Event::Start(Tag::Image { dest_url, .. })
+                if is_unsafe_url(&dest_url) =>
+            {
+                return Some("Markdown links must not use script URLs".to_string());
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+pub fn check_content_format(format: ContentFormat, content: &str) -> RuleResult {
+    if let ContentFormat::Markdown = format {
+        if let Some(reason) = check_markdown_content(content) {
+            return Err(BlogError::Invalid(reason));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn raw_html_is_rejected_in_markdown() {
+        assert!(check_markdown_content("Hello <script>alert(1)</script>").is_some());
+        assert!(check_markdown_content("<!-- comment -->").is_some());
+        assert!(check_markdown_content("<div>\nblock\n</div>").is_some());
+        assert_eq!(check_markdown_content("1 < 2 and 3 > 2"), None);
+    }
+
+    #[test]
+    fn markup_that_is_not_rendered_as_html_is_accepted() {
+        assert_eq!(check_markdown_content("Use `a < b` or `<div>`"), None);
+        assert_eq!(
+            check_markdown_content("```html\n<script>alert(1)</script>\n```"),
+            None
+        );
+        assert_eq!(check_markdown_content("    <p>indented code</p>"), None);
+        assert_eq!(check_markdown_content("See <https://example.com>"), None);
+        assert_eq!(check_markdown_content("Mail <me@example.com>"), None);
+        // A line break ends the destination, so this is text rather than a link
+        assert_eq!(check_markdown_content("[x](java\nscript:alert(1))"), None);
+    }
+
+    #[test]
+    fn script_urls_are_rejected_in_markdown_links() {
+        assert!(check_markdown_content("[click](javascript:alert(1))").is_some());
+        assert!(check_markdown_content("![image](DATA:text/html;base64,AAAA)").is_some());
+        assert!(check_markdown_content("[click](<java\tscript:alert(1)>)").is_some());
+        assert!(check_markdown_content("[name]\n\n[name]: vbscript:msgbox").is_some());
+        assert!(check_markdown_content("<javascript:alert(1)>").is_some());
+        assert_eq!(
+            check_markdown_content("[docs](https://example.com)\n\n[name]: https://example.com"),
+            None
+        );
+    }
+
+    #[test]
+    fn script_urls_are_rejected_once_decoded() {
+        assert!(check_markdown_content("[x](javascript&#58;alert(1))").is_some());
+        assert!(check_markdown_content("[x](java&#10;script:alert(1))").is_some());
+        assert!(check_markdown_content("[x](&#x6A;avascript:alert(1))").is_some());
+        assert!(check_markdown_content("[x](javascript\\:alert(1))").is_some());
+        assert!(check_markdown_content("[x][r]\n\n[r]: java&#9;script:alert(1)").is_some());
+    }
+
+    #[test]
+    fn plain_content_is_never_checked_for_markup() {
+        assert_eq!(
+            check_content_format(ContentFormat::Plain, "<script>alert(1)</script>"),
+            Ok(())
+        );
+        assert_eq!(
+            check_content_format(ContentFormat::Markdown, "<script>alert(1)</script>")
+                .unwrap_err()
+                .code(),
+            "INVALID"
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn plain_content_is_always_valid(content in "\\PC*") {
+            prop_assert_eq!(check_content_format(ContentFormat::Plain, &content), Ok(()));
+        }
+
+        #[test]
+        fn markdown_without_angle_brackets_or_links_is_valid(content in "[a-zA-Z0-9 .,*_#\n-]*") {
+            prop_assert_eq!(check_content_format(ContentFormat::Markdown, &content), Ok(()));
+        }
+
+        #[test]
+        fn markdown_with_an_html_tag_is_invalid(
+            // Starts with a letter so that the tag is never in an indented code block
+            before in "([a-z][a-z ]*)?",
+            tag in "[a-zA-Z][a-zA-Z0-9]*",
+            after in "[a-z ]*",
+        ) {
+            let content = format!("{before}<{tag}>{after}");
+            prop_assert!(check_content_format(ContentFormat::Markdown, &content).is_err());
+        }
+
+        #[test]
+        fn unsafe_schemes_are_detected_regardless_of_case(
+            scheme in prop::sample::select(UNSAFE_URL_SCHEMES.to_vec()),
+            uppercase in prop::collection::vec(any::<bool>(), 16),
+            rest in "[a-z0-9]*(\\([a-z0-9]*\\))?",
+        ) {
+            let scheme: String = scheme
+                .chars()
+                .zip(uppercase)
+                .map(|(c, upper)| if upper { c.to_ascii_uppercase() } else { c })
+                .collect();
+            let url = format!("{scheme}{rest}");
+            prop_assert!(is_unsafe_url(&url));
+            let content = format!("[link]({scheme}{rest})");
+            prop_assert!(check_content_format(ContentFormat::Markdown, &content).is_err());
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use hdi::prelude::*;
+
+/// The failures of the zome calls and validation rules of this DNA
+///
+/// Each variant has a stable code that is sent to clients as the prefix of the error message,
+/// e.g. `POST_NOT_FOUND: Post not found`, so that they can branch on it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "code", content = "reason", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BlogError {
+    PostNotFound,
+    CommentNotFound,
+    RevisionNotFound,
+    SeriesNotFound,
+    CommentLockNotFound,
+    EncryptionKeyNotFound,
+    FileManifestNotFound,
+    FileChunkNotFound,
+    DelegationGrantNotFound,
+    // The CreateLink action that a DeleteLink deletes could not be found
+    LinkNotFound,
+    // A record that was expected to contain an app entry doesn't
+    MissingEntry,
+    // The host returned details of an unexpected kind
+    MalformedDetails,
+    // A link points to something other than an action
+    MalformedLink,
+    // A link tag doesn't decode to the metadata that its link type carries
+    MalformedLinkTag,
+    PostNotDeleted,
+    PostAlreadyRestored,
+    RestoreGracePeriodExpired,
+    CommentsLocked,
+    PostNotInSeries,
+    // Another agent or cell could not be reached
+    NetworkError(String),
+    // A countersigning session could not be started or completed
+    CountersigningFailed(String),
+    // The agent is not allowed to perform the action
+    Unauthorized(String),
+    // The entry or link cannot be changed once it has been created
+    Immutable(String),
+    // The entry or link is malformed or inconsistent with the data it references
+    Invalid(String),
+}
+
+impl BlogError {
+    /// The stable code of the error
+    pub fn code(&self) -> &'static str {
+        match self {
+            BlogError::PostNotFound => "POST_NOT_FOUND",
+            BlogError::CommentNotFound => "COMMENT_NOT_FOUND",
+            BlogError::RevisionNotFound => "REVISION_NOT_FOUND",
+            BlogError::SeriesNotFound => "SERIES_NOT_FOUND",
+            BlogError::CommentLockNotFound => "COMMENT_LOCK_NOT_FOUND",
+            BlogError::EncryptionKeyNotFound => "ENCRYPTION_KEY_NOT_FOUND",
+            BlogError::FileManifestNotFound => "FILE_MANIFEST_NOT_FOUND",
+            BlogError::FileChunkNotFound => "FILE_CHUNK_NOT_FOUND",
+            BlogError::DelegationGrantNotFound => "DELEGATION_GRANT_NOT_FOUND",
+            BlogError::LinkNotFound => "LINK_NOT_FOUND",
+            BlogError::MissingEntry => "MISSING_ENTRY",
+            BlogError::MalformedDetails => "MALFORMED_DETAILS",
+            BlogError::MalformedLink => "MALFORMED_LINK",
+            BlogError::MalformedLinkTag => "MALFORMED_LINK_TAG",
+            BlogError::PostNotDeleted => "POST_NOT_DELETED",
+            BlogError::PostAlreadyRestored => "POST_ALREADY_RESTORED",
+            BlogError::RestoreGracePeriodExpired => "RESTORE_GRACE_PERIOD_EXPIRED",
+            BlogError::CommentsLocked => "COMMENTS_LOCKED",
+            BlogError::PostNotInSeries => "POST_NOT_IN_SERIES",
+            BlogError::NetworkError(_) => "NETWORK_ERROR",
+            BlogError::CountersigningFailed(_) => "COUNTERSIGNING_FAILED",
+            BlogError::Unauthorized(_) => "UNAUTHORIZED",
+            BlogError::Immutable(_) => "IMMUTABLE",
+            BlogError::Invalid(_) => "INVALID",
+        }
+    }
+
+    /// A human readable description of the error
+    pub fn reason(&self) -> &str {
+        match self {
+            BlogError::PostNotFound => "Post not found",
+            BlogError::CommentNotFound => "Comment not found",
+            BlogError::RevisionNotFound => "Revision not found",
+            BlogError::SeriesNotFound => "Series not found",
+            BlogError::CommentLockNotFound => "Comment Lock not found",
+            BlogError::EncryptionKeyNotFound => "Encryption Key not found",
+            BlogError::FileManifestNotFound => "File Manifest not found",
+            BlogError::FileChunkNotFound => "File Chunk not found",
+            BlogError::DelegationGrantNotFound => "Delegation grant not found",
+            BlogError::LinkNotFound => "Create Link not found",
+            BlogError::MissingEntry => "Record has no entry",
+            BlogError::MalformedDetails => "Malformed get details response",
+            BlogError::MalformedLink => "No action hash associated with link",
+            BlogError::MalformedLinkTag => "Malformed link tag",
+            BlogError::PostNotDeleted => "Post has not been deleted",
+            BlogError::PostAlreadyRestored => "Post has already been restored",
+            BlogError::RestoreGracePeriodExpired => {
+                "The grace period to restore this Post has expired"
+            }
+            BlogError::CommentsLocked => "Comments on this Post are locked",
+            BlogError::PostNotInSeries => "Post is not part of the Series",
+            BlogError::NetworkError(reason)
+            | BlogError::CountersigningFailed(reason)
+            | BlogError::Unauthorized(reason)
+            | BlogError::Immutable(reason)
+            | BlogError::Invalid(reason) => reason,
+        }
+    }
+}
+
+impl std::fmt::Display for BlogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code(), self.reason())
+    }
+}
+
+impl From<BlogError> for WasmError {
+    fn from(error: BlogError) -> Self {
+        wasm_error!(WasmErrorInner::Guest(error.to_string()))
+    }
+}
+
+impl From<BlogError> for ValidateCallbackResult {
+    fn from(error: BlogError) -> Self {
+        ValidateCallbackResult::Invalid(error.to_string())
+    }
+}
+
+/// The outcome of a validation rule, computed only from data that has already been fetched
+pub type RuleResult = Result<(), BlogError>;
+
+/// Turns the outcome of a validation rule into the result of a validation callback
+pub fn validation_result(result: RuleResult) -> ValidateCallbackResult {
+    match result {
+        Ok(()) => ValidateCallbackResult::Valid,
+        Err(error) => error.into(),
+    }
+}
@@ -0,0 +1,283 @@
+use hdi::prelude::*;
+
+use crate::{BlogError, RuleResult};
+
+// Each chunk stays well under the maximum entry size
+pub const FILE_CHUNK_SIZE: usize = 256 * 1024;
+
+#[derive(Clone, PartialEq)]
+#[hdk_entry_helper]
+pub struct FileChunk {
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq)]
+#[hdk_entry_helper]
+pub struct FileManifest {
+    pub name: String,
+    pub mime_type: String,
+    pub size: usize,
+    // SHA-256 of the whole file
+    #[serde(with = "serde_bytes")]
+    pub sha256: Vec<u8>,
+    pub chunks: Vec<EntryHash>,
+}
+
+pub fn check_file_chunk(file_chunk: &FileChunk) -> RuleResult {
+    if file_chunk.data.is_empty() || file_chunk.data.len() > FILE_CHUNK_SIZE {
+        return Err(BlogError::Invalid(format!(
+            "File chunks must contain between 1 and {FILE_CHUNK_SIZE} bytes"
+        )));
+    }
+    Ok(())
+}
+
+pub fn validate_create_file_chunk(
+    _action: EntryCreationAction,
+    file_chunk: FileChunk,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(crate::validation_result(check_file_chunk(&file_chunk)))
+}
+
+pub fn validate_update_file_chunk(
+    _action: Update,
+    _file_chunk: FileChunk,
+    _original_action: EntryCreationAction,
+    _original_file_chunk: FileChunk,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(BlogError::Immutable("File Chunks cannot be updated".to_string()).into())
+}
+
+pub fn validate_delete_file_chunk(
+    action: Delete,
+    original_action: EntryCreationAction,
+    _original_file_chunk: FileChunk,
+) -> ExternResult<ValidateCallbackResult> {
+    if &action.author != original_action.author() {
+        return Ok(BlogError::Unauthorized(
+            "Only the uploader of a File Chunk can delete it".to_string(),
+        )
+        .into());
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Checks the shape of a File Manifest and reassembles the file from its chunk entries
+pub fn assemble_file(
+    file_manifest: &FileManifest,
+    max_file_size: usize,
+    chunks: &[Entry],
+) -> Result<Vec<u8>, BlogError> {
+    if file_manifest.size > max_file_size {
+        return Err(BlogError::Invalid(format!(
+            "Files must be at most {max_file_size} bytes"
+        )));
+    }
+    if file_manifest.chunks.is_empty() {
+        return Err(BlogError::Invalid(
+            "A File Manifest must reference at least one chunk".to_string(),
+        ));
+    }
+    let mut data = Vec::with_capacity(file_manifest.size);
+    for chunk in chunks {
+        let Ok(chunk) = FileChunk::try_from(chunk.clone()) else {
+            return Err(BlogError::Invalid(
+                "File Manifest chunks must reference File Chunk entries".to_string(),
+            ));
+        };
+        data.extend(chunk.data);
+        if data.len() > file_manifest.size {
+            break;
+        }
+    }
+    if data.len() != file_manifest.size {
+        return Err(BlogError::Invalid(
+            "The size of a File Manifest must match the total size of its chunks".to_string(),
+        ));
+    }
+    Ok(data)
+}
+
+pub fn check_file_hash(file_manifest: &FileManifest, sha256: &[u8]) -> RuleResult {
+    if sha256 != file_manifest.sha256.as_slice() {
+        return Err(BlogError::Invalid(
+            "The SHA-256 of a File Manifest must match the contents of its chunks".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_create_file_manifest(
+    _action: EntryCreationAction,
+    file_manifest: FileManifest,
+) -> ExternResult<ValidateCallbackResult> {
+    let max_file_size = crate::DnaProperties::get()?.max_file_size();
+    // Stop fetching at the first chunk that can't be part of the file, so that a manifest
+    // can't make validators fetch more than a file's worth of chunks
+    let mut chunks = Vec::with_capacity(file_manifest.chunks.len());
+    if file_manifest.size <= max_file_size {
+        let mut size = 0;
+        for chunk_hash in &file_manifest.chunks {
+            let chunk = must_get_entry(chunk_hash.clone())?.into_content();
+            let chunk_size = FileChunk::try_from(chunk.clone()).map(|chunk| chunk.data.len());
+            chunks.push(chunk);
+            match chunk_size {
+                Ok(chunk_size) if size + chunk_size <= file_manifest.size => size += chunk_size,
+                _ => break,
+            }
+        }
+    }
+    let data = match assemble_file(&file_manifest, max_file_size, &chunks) {
+        Ok(data) => data,
+        Err(error) => return Ok(error.into()),
+    };
+    Ok(crate::validation_result(check_file_hash(
+        &file_manifest,
+        &hash_sha256(data)?,
+    )))
+}
+
+pub fn validate_update_file_manifest(
+    _action: Update,
+    _file_manifest: FileManifest,
+    _original_action: EntryCreationAction,
+    _original_file_manifest: FileManifest,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(BlogError::Immutable("File Manifests cannot be updated".to_string()).into())
+}
+
+pub fn validate_delete_file_manifest(
+    action: Delete,
+    original_action: EntryCreationAction,
+    _original_file_manifest: FileManifest,
+) -> ExternResult<ValidateCallbackResult> {
+    if &action.author != original_action.author() {
+        return Ok(BlogError::Unauthorized(
+            "Only the uploader of a File Manifest can delete it".to_string(),
+        )
+        .into());
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn check_attachments(attachments: &[Entry]) -> RuleResult {
+    for attachment in attachments {
+        if FileManifest::try_from(attachment.clone()).is_err() {
+            return Err(BlogError::Invalid(
+                "Post attachments must reference File Manifest entries".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_attachments(attachments: &[EntryHash]) -> ExternResult<ValidateCallbackResult> {
+    let attachments = attachments
+        .iter()
+        .map(|attachment| Ok(must_get_entry(attachment.clone())?.into_content()))
+        .collect::<ExternResult<Vec<Entry>>>()?;
+    Ok(crate::validation_result(check_attachments(&attachments)))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    fn chunk_entry(data: Vec<u8>) -> Entry {
+        Entry::try_from(FileChunk { data }).unwrap()
+    }
+
+    fn manifest(size: usize, chunks: usize) -> FileManifest {
+        FileManifest {
+            name: "file.txt".to_string(),
+            mime_type: "text/plain".to_string(),
+            size,
+            sha256: vec![0; 32],
+            chunks: (0..chunks).map(|index| entry_hash(index as u8)).collect(),
+        }
+    }
+
+    #[test]
+    fn chunks_must_not_be_empty_or_oversized() {
+        assert!(check_file_chunk(&FileChunk { data: vec![] }).is_err());
+        assert_eq!(check_file_chunk(&FileChunk { data: vec![1] }), Ok(()));
+        assert_eq!(
+            check_file_chunk(&FileChunk {
+                data: vec![0; FILE_CHUNK_SIZE]
+            }),
+            Ok(())
+        );
+        assert!(check_file_chunk(&FileChunk {
+            data: vec![0; FILE_CHUNK_SIZE + 1]
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn manifests_must_reference_file_chunks() {
+        let chunks = [chunk_entry(vec![1, 2]), chunk_entry(vec![3])];
+        assert_eq!(
+            assemble_file(&manifest(3, 2), 16, &chunks),
+            Ok(vec![1, 2, 3])
+        );
+        assert!(assemble_file(&manifest(0, 0), 16, &[]).is_err());
+        assert!(assemble_file(&manifest(17, 1), 16, &chunks[..1]).is_err());
+        let not_a_chunk = Entry::try_from(post(agent(1))).unwrap();
+        assert!(assemble_file(&manifest(2, 1), 16, &[not_a_chunk]).is_err());
+    }
+
+    #[test]
+    fn manifests_must_hash_their_contents() {
+        let manifest = manifest(3, 1);
+        assert_eq!(check_file_hash(&manifest, &[0; 32]), Ok(()));
+        assert!(check_file_hash(&manifest, &[1; 32]).is_err());
+    }
+
+    #[test]
+    fn attachments_must_be_file_manifests() {
+        let manifest = Entry::try_from(manifest(3, 1)).unwrap();
+        assert_eq!(check_attachments(std::slice::from_ref(&manifest)), Ok(()));
+        assert!(check_attachments(&[manifest, chunk_entry(vec![1])]).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn assembled_files_are_the_concatenation_of_their_chunks(
+            chunks in prop::collection::vec(prop::collection::vec(any::<u8>(), 1..32), 1..8),
+            size_offset in -3i64..3,
+        ) {
+            let data: Vec<u8> = chunks.concat();
+            let size = (data.len() as i64 + size_offset).max(0) as usize;
+            let manifest = manifest(size, chunks.len());
+            let entries: Vec<Entry> = chunks.into_iter().map(chunk_entry).collect();
+            let result = assemble_file(&manifest, 1024, &entries);
+            if size == data.len() {
+                prop_assert_eq!(result, Ok(data));
+            } else {
+                prop_assert!(result.is_err());
+            }
+        }
+
+        #[test]
+        fn only_the_uploader_deletes_files(uploader in arb_agent(), deleter in arb_agent()) {
+            let action = Delete {
+                author: deleter.clone(),
+                timestamp: Timestamp::from_micros(2_000_000),
+                action_seq: 10,
+                prev_action: action_hash(0),
+                deletes_address: action_hash(1),
+                deletes_entry_address: entry_hash(1),
+                weight: Default::default(),
+            };
+            let original_action = EntryCreationAction::Create(create(uploader.clone(), 3, 1_000_000));
+            let chunk = validate_delete_file_chunk(action.clone(), original_action.clone(), FileChunk { data: vec![1] }).unwrap();
+            let manifest = validate_delete_file_manifest(action, original_action, manifest(1, 1)).unwrap();
+            prop_assert_eq!(chunk == ValidateCallbackResult::Valid, uploader == deleter);
+            prop_assert_eq!(manifest == ValidateCallbackResult::Valid, uploader == deleter);
+        }
+    }
+}
@@ -0,0 +1,700 @@
+//! Property-based fuzzing of the `validate` callback, run against a fake host whose DHT holds
+//! a small pool of generated records, so that ops can reference both existing and missing data
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use hdi::prelude::holo_hash::AgentPubKeyB64;
+use hdi::prelude::*;
+use proptest::prelude::*;
+
+use crate::test_utils::*;
+use crate::*;
+
+// Hashes from 1 to POOL_SIZE resolve in the fake DHT, the ones above it are missing
+const POOL_SIZE: u8 = 6;
+
+/// A deterministic host that serves a fixed set of records and entries
+#[derive(Debug, Clone)]
+struct FakeHost {
+    records: HashMap<ActionHash, Record>,
+    entries: HashMap<EntryHash, Entry>,
+    properties: DnaProperties,
+}
+
+fn fake_hash<const N: usize>(value: impl std::fmt::Debug) -> [u8; N] {
+    let mut hasher = DefaultHasher::new();
+    format!("{value:?}").hash(&mut hasher);
+    let bytes = hasher.finish().to_le_bytes();
+    std::array::from_fn(|index| bytes[index % bytes.len()])
+}
+
+fn missing(name: &str) -> WasmError {
+    wasm_error!(WasmErrorInner::Guest(format!("{name}: not found")))
+}
+
+impl HdiT for FakeHost {
+    fn verify_signature(&self, verify_signature: VerifySignature) -> ExternResult<bool> {
+        Ok(verify_signature.signature.0[0] == 0)
+    }
+
+    fn hash(&self, hash_input: HashInput) -> ExternResult<HashOutput> {
+        match hash_input {
+            HashInput::Entry(entry) => Ok(HashOutput::Entry(EntryHash::from_raw_36(
+                fake_hash::<36>(entry).to_vec(),
+            ))),
+            HashInput::Action(action) => Ok(HashOutput::Action(ActionHash::from_raw_36(
+                fake_hash::<36>(action).to_vec(),
+            ))),
+            HashInput::Sha256(data) => Ok(HashOutput::Sha256(fake_hash::<32>(data).into())),
+            _ => Err(missing("hash")),
+        }
+    }
+
+    fn must_get_entry(&self, input: MustGetEntryInput) -> ExternResult<EntryHashed> {
+        let entry = self
+            .entries
+            .get(&input.0)
+            .ok_or(missing("must_get_entry"))?;
+        Ok(EntryHashed::with_pre_hashed(entry.clone(), input.0))
+    }
+
+    fn must_get_action(&self, input: MustGetActionInput) -> ExternResult<SignedActionHashed> {
+        let record = self
+            .records
+            .get(&input.0)
+            .ok_or(missing("must_get_action"))?;
+        Ok(record.signed_action().clone())
+    }
+
+    fn must_get_valid_record(&self, input: MustGetValidRecordInput) -> ExternResult<Record> {
+        self.records
+            .get(&input.0)
+            .cloned()
+            .ok_or(missing("must_get_valid_record"))
+    }
+
+    fn must_get_agent_activity(
+        &self,
+        input: MustGetAgentActivityInput,
+    ) -> ExternResult<Vec<RegisterAgentActivity>> {
+        let mut activity: Vec<RegisterAgentActivity> = self
+            .records
+            .values()
+            .filter(|record| record.action().author() == &input.author)
+            .map(|record| RegisterAgentActivity {
+                action: record.signed_action().clone(),
+                cached_entry: None,
+            })
+            .collect();
+        activity.sort_by_key(|activity| activity.action.action().action_seq());
+        Ok(activity)
+    }
+
+    fn dna_info(&self, _: ()) -> ExternResult<DnaInfo> {
+        Ok(DnaInfo {
+            name: "blog".to_string(),
+            hash: DnaHash::from_raw_36(vec![0; 36]),
+            modifiers: DnaModifiers {
+                network_seed: String::new(),
+                properties: SerializedBytes::try_from(self.properties.clone())
+                    .map_err(|e| wasm_error!(e))?,
+                origin_time: Timestamp::from_micros(0),
+                quantum_time: std::time::Duration::from_secs(300),
+            },
+            zome_names: vec!["blog_integrity".into()],
+        })
+    }
+
+    fn zome_info(&self, _: ()) -> ExternResult<ZomeInfo> {
+        Ok(ZomeInfo::new(
+            "blog_integrity".into(),
+            0.into(),
+            SerializedBytes::try_from(()).map_err(|e| wasm_error!(e))?,
+            EntryDefs(vec![]),
+            vec![],
+            ScopedZomeTypesSet {
+                entries: ScopedZomeTypes(vec![(
+                    0.into(),
+                    (0..UnitEntryTypes::ENUM_LEN).map(EntryDefIndex).collect(),
+                )]),
+                links: ScopedZomeTypes(vec![(
+                    0.into(),
+                    (0..LinkTypes::ENUM_LEN).map(LinkType).collect(),
+                )]),
+            },
+        ))
+    }
+
+    fn trace(&self, _: TraceMsg) -> ExternResult<()> {
+        Ok(())
+    }
+
+    fn x_salsa20_poly1305_decrypt(
+        &self,
+        _: XSalsa20Poly1305Decrypt,
+    ) -> ExternResult<Option<XSalsa20Poly1305Data>> {
+        Ok(None)
+    }
+
+    fn x_25519_x_salsa20_poly1305_decrypt(
+        &self,
+        _: X25519XSalsa20Poly1305Decrypt,
+    ) -> ExternResult<Option<XSalsa20Poly1305Data>> {
+        Ok(None)
+    }
+
+    fn ed_25519_x_salsa20_poly1305_decrypt(
+        &self,
+        _: Ed25519XSalsa20Poly1305Decrypt,
+    ) -> ExternResult<XSalsa20Poly1305Data> {
+        Err(missing("ed_25519_x_salsa20_poly1305_decrypt"))
+    }
+}
+
+fn pool_action_hash() -> impl Strategy<Value = ActionHash> {
+    (1..POOL_SIZE + 3).prop_map(action_hash)
+}
+
+fn pool_entry_hash() -> impl Strategy<Value = EntryHash> {
+    (1..POOL_SIZE + 3).prop_map(entry_hash)
+}
+
+fn arb_text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "\\PC{0,40}",
+        Just(String::new()),
+        Just("[x](javascript:alert(1)) <script>".to_string()),
+    ]
+}
+
+fn arb_timestamp() -> impl Strategy<Value = Timestamp> {
+    prop_oneof![0i64..10_000_000, any::<i64>()].prop_map(Timestamp::from_micros)
+}
+
+fn arb_edit() -> impl Strategy<Value = Option<EditMetadata>> {
+    prop::option::of(
+        (prop::option::of("\\PC{0,250}"), any::<bool>())
+            .prop_map(|(reason, minor)| EditMetadata { reason, minor }),
+    )
+}
+
+fn arb_encrypted() -> impl Strategy<Value = XSalsa20Poly1305EncryptedData> {
+    prop::collection::vec(any::<u8>(), 0..8)
+        .prop_map(|data| XSalsa20Poly1305EncryptedData::new([0; 24].into(), data))
+}
+
+fn arb_envelope() -> impl Strategy<Value = PostEnvelope> {
+    (
+        pool_action_hash(),
+        arb_encrypted(),
+        prop::collection::vec((arb_agent(), pool_action_hash(), arb_encrypted()), 0..3),
+    )
+        .prop_map(
+            |(sender_key_hash, encrypted_content, recipients)| PostEnvelope {
+                sender_key_hash,
+                encrypted_content,
+                recipients: recipients
+                    .into_iter()
+                    .map(
+                        |(recipient, recipient_key_hash, encrypted_key)| WrappedKey {
+                            recipient,
+                            recipient_key_hash,
+                            encrypted_key,
+                        },
+                    )
+                    .collect(),
+            },
+        )
+}
+
+fn arb_post() -> impl Strategy<Value = Post> {
+    (
+        (arb_text(), arb_text(), arb_agent()),
+        prop::collection::vec(arb_agent(), 0..3),
+        prop::option::of(arb_agent()),
+        prop::option::weighted(0.2, arb_envelope()),
+        any::<bool>(),
+        prop::collection::vec(pool_entry_hash(), 0..3),
+        prop::option::of(pool_action_hash()),
+        arb_edit(),
+    )
+        .prop_map(
+            |(
+                (name, content, author),
+                co_authors,
+                delegate,
+                envelope,
+                markdown,
+                attachments,
+                reverted_from,
+                edit,
+            )| Post {
+                name,
+                content,
+                author,
+                co_authors,
+                delegate,
+                envelope,
+                format: if markdown {
+                    ContentFormat::Markdown
+                } else {
+                    ContentFormat::Plain
+                },
+                attachments,
+                reverted_from,
+                edit,
+            },
+        )
+}
+
+fn arb_comment() -> impl Strategy<Value = Comment> {
+    (
+        arb_text(),
+        pool_action_hash(),
+        arb_agent(),
+        prop::option::of(pool_action_hash()),
+        arb_edit(),
+        prop::option::of(pool_action_hash()),
+    )
+        .prop_map(
+            |(content, post_hash, author, reverted_from, edit, comment_lock_hash)| Comment {
+                content,
+                post_hash,
+                author,
+                reverted_from,
+                edit,
+                comment_lock_hash,
+            },
+        )
+}
+
+fn encoded(value: &(impl Serialize + std::fmt::Debug)) -> Entry {
+    Entry::App(AppEntryBytes(SerializedBytes::from(UnsafeBytes::from(
+        encode(value).unwrap(),
+    ))))
+}
+
+/// An entry together with the index of its entry type, or `None` for entries that aren't app entries
+fn arb_entry() -> impl Strategy<Value = (Option<u8>, Entry)> {
+    prop_oneof![
+        3 => arb_post().prop_map(|post| (Some(0), Entry::try_from(post).unwrap())),
+        // Posts and comments stored with the layout from before schema versions
+        1 => arb_post().prop_map(|post| {
+            let PostVersions::V2(post) = PostVersions::from(post) else {
+                unreachable!()
+            };
+            (Some(0), encoded(&PostVersions::V1(post.post)))
+        }),
+        3 => arb_comment().prop_map(|comment| (Some(1), Entry::try_from(comment).unwrap())),
+        1 => arb_comment().prop_map(|comment| {
+            let CommentVersions::V2(comment) = CommentVersions::from(comment) else {
+                unreachable!()
+            };
+            (Some(1), encoded(&CommentVersions::V1(comment.comment)))
+        }),
+        1 => (pool_action_hash(), any::<bool>()).prop_map(|(post_hash, locked)| {
+            (Some(2), Entry::try_from(CommentLock { post_hash, locked }).unwrap())
+        }),
+        1 => prop::collection::vec(any::<u8>(), 0..64)
+            .prop_map(|data| (Some(3), Entry::try_from(FileChunk { data }).unwrap())),
+        1 => (
+            0usize..128,
+            prop::collection::vec(any::<u8>(), 0..33),
+            prop::collection::vec(pool_entry_hash(), 0..3),
+        )
+            .prop_map(|(size, sha256, chunks)| {
+                let manifest = FileManifest {
+                    name: "file".to_string(),
+                    mime_type: "text/plain".to_string(),
+                    size,
+                    sha256,
+                    chunks,
+                };
+                (Some(4), Entry::try_from(manifest).unwrap())
+            }),
+        1 => (
+            arb_text(),
+            prop::collection::vec(pool_action_hash(), 0..4),
+            arb_agent(),
+        )
+            .prop_map(|(title, posts, author)| {
+                let series = Series {
+                    title,
+                    description: String::new(),
+                    posts,
+                    author,
+                };
+                (Some(5), Entry::try_from(series).unwrap())
+            }),
+        1 => any::<[u8; 32]>().prop_map(|key| {
+            (Some(6), Entry::try_from(EncryptionKey { key: key.into() }).unwrap())
+        }),
+        1 => (
+            pool_action_hash(),
+            arb_agent(),
+            any::<u8>(),
+            arb_post(),
+            pool_action_hash(),
+        )
+            .prop_map(|(original_hash, author, signature, post, imported_hash)| {
+                let revision = create(author, 3, 1_000_000);
+                let provenance = Provenance {
+                    original_hash,
+                    revision: SignedActionHashed::with_presigned(
+                        ActionHashed::with_pre_hashed(Action::Create(revision), action_hash(1)),
+                        Signature([signature; 64]),
+                    ),
+                    revision_entry: Entry::try_from(post).unwrap(),
+                    lineage: vec![],
+                    imported_hash,
+                };
+                (Some(7), Entry::try_from(provenance).unwrap())
+            }),
+        // Malformed entries
+        1 => prop::collection::vec(any::<u8>(), 0..32).prop_map(|bytes| {
+            (Some(0), Entry::App(AppEntryBytes(SerializedBytes::from(UnsafeBytes::from(bytes)))))
+        }),
+        1 => arb_agent().prop_map(|agent| (None, Entry::Agent(agent))),
+    ]
+}
+
+fn arb_entry_type() -> impl Strategy<Value = EntryType> {
+    prop_oneof![
+        4 => (0..UnitEntryTypes::ENUM_LEN + 2, 0u8..2, any::<bool>()).prop_map(
+            |(entry_index, zome_index, public)| {
+                EntryType::App(AppEntryDef::new(
+                    entry_index.into(),
+                    zome_index.into(),
+                    if public {
+                        EntryVisibility::Public
+                    } else {
+                        EntryVisibility::Private
+                    },
+                ))
+            }
+        ),
+        1 => Just(EntryType::AgentPubKey),
+        1 => Just(EntryType::CapClaim),
+        1 => Just(EntryType::CapGrant),
+    ]
+}
+
+/// An entry with the entry type it declares, which most of the time matches its content
+fn arb_typed_entry() -> impl Strategy<Value = (EntryType, Entry)> {
+    (arb_entry(), arb_entry_type(), prop::bool::weighted(0.8)).prop_map(
+        |((index, entry), other_type, matching)| {
+            let entry_type = match index {
+                Some(index) if matching => EntryType::App(AppEntryDef::new(
+                    index.into(),
+                    0.into(),
+                    EntryVisibility::Public,
+                )),
+                None if matching => EntryType::AgentPubKey,
+                _ => other_type,
+            };
+            (entry_type, entry)
+        },
+    )
+}
+
+fn arb_linkable_hash() -> impl Strategy<Value = AnyLinkableHash> {
+    prop_oneof![
+        3 => pool_action_hash().prop_map(AnyLinkableHash::from),
+        2 => arb_agent().prop_map(AnyLinkableHash::from),
+        1 => pool_entry_hash().prop_map(AnyLinkableHash::from),
+    ]
+}
+
+fn arb_tag() -> impl Strategy<Value = LinkTag> {
+    prop_oneof![
+        prop::collection::vec(any::<u8>(), 0..16).prop_map(LinkTag::new),
+        pool_action_hash().prop_map(|delete_hash| {
+            LinkTag::new(
+                SerializedBytes::try_from(RestorationTag { delete_hash })
+                    .unwrap()
+                    .bytes()
+                    .clone(),
+            )
+        }),
+        prop::option::of(arb_timestamp()).prop_map(|expires_at| {
+            LinkTag::new(
+                SerializedBytes::try_from(PinTag { expires_at })
+                    .unwrap()
+                    .bytes()
+                    .clone(),
+            )
+        }),
+        arb_post().prop_map(|post| encode_link_tag(PostTag::new(&post)).unwrap()),
+        arb_comment().prop_map(|comment| encode_link_tag(CommentTag::new(&comment)).unwrap()),
+    ]
+}
+
+fn arb_create() -> impl Strategy<Value = (Create, Entry)> {
+    (
+        arb_agent(),
+        0u32..20,
+        arb_timestamp(),
+        arb_typed_entry(),
+        pool_entry_hash(),
+    )
+        .prop_map(
+            |(author, action_seq, timestamp, (entry_type, entry), entry_hash)| {
+                let create = Create {
+                    author,
+                    timestamp,
+                    action_seq,
+                    prev_action: action_hash(0),
+                    entry_type,
+                    entry_hash,
+                    weight: Default::default(),
+                };
+                (create, entry)
+            },
+        )
+}
+
+fn arb_update() -> impl Strategy<Value = (Update, Entry)> {
+    (arb_create(), pool_action_hash(), pool_entry_hash()).prop_map(
+        |((create, entry), original_action_address, original_entry_address)| {
+            let update = Update {
+                author: create.author,
+                timestamp: create.timestamp,
+                action_seq: create.action_seq,
+                prev_action: create.prev_action,
+                original_action_address,
+                original_entry_address,
+                entry_type: create.entry_type,
+                entry_hash: create.entry_hash,
+                weight: Default::default(),
+            };
+            (update, entry)
+        },
+    )
+}
+
+fn arb_delete() -> impl Strategy<Value = Delete> {
+    (
+        arb_agent(),
+        arb_timestamp(),
+        pool_action_hash(),
+        pool_entry_hash(),
+    )
+        .prop_map(
+            |(author, timestamp, deletes_address, deletes_entry_address)| Delete {
+                author,
+                timestamp,
+                action_seq: 10,
+                prev_action: action_hash(0),
+                deletes_address,
+                deletes_entry_address,
+                weight: Default::default(),
+            },
+        )
+}
+
+fn arb_create_link() -> impl Strategy<Value = CreateLink> {
+    (
+        arb_agent(),
+        arb_timestamp(),
+        arb_linkable_hash(),
+        arb_linkable_hash(),
+        0u8..2,
+        0..LinkTypes::ENUM_LEN + 2,
+        arb_tag(),
+    )
+        .prop_map(
+            |(author, timestamp, base_address, target_address, zome_index, link_type, tag)| {
+                CreateLink {
+                    author,
+                    timestamp,
+                    action_seq: 10,
+                    prev_action: action_hash(0),
+                    base_address,
+                    target_address,
+                    zome_index: zome_index.into(),
+                    link_type: link_type.into(),
+                    tag,
+                    weight: Default::default(),
+                }
+            },
+        )
+}
+
+fn arb_delete_link() -> impl Strategy<Value = (DeleteLink, CreateLink)> {
+    (
+        arb_agent(),
+        arb_linkable_hash(),
+        pool_action_hash(),
+        arb_create_link(),
+    )
+        .prop_map(|(author, base_address, link_add_address, create_link)| {
+            let delete_link = DeleteLink {
+                author,
+                timestamp: Timestamp::from_micros(5_000_000),
+                action_seq: 11,
+                prev_action: action_hash(0),
+                base_address,
+                link_add_address,
+            };
+            (delete_link, create_link)
+        })
+}
+
+fn signed<T>(content: T) -> SignedHashed<T>
+where
+    T: HashableContent<HashType = holo_hash::hash_type::Action>,
+{
+    SignedHashed::with_presigned(
+        HoloHashed::with_pre_hashed(content, action_hash(0xAA)),
+        Signature([0; 64]),
+    )
+}
+
+/// Any action, with the entry it creates if it is an entry creation
+fn arb_action() -> impl Strategy<Value = (Action, Option<Entry>)> {
+    prop_oneof![
+        3 => arb_create().prop_map(|(create, entry)| (Action::Create(create), Some(entry))),
+        2 => arb_update().prop_map(|(update, entry)| (Action::Update(update), Some(entry))),
+        1 => arb_delete().prop_map(|delete| (Action::Delete(delete), None)),
+        2 => arb_create_link().prop_map(|create_link| (Action::CreateLink(create_link), None)),
+        1 => arb_delete_link().prop_map(|(delete_link, _)| (Action::DeleteLink(delete_link), None)),
+    ]
+}
+
+fn arb_host() -> impl Strategy<Value = FakeHost> {
+    (
+        prop::collection::vec(arb_action(), POOL_SIZE as usize),
+        prop::collection::vec(arb_entry(), POOL_SIZE as usize),
+        prop::option::of(arb_agent()),
+        prop::collection::vec(arb_agent(), 0..2),
+        prop::option::of(0i64..10),
+    )
+        .prop_map(
+            |(actions, entries, progenitor, moderators, restore_grace_period_secs)| {
+                let records = actions
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, (mut action, entry))| {
+                        let hash = action_hash(index as u8 + 1);
+                        // Actions can only reference actions that existed before them,
+                        // so the updates in a DHT never form a cycle
+                        if let Action::Update(update) = &mut action {
+                            if update.original_action_address >= hash {
+                                update.original_action_address = action_hash(POOL_SIZE + 1);
+                            }
+                        }
+                        let signed_action = SignedActionHashed::with_presigned(
+                            ActionHashed::with_pre_hashed(action, hash.clone()),
+                            Signature([0; 64]),
+                        );
+                        (hash, Record::new(signed_action, entry))
+                    })
+                    .collect();
+                let entries = entries
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, (_, entry))| (entry_hash(index as u8 + 1), entry))
+                    .collect();
+                FakeHost {
+                    records,
+                    entries,
+                    properties: DnaProperties {
+                        restore_grace_period_secs,
+                        progenitor: progenitor.map(AgentPubKeyB64::from),
+                        moderators: moderators.into_iter().map(AgentPubKeyB64::from).collect(),
+                        max_file_size: Some(64),
+                    },
+                }
+            },
+        )
+}
+
+fn arb_op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        arb_action().prop_map(|(action, entry)| {
+            let signed_action = SignedActionHashed::with_presigned(
+                ActionHashed::with_pre_hashed(action, action_hash(0xAA)),
+                Signature([0; 64]),
+            );
+            Op::StoreRecord(StoreRecord {
+                record: Record::new(signed_action, entry),
+            })
+        }),
+        arb_create().prop_map(|(create, entry)| {
+            Op::StoreEntry(StoreEntry {
+                action: signed(EntryCreationAction::Create(create)),
+                entry,
+            })
+        }),
+        arb_update().prop_map(|(update, entry)| {
+            Op::StoreEntry(StoreEntry {
+                action: signed(EntryCreationAction::Update(update)),
+                entry,
+            })
+        }),
+        (arb_update(), any::<bool>()).prop_map(|((update, entry), with_entry)| {
+            Op::RegisterUpdate(RegisterUpdate {
+                update: signed(update),
+                new_entry: with_entry.then_some(entry),
+            })
+        }),
+        arb_delete().prop_map(|delete| {
+            Op::RegisterDelete(RegisterDelete {
+                delete: signed(delete),
+            })
+        }),
+        (arb_action(), any::<bool>()).prop_map(|((action, entry), cached)| {
+            Op::RegisterAgentActivity(RegisterAgentActivity {
+                action: SignedActionHashed::with_presigned(
+                    ActionHashed::with_pre_hashed(action, action_hash(0xAA)),
+                    Signature([0; 64]),
+                ),
+                cached_entry: entry.filter(|_| cached),
+            })
+        }),
+        arb_create_link().prop_map(|create_link| {
+            Op::RegisterCreateLink(RegisterCreateLink {
+                create_link: signed(create_link),
+            })
+        }),
+        arb_delete_link().prop_map(|(delete_link, create_link)| {
+            Op::RegisterDeleteLink(RegisterDeleteLink {
+                delete_link: signed(delete_link),
+                create_link,
+            })
+        }),
+    ]
+}
+
+#[test]
+fn the_fake_host_validates_a_new_post() {
+    let post = post(agent(1));
+    let host = FakeHost {
+        records: HashMap::new(),
+        entries: HashMap::new(),
+        properties: DnaProperties::default(),
+    };
+    set_hdi(host);
+    let mut create = create(agent(1), 3, 1_000_000);
+    create.entry_type = EntryType::try_from(UnitEntryTypes::Post).unwrap();
+    let op = Op::StoreEntry(StoreEntry {
+        action: signed(EntryCreationAction::Create(create)),
+        entry: Entry::try_from(post).unwrap(),
+    });
+    assert_eq!(validate(op), Ok(ValidateCallbackResult::Valid));
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1024))]
+
+    #[test]
+    fn validation_never_panics_and_is_deterministic(host in arb_host(), op in arb_op()) {
+        set_hdi(host.clone());
+        let first = validate(op.clone());
+        set_hdi(host);
+        let second = validate(op);
+        prop_assert_eq!(first, second);
+    }
+}
@@ -1,9 +1,39 @@
+pub mod backup;
+pub use backup::*;
 pub mod comment;
 pub use comment::*;
+pub mod comment_lock;
+pub use comment_lock::*;
+pub mod content_format;
+pub use content_format::*;
+pub mod error;
+pub use error::*;
+pub mod file_storage;
+pub use file_storage::*;
+pub mod link_tag;
+pub use link_tag::*;
+pub mod migration;
+pub use migration::*;
+pub mod pinned_posts;
+pub use pinned_posts::*;
 pub mod post;
 use hdi::prelude::*;
 
 pub use post::*;
+pub mod private_post;
+pub use private_post::*;
+pub mod properties;
+pub use properties::*;
+pub mod revision;
+pub use revision::*;
+pub mod schema;
+pub use schema::*;
+pub mod series;
+pub use series::*;
+#[cfg(test)]
+mod fuzz;
+#[cfg(test)]
+mod test_utils;
 
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -12,6 +42,12 @@ pub use post::*;
 pub enum EntryTypes {
     Post(Post),
     Comment(Comment),
+    CommentLock(CommentLock),
+    FileChunk(FileChunk),
+    FileManifest(FileManifest),
+    Series(Series),
+    EncryptionKey(EncryptionKey),
+    Provenance(Provenance),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -22,6 +58,18 @@ pub enum LinkTypes {
     AllPosts,
     PostToComments,
     AuthorToComments,
+    PostRestorations,
+    AuthorToUnlistedPosts,
+    PostToCommentLocks,
+    PinnedPosts,
+    AuthorToSeries,
+    PostToSeries,
+    AgentToEncryptionKeys,
+    OriginalToProvenance,
+    ImportedToProvenance,
+    ExportedToImported,
+    PostRevisionTags,
+    CommentRevisionTags,
 }
 
 // Validation you perform during the genesis process. Nobody else on the network performs it, only you.
@@ -60,6 +108,10 @@ pub fn validate_agent_joining(
 // You can read more about validation here: https://docs.rs/hdi/latest/hdi/index.html#data-validation
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    let result = validate_schema_version(&op)?;
+    if result != ValidateCallbackResult::Valid {
+        return Ok(result);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, action } => match app_entry {
@@ -69,6 +121,26 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::Comment(comment) => {
                     validate_create_comment(EntryCreationAction::Create(action), comment)
                 }
+                EntryTypes::CommentLock(comment_lock) => {
+                    validate_create_comment_lock(EntryCreationAction::Create(action), comment_lock)
+                }
+                EntryTypes::FileChunk(file_chunk) => {
+                    validate_create_file_chunk(EntryCreationAction::Create(action), file_chunk)
+                }
+                EntryTypes::FileManifest(file_manifest) => validate_create_file_manifest(
+                    EntryCreationAction::Create(action),
+                    file_manifest,
+                ),
+                EntryTypes::Series(series) => {
+                    validate_create_series(EntryCreationAction::Create(action), series)
+                }
+                EntryTypes::EncryptionKey(encryption_key) => validate_create_encryption_key(
+                    EntryCreationAction::Create(action),
+                    encryption_key,
+                ),
+                EntryTypes::Provenance(provenance) => {
+                    validate_create_provenance(EntryCreationAction::Create(action), provenance)
+                }
             },
             OpEntry::UpdateEntry {
                 app_entry, action, ..
@@ -79,6 +151,26 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::Comment(comment) => {
                     validate_create_comment(EntryCreationAction::Update(action), comment)
                 }
+                EntryTypes::CommentLock(comment_lock) => {
+                    validate_create_comment_lock(EntryCreationAction::Update(action), comment_lock)
+                }
+                EntryTypes::FileChunk(file_chunk) => {
+                    validate_create_file_chunk(EntryCreationAction::Update(action), file_chunk)
+                }
+                EntryTypes::FileManifest(file_manifest) => validate_create_file_manifest(
+                    EntryCreationAction::Update(action),
+                    file_manifest,
+                ),
+                EntryTypes::Series(series) => {
+                    validate_create_series(EntryCreationAction::Update(action), series)
+                }
+                EntryTypes::EncryptionKey(encryption_key) => validate_create_encryption_key(
+                    EntryCreationAction::Update(action),
+                    encryption_key,
+                ),
+                EntryTypes::Provenance(provenance) => {
+                    validate_create_provenance(EntryCreationAction::Update(action), provenance)
+                }
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -90,9 +182,10 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 let original_create_action = match EntryCreationAction::try_from(original_action) {
                     Ok(action) => action,
                     Err(e) => {
-                        return Ok(ValidateCallbackResult::Invalid(format!(
+                        return Ok(BlogError::Invalid(format!(
                             "Expected to get EntryCreationAction from Action: {e:?}"
-                        )));
+                        ))
+                        .into());
                     }
                 };
                 match app_entry {
@@ -102,9 +195,10 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         let original_comment = match Comment::try_from(original_app_entry) {
                             Ok(entry) => entry,
                             Err(e) => {
-                                return Ok(ValidateCallbackResult::Invalid(format!(
+                                return Ok(BlogError::Invalid(format!(
                                     "Expected to get Comment from Record: {e:?}"
-                                )));
+                                ))
+                                .into());
                             }
                         };
                         validate_update_comment(
@@ -120,13 +214,131 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         let original_post = match Post::try_from(original_app_entry) {
                             Ok(entry) => entry,
                             Err(e) => {
-                                return Ok(ValidateCallbackResult::Invalid(format!(
+                                return Ok(BlogError::Invalid(format!(
                                     "Expected to get Post from Record: {e:?}"
-                                )));
+                                ))
+                                .into());
                             }
                         };
                         validate_update_post(action, post, original_create_action, original_post)
                     }
+                    EntryTypes::CommentLock(comment_lock) => {
+                        let original_app_entry =
+                            must_get_valid_record(action.clone().original_action_address)?;
+                        let original_comment_lock = match CommentLock::try_from(original_app_entry)
+                        {
+                            Ok(entry) => entry,
+                            Err(e) => {
+                                return Ok(BlogError::Invalid(format!(
+                                    "Expected to get CommentLock from Record: {e:?}"
+                                ))
+                                .into());
+                            }
+                        };
+                        validate_update_comment_lock(
+                            action,
+                            comment_lock,
+                            original_create_action,
+                            original_comment_lock,
+                        )
+                    }
+                    EntryTypes::FileChunk(file_chunk) => {
+                        let original_app_entry =
+                            must_get_valid_record(action.clone().original_action_address)?;
+                        let original_file_chunk = match FileChunk::try_from(original_app_entry) {
+                            Ok(entry) => entry,
+                            Err(e) => {
+                                return Ok(BlogError::Invalid(format!(
+                                    "Expected to get FileChunk from Record: {e:?}"
+                                ))
+                                .into());
+                            }
+                        };
+                        validate_update_file_chunk(
+                            action,
+                            file_chunk,
+                            original_create_action,
+                            original_file_chunk,
+                        )
+                    }
+                    EntryTypes::FileManifest(file_manifest) => {
+                        let original_app_entry =
+                            must_get_valid_record(action.clone().original_action_address)?;
+                        let original_file_manifest =
+                            match FileManifest::try_from(original_app_entry) {
+                                Ok(entry) => entry,
+                                Err(e) => {
+                                    return Ok(BlogError::Invalid(format!(
+                                        "Expected to get FileManifest from Record: {e:?}"
+                                    ))
+                                    .into());
+                                }
+                            };
+                        validate_update_file_manifest(
+                            action,
+                            file_manifest,
+                            original_create_action,
+                            original_file_manifest,
+                        )
+                    }
+                    EntryTypes::Series(series) => {
+                        let original_app_entry =
+                            must_get_valid_record(action.clone().original_action_address)?;
+                        let original_series = match Series::try_from(original_app_entry) {
+                            Ok(entry) => entry,
+                            Err(e) => {
+                                return Ok(BlogError::Invalid(format!(
+                                    "Expected to get Series from Record: {e:?}"
+                                ))
+                                .into());
+                            }
+                        };
+                        validate_update_series(
+                            action,
+                            series,
+                            original_create_action,
+                            original_series,
+                        )
+                    }
+                    EntryTypes::EncryptionKey(encryption_key) => {
+                        let original_app_entry =
+                            must_get_valid_record(action.clone().original_action_address)?;
+                        let original_encryption_key =
+                            match EncryptionKey::try_from(original_app_entry) {
+                                Ok(entry) => entry,
+                                Err(e) => {
+                                    return Ok(BlogError::Invalid(format!(
+                                        "Expected to get EncryptionKey from Record: {e:?}"
+                                    ))
+                                    .into());
+                                }
+                            };
+                        validate_update_encryption_key(
+                            action,
+                            encryption_key,
+                            original_create_action,
+                            original_encryption_key,
+                        )
+                    }
+                    EntryTypes::Provenance(provenance) => {
+                        let original_app_entry =
+                            must_get_valid_record(action.clone().original_action_address)?;
+                        let original_provenance = match Provenance::try_from(original_app_entry) {
+                            Ok(entry) => entry,
+                            Err(e) => {
+                                return Ok(BlogError::Invalid(format!(
+                                    "Expected to get Provenance from Record: {e:?}"
+                                ))
+                                .into());
+                            }
+                        };
+                        validate_update_provenance(
+                            action,
+                            provenance,
+                            original_create_action,
+                            original_provenance,
+                        )
+                    }
                 }
             }
             _ => Ok(ValidateCallbackResult::Valid),
@@ -138,9 +350,10 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             let original_action = match EntryCreationAction::try_from(original_record_action) {
                 Ok(action) => action,
                 Err(e) => {
-                    return Ok(ValidateCallbackResult::Invalid(format!(
+                    return Ok(BlogError::Invalid(format!(
                         "Expected to get EntryCreationAction from Action: {e:?}"
-                    )));
+                    ))
+                    .into());
                 }
             };
             let app_entry_type = match original_action.entry_type() {
@@ -152,9 +365,10 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             let entry = match original_record.entry().as_option() {
                 Some(entry) => entry,
                 None => {
-                    return Ok(ValidateCallbackResult::Invalid(
+                    return Ok(BlogError::Invalid(
                         "Original record for a delete must contain an entry".to_string(),
-                    ));
+                    )
+                    .into());
                 }
             };
             let original_app_entry = match EntryTypes::deserialize_from_type(
@@ -164,10 +378,11 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             )? {
                 Some(app_entry) => app_entry,
                 None => {
-                    return Ok(ValidateCallbackResult::Invalid(
+                    return Ok(BlogError::Invalid(
                         "Original app entry must be one of the defined entry types for this zome"
                             .to_string(),
-                    ));
+                    )
+                    .into());
                 }
             };
             match original_app_entry {
@@ -181,6 +396,38 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     original_action,
                     original_post,
                 ),
+                EntryTypes::CommentLock(original_comment_lock) => validate_delete_comment_lock(
+                    delete_entry.clone().action,
+                    original_action,
+                    original_comment_lock,
+                ),
+                EntryTypes::FileChunk(original_file_chunk) => validate_delete_file_chunk(
+                    delete_entry.clone().action,
+                    original_action,
+                    original_file_chunk,
+                ),
+                EntryTypes::FileManifest(original_file_manifest) => validate_delete_file_manifest(
+                    delete_entry.clone().action,
+                    original_action,
+                    original_file_manifest,
+                ),
+                EntryTypes::Series(original_series) => validate_delete_series(
+                    delete_entry.clone().action,
+                    original_action,
+                    original_series,
+                ),
+                EntryTypes::EncryptionKey(original_encryption_key) => {
+                    validate_delete_encryption_key(
+                        delete_entry.clone().action,
+                        original_action,
+                        original_encryption_key,
+                    )
+                }
+                EntryTypes::Provenance(original_provenance) => validate_delete_provenance(
+                    delete_entry.clone().action,
+                    original_action,
+                    original_provenance,
+                ),
             }
         }
         FlatOp::RegisterCreateLink {
@@ -205,6 +452,60 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             LinkTypes::AuthorToComments => {
                 validate_create_link_author_to_comments(action, base_address, target_address, tag)
             }
+            LinkTypes::PostRestorations => {
+                validate_create_link_post_restorations(action, base_address, target_address, tag)
+            }
+            LinkTypes::AuthorToUnlistedPosts => validate_create_link_author_to_unlisted_posts(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::PostToCommentLocks => validate_create_link_post_to_comment_locks(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::PinnedPosts => {
+                validate_create_link_pinned_posts(action, base_address, target_address, tag)
+            }
+            LinkTypes::AuthorToSeries => {
+                validate_create_link_author_to_series(action, base_address, target_address, tag)
+            }
+            LinkTypes::PostToSeries => {
+                validate_create_link_post_to_series(action, base_address, target_address, tag)
+            }
+            LinkTypes::AgentToEncryptionKeys => validate_create_link_agent_to_encryption_keys(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::OriginalToProvenance => validate_create_link_original_to_provenance(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::ImportedToProvenance => validate_create_link_imported_to_provenance(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::ExportedToImported => {
+                validate_create_link_exported_to_imported(action, base_address, target_address, tag)
+            }
+            LinkTypes::PostRevisionTags => {
+                validate_create_link_post_revision_tags(action, base_address, target_address, tag)
+            }
+            LinkTypes::CommentRevisionTags => validate_create_link_comment_revision_tags(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
         },
         FlatOp::RegisterDeleteLink {
             link_type,
@@ -249,6 +550,90 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 target_address,
                 tag,
             ),
+            LinkTypes::PostRestorations => validate_delete_link_post_restorations(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::AuthorToUnlistedPosts => validate_delete_link_author_to_unlisted_posts(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::PostToCommentLocks => validate_delete_link_post_to_comment_locks(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::PinnedPosts => validate_delete_link_pinned_posts(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::AuthorToSeries => validate_delete_link_author_to_series(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::PostToSeries => validate_delete_link_post_to_series(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::AgentToEncryptionKeys => validate_delete_link_agent_to_encryption_keys(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::OriginalToProvenance => validate_delete_link_original_to_provenance(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::ImportedToProvenance => validate_delete_link_imported_to_provenance(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::ExportedToImported => validate_delete_link_exported_to_imported(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::PostRevisionTags => validate_delete_link_post_revision_tags(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::CommentRevisionTags => validate_delete_link_comment_revision_tags(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
         },
         FlatOp::StoreRecord(store_record) => {
             match store_record {
@@ -262,6 +647,27 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     EntryTypes::Comment(comment) => {
                         validate_create_comment(EntryCreationAction::Create(action), comment)
                     }
+                    EntryTypes::CommentLock(comment_lock) => validate_create_comment_lock(
+                        EntryCreationAction::Create(action),
+                        comment_lock,
+                    ),
+                    EntryTypes::FileChunk(file_chunk) => {
+                        validate_create_file_chunk(EntryCreationAction::Create(action), file_chunk)
+                    }
+                    EntryTypes::FileManifest(file_manifest) => validate_create_file_manifest(
+                        EntryCreationAction::Create(action),
+                        file_manifest,
+                    ),
+                    EntryTypes::Series(series) => {
+                        validate_create_series(EntryCreationAction::Create(action), series)
+                    }
+                    EntryTypes::EncryptionKey(encryption_key) => validate_create_encryption_key(
+                        EntryCreationAction::Create(action),
+                        encryption_key,
+                    ),
+                    EntryTypes::Provenance(provenance) => {
+                        validate_create_provenance(EntryCreationAction::Create(action), provenance)
+                    }
                 },
                 // Complementary validation to the `RegisterUpdate` Op, in which the record itself is validated
                 // If you want to optimize performance, you can remove the validation for an entry type here and keep it in `StoreEntry` and in `RegisterUpdate`
@@ -278,10 +684,11 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         Action::Create(create) => EntryCreationAction::Create(create),
                         Action::Update(update) => EntryCreationAction::Update(update),
                         _ => {
-                            return Ok(ValidateCallbackResult::Invalid(
+                            return Ok(BlogError::Invalid(
                                 "Original action for an update must be a Create or Update action"
                                     .to_string(),
-                            ));
+                            )
+                            .into());
                         }
                     };
                     match app_entry {
@@ -299,10 +706,8 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                                     Some(post) => post,
                                     None => {
                                         return Ok(
-                                            ValidateCallbackResult::Invalid(
-                                                "The updated entry type must be the same as the original entry type"
-                                                    .to_string(),
-                                            ),
+                                            BlogError::Invalid("The updated entry type must be the same as the original entry type"
+                                                    .to_string()).into(),
                                         );
                                     }
                                 };
@@ -325,10 +730,8 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                                     Some(comment) => comment,
                                     None => {
                                         return Ok(
-                                            ValidateCallbackResult::Invalid(
-                                                "The updated entry type must be the same as the original entry type"
-                                                    .to_string(),
-                                            ),
+                                            BlogError::Invalid("The updated entry type must be the same as the original entry type"
+                                                    .to_string()).into(),
                                         );
                                     }
                                 };
@@ -342,6 +745,184 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                                 Ok(result)
                             }
                         }
+                        EntryTypes::CommentLock(comment_lock) => {
+                            let result = validate_create_comment_lock(
+                                EntryCreationAction::Update(action.clone()),
+                                comment_lock.clone(),
+                            )?;
+                            if let ValidateCallbackResult::Valid = result {
+                                let original_comment_lock: Option<CommentLock> = original_record
+                                    .entry()
+                                    .to_app_option()
+                                    .map_err(|e| wasm_error!(e))?;
+                                let original_comment_lock = match original_comment_lock {
+                                    Some(comment_lock) => comment_lock,
+                                    None => {
+                                        return Ok(
+                                            BlogError::Invalid("The updated entry type must be the same as the original entry type"
+                                                    .to_string()).into(),
+                                        );
+                                    }
+                                };
+                                validate_update_comment_lock(
+                                    action,
+                                    comment_lock,
+                                    original_action,
+                                    original_comment_lock,
+                                )
+                            } else {
+                                Ok(result)
+                            }
+                        }
+                        EntryTypes::FileChunk(file_chunk) => {
+                            let result = validate_create_file_chunk(
+                                EntryCreationAction::Update(action.clone()),
+                                file_chunk.clone(),
+                            )?;
+                            if let ValidateCallbackResult::Valid = result {
+                                let original_file_chunk: Option<FileChunk> = original_record
+                                    .entry()
+                                    .to_app_option()
+                                    .map_err(|e| wasm_error!(e))?;
+                                let original_file_chunk = match original_file_chunk {
+                                    Some(file_chunk) => file_chunk,
+                                    None => {
+                                        return Ok(
+                                            BlogError::Invalid("The updated entry type must be the same as the original entry type"
+                                                    .to_string()).into(),
+                                        );
+                                    }
+                                };
+                                validate_update_file_chunk(
+                                    action,
+                                    file_chunk,
+                                    original_action,
+                                    original_file_chunk,
+                                )
+                            } else {
+                                Ok(result)
+                            }
+                        }
+                        EntryTypes::FileManifest(file_manifest) => {
+                            let result = validate_create_file_manifest(
+                                EntryCreationAction::Update(action.clone()),
+                                file_manifest.clone(),
+                            )?;
+                            if let ValidateCallbackResult::Valid = result {
+                                let original_file_manifest: Option<FileManifest> = original_record
+                                    .entry()
+                                    .to_app_option()
+                                    .map_err(|e| wasm_error!(e))?;
+                                let original_file_manifest = match original_file_manifest {
+                                    Some(file_manifest) => file_manifest,
+                                    None => {
+                                        return Ok(
+                                            BlogError::Invalid("The updated entry type must be the same as the original entry type"
+                                                    .to_string()).into(),
+                                        );
+                                    }
+                                };
+                                validate_update_file_manifest(
+                                    action,
+                                    file_manifest,
+                                    original_action,
+                                    original_file_manifest,
+                                )
+                            } else {
+                                Ok(result)
+                            }
+                        }
+                        EntryTypes::Series(series) => {
+                            let result = validate_create_series(
+                                EntryCreationAction::Update(action.clone()),
+                                series.clone(),
+                            )?;
+                            if let ValidateCallbackResult::Valid = result {
+                                let original_series: Option<Series> = original_record
+                                    .entry()
+                                    .to_app_option()
+                                    .map_err(|e| wasm_error!(e))?;
+                                let original_series = match original_series {
+                                    Some(series) => series,
+                                    None => {
+                                        return Ok(
+                                            BlogError::Invalid("The updated entry type must be the same as the original entry type"
+                                                    .to_string()).into(),
+                                        );
+                                    }
+                                };
+                                validate_update_series(
+                                    action,
+                                    series,
+                                    original_action,
+                                    original_series,
+                                )
+                            } else {
+                                Ok(result)
+                            }
+                        }
+                        EntryTypes::EncryptionKey(encryption_key) => {
+                            let result = validate_create_encryption_key(
+                                EntryCreationAction::Update(action.clone()),
+                                encryption_key.clone(),
+                            )?;
+                            if let ValidateCallbackResult::Valid = result {
+                                let original_encryption_key: Option<EncryptionKey> =
+                                    original_record
+                                        .entry()
+                                        .to_app_option()
+                                        .map_err(|e| wasm_error!(e))?;
+                                let original_encryption_key = match original_encryption_key {
+                                    Some(encryption_key) => encryption_key,
+                                    None => {
+                                        return Ok(
+                                            BlogError::Invalid("The updated entry type must be the same as the original entry type"
+                                                    .to_string()).into(),
+                                        );
+                                    }
+                                };
+                                validate_update_encryption_key(
+                                    action,
+                                    encryption_key,
+                                    original_action,
+                                    original_encryption_key,
+                                )
+                            } else {
+                                Ok(result)
+                            }
+                        }
+                        EntryTypes::Provenance(provenance) => {
+                            let result = validate_create_provenance(
+                                EntryCreationAction::Update(action.clone()),
+                                provenance.clone(),
+                            )?;
+                            if let ValidateCallbackResult::Valid = result {
+                                let original_provenance: Option<Provenance> = original_record
+                                    .entry()
+                                    .to_app_option()
+                                    .map_err(|e| wasm_error!(e))?;
+                                let original_provenance = match original_provenance {
+                                    Some(provenance) => provenance,
+                                    None => {
+                                        return Ok(
+                                            BlogError::Invalid(
+                                                "The updated entry type must be the same as the original entry type"
+                                                    .to_string(),
+                                            )
+                                            .into(),
+                                        );
+                                    }
+                                };
+                                validate_update_provenance(
+                                    action,
+                                    provenance,
+                                    original_action,
+                                    original_provenance,
+                                )
+                            } else {
+                                Ok(result)
+                            }
+                        }
                     }
                 }
                 // Complementary validation to the `RegisterDelete` Op, in which the record itself is validated
@@ -358,10 +939,11 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         Action::Create(create) => EntryCreationAction::Create(create),
                         Action::Update(update) => EntryCreationAction::Update(update),
                         _ => {
-                            return Ok(ValidateCallbackResult::Invalid(
+                            return Ok(BlogError::Invalid(
                                 "Original action for a delete must be a Create or Update action"
                                     .to_string(),
-                            ));
+                            )
+                            .into());
                         }
                     };
                     let app_entry_type = match original_action.entry_type() {
@@ -373,9 +955,10 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     let entry = match original_record.entry().as_option() {
                         Some(entry) => entry,
                         None => {
-                            return Ok(ValidateCallbackResult::Invalid(
+                            return Ok(BlogError::Invalid(
                                 "Original record for a delete must contain an entry".to_string(),
-                            ));
+                            )
+                            .into());
                         }
                     };
                     let original_app_entry = match EntryTypes::deserialize_from_type(
@@ -386,10 +969,8 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         Some(app_entry) => app_entry,
                         None => {
                             return Ok(
-                                ValidateCallbackResult::Invalid(
-                                    "Original app entry must be one of the defined entry types for this zome"
-                                        .to_string(),
-                                ),
+                                BlogError::Invalid("Original app entry must be one of the defined entry types for this zome"
+                                        .to_string()).into(),
                             );
                         }
                     };
@@ -400,6 +981,36 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         EntryTypes::Comment(original_comment) => {
                             validate_delete_comment(action, original_action, original_comment)
                         }
+                        EntryTypes::CommentLock(original_comment_lock) => {
+                            validate_delete_comment_lock(
+                                action,
+                                original_action,
+                                original_comment_lock,
+                            )
+                        }
+                        EntryTypes::FileChunk(original_file_chunk) => {
+                            validate_delete_file_chunk(action, original_action, original_file_chunk)
+                        }
+                        EntryTypes::FileManifest(original_file_manifest) => {
+                            validate_delete_file_manifest(
+                                action,
+                                original_action,
+                                original_file_manifest,
+                            )
+                        }
+                        EntryTypes::Series(original_series) => {
+                            validate_delete_series(action, original_action, original_series)
+                        }
+                        EntryTypes::EncryptionKey(original_encryption_key) => {
+                            validate_delete_encryption_key(
+                                action,
+                                original_action,
+                                original_encryption_key,
+                            )
+                        }
+                        EntryTypes::Provenance(original_provenance) => {
+                            validate_delete_provenance(action, original_action, original_provenance)
+                        }
                     }
                 }
                 // Complementary validation to the `RegisterCreateLink` Op, in which the record itself is validated
@@ -436,6 +1047,79 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         target_address,
                         tag,
                     ),
+                    LinkTypes::PostRestorations => validate_create_link_post_restorations(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    ),
+                    LinkTypes::AuthorToUnlistedPosts => {
+                        validate_create_link_author_to_unlisted_posts(
+                            action,
+                            base_address,
+                            target_address,
+                            tag,
+                        )
+                    }
+                    LinkTypes::PostToCommentLocks => validate_create_link_post_to_comment_locks(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    ),
+                    LinkTypes::PinnedPosts => {
+                        validate_create_link_pinned_posts(action, base_address, target_address, tag)
+                    }
+                    LinkTypes::AuthorToSeries => validate_create_link_author_to_series(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    ),
+                    LinkTypes::PostToSeries => validate_create_link_post_to_series(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    ),
+                    LinkTypes::AgentToEncryptionKeys => {
+                        validate_create_link_agent_to_encryption_keys(
+                            action,
+                            base_address,
+                            target_address,
+                            tag,
+                        )
+                    }
+                    LinkTypes::OriginalToProvenance => validate_create_link_original_to_provenance(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    ),
+                    LinkTypes::ImportedToProvenance => validate_create_link_imported_to_provenance(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    ),
+                    LinkTypes::ExportedToImported => validate_create_link_exported_to_imported(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    ),
+                    LinkTypes::PostRevisionTags => validate_create_link_post_revision_tags(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    ),
+                    LinkTypes::CommentRevisionTags => validate_create_link_comment_revision_tags(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    ),
                 },
                 // Complementary validation to the `RegisterDeleteLink` Op, in which the record itself is validated
                 // If you want to optimize performance, you can remove the validation for an entry type here and keep it in `RegisterDeleteLink`
@@ -449,10 +1133,11 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     let create_link = match record.action() {
                         Action::CreateLink(create_link) => create_link.clone(),
                         _ => {
-                            return Ok(ValidateCallbackResult::Invalid(
+                            return Ok(BlogError::Invalid(
                                 "The action that a DeleteLink deletes must be a CreateLink"
                                     .to_string(),
-                            ));
+                            )
+                            .into());
                         }
                     };
                     let link_type = match LinkTypes::from_type(
@@ -500,6 +1185,102 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                             create_link.target_address,
                             create_link.tag,
                         ),
+                        LinkTypes::PostRestorations => validate_delete_link_post_restorations(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        ),
+                        LinkTypes::AuthorToUnlistedPosts => {
+                            validate_delete_link_author_to_unlisted_posts(
+                                action,
+                                create_link.clone(),
+                                base_address,
+                                create_link.target_address,
+                                create_link.tag,
+                            )
+                        }
+                        LinkTypes::PostToCommentLocks => {
+                            validate_delete_link_post_to_comment_locks(
+                                action,
+                                create_link.clone(),
+                                base_address,
+                                create_link.target_address,
+                                create_link.tag,
+                            )
+                        }
+                        LinkTypes::PinnedPosts => validate_delete_link_pinned_posts(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        ),
+                        LinkTypes::AuthorToSeries => validate_delete_link_author_to_series(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        ),
+                        LinkTypes::PostToSeries => validate_delete_link_post_to_series(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        ),
+                        LinkTypes::AgentToEncryptionKeys => {
+                            validate_delete_link_agent_to_encryption_keys(
+                                action,
+                                create_link.clone(),
+                                base_address,
+                                create_link.target_address,
+                                create_link.tag,
+                            )
+                        }
+                        LinkTypes::OriginalToProvenance => {
+                            validate_delete_link_original_to_provenance(
+                                action,
+                                create_link.clone(),
+                                base_address,
+                                create_link.target_address,
+                                create_link.tag,
+                            )
+                        }
+                        LinkTypes::ImportedToProvenance => {
+                            validate_delete_link_imported_to_provenance(
+                                action,
+                                create_link.clone(),
+                                base_address,
+                                create_link.target_address,
+                                create_link.tag,
+                            )
+                        }
+                        LinkTypes::ExportedToImported => validate_delete_link_exported_to_imported(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        ),
+                        LinkTypes::PostRevisionTags => validate_delete_link_post_revision_tags(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        ),
+                        LinkTypes::CommentRevisionTags => {
+                            validate_delete_link_comment_revision_tags(
+                                action,
+                                create_link.clone(),
+                                base_address,
+                                create_link.target_address,
+                                create_link.tag,
+                            )
+                        }
                     }
                 }
                 OpRecord::CreatePrivateEntry { .. } => Ok(ValidateCallbackResult::Valid),
@@ -524,10 +1305,8 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         ) => validate_agent_joining(agent, membrane_proof),
                         _ => {
                             Ok(
-                                ValidateCallbackResult::Invalid(
-                                    "The previous action for a `CreateAgent` action must be an `AgentValidationPkg`"
-                                        .to_string(),
-                                ),
+                                BlogError::Invalid("The previous action for a `CreateAgent` action must be an `AgentValidationPkg`"
+                                        .to_string()).into(),
                             )
                         }
                     }
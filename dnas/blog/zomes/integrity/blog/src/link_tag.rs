@@ -0,0 +1,182 @@
+use hdi::prelude::*;
+
+use crate::{BlogError, Comment, Post, RuleResult};
+
+// The layout version that every new tag must be written with
+pub const LINK_TAG_VERSION: u8 = 1;
+
+// Link tags are small, so the strings they carry are cut to at most these many bytes
+pub const TAG_TITLE_MAX_BYTES: usize = 100;
+pub const TAG_EXCERPT_MAX_BYTES: usize = 200;
+
+/// Tag of the `AllPosts`, `AuthorToPosts` and `PostRevisionTags` links, describing the latest revision of
+/// the linked post when it was linked, so that lists of posts can be shown without getting every post
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct PostTag {
+    pub version: u8,
+    pub title: String,
+    pub author: AgentPubKey,
+    // Empty for private posts, whose content is only stored in the envelope
+    pub excerpt: String,
+}
+
+impl PostTag {
+    pub fn new(post: &Post) -> PostTag {
+        PostTag {
+            version: LINK_TAG_VERSION,
+            title: truncate(&post.name, TAG_TITLE_MAX_BYTES),
+            author: post.author.clone(),
+            excerpt: excerpt(&post.content),
+        }
+    }
+}
+
+/// Tag of the `PostToComments` links, describing the linked comment as it was created, and of the
+/// `CommentRevisionTags` links, describing it as it was last edited
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct CommentTag {
+    pub version: u8,
+    pub author: AgentPubKey,
+    pub excerpt: String,
+}
+
+impl CommentTag {
+    pub fn new(comment: &Comment) -> CommentTag {
+        CommentTag {
+            version: LINK_TAG_VERSION,
+            author: comment.author.clone(),
+            excerpt: excerpt(&comment.content),
+        }
+    }
+}
+
+// Cuts the text to the last character boundary that fits in `max_bytes`
+fn truncate(text: &str, max_bytes: usize) -> String {
+    let mut end = text.len().min(max_bytes);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text[..end].to_string()
+}
+
+// The start of the content on a single line
+fn excerpt(content: &str) -> String {
+    let line = content.split_whitespace().collect::<Vec<&str>>().join(" ");
+    truncate(&line, TAG_EXCERPT_MAX_BYTES)
+}
+
+pub fn encode_link_tag<T>(tag: T) -> ExternResult<LinkTag>
+where
+    T: TryInto<SerializedBytes, Error = SerializedBytesError>,
+{
+    let bytes: SerializedBytes = tag.try_into().map_err(|e| wasm_error!(e))?;
+    Ok(LinkTag::new(bytes.bytes().clone()))
+}
+
+pub fn decode_link_tag<T>(tag: LinkTag) -> Result<T, BlogError>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    T::try_from(SerializedBytes::from(UnsafeBytes::from(tag.into_inner())))
+        .map_err(|_| BlogError::MalformedLinkTag)
+}
+
+/// Checks that the tag of a link to a post is the one derived from the given revision of the post
+pub fn check_post_tag(tag: LinkTag, post: &Post) -> RuleResult {
+    let tag: PostTag = decode_link_tag(tag)?;
+    check_tag_version(tag.version)?;
+    if tag != PostTag::new(post) {
+        return Err(BlogError::Invalid(
+            "The tag of a link to a Post must describe that Post".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the tag of a link to a comment is the one derived from the comment
+pub fn check_comment_tag(tag: LinkTag, comment: &Comment) -> RuleResult {
+    let tag: CommentTag = decode_link_tag(tag)?;
+    check_tag_version(tag.version)?;
+    if tag != CommentTag::new(comment) {
+        return Err(BlogError::Invalid(
+            "The tag of a link to a Comment must describe that Comment".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_tag_version(version: u8) -> RuleResult {
+    if version != LINK_TAG_VERSION {
+        return Err(BlogError::Invalid(format!(
+            "New link tags must be written with version {LINK_TAG_VERSION}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    fn post_with(name: &str, content: &str) -> Post {
+        Post {
+            name: name.to_string(),
+            content: content.to_string(),
+            ..post(agent(1))
+        }
+    }
+
+    #[test]
+    fn a_post_tag_round_trips() {
+        let post = post_with("Title", "Some\n\n  content");
+        let tag = PostTag::new(&post);
+        assert_eq!(tag.excerpt, "Some content");
+        let decoded: PostTag = decode_link_tag(encode_link_tag(tag.clone()).unwrap()).unwrap();
+        assert_eq!(decoded, tag);
+        assert!(check_post_tag(encode_link_tag(tag).unwrap(), &post).is_ok());
+    }
+
+    #[test]
+    fn a_tag_that_describes_another_post_is_rejected() {
+        let tag = encode_link_tag(PostTag::new(&post_with("Title", "Content"))).unwrap();
+        let error = check_post_tag(tag, &post_with("Other title", "Content")).unwrap_err();
+        assert_eq!(error.code(), "INVALID");
+    }
+
+    #[test]
+    fn an_empty_tag_is_rejected() {
+        let error = check_post_tag(LinkTag::new(vec![]), &post(agent(1))).unwrap_err();
+        assert_eq!(error, BlogError::MalformedLinkTag);
+        let comment = comment(agent(1), action_hash(1));
+        let error = check_comment_tag(LinkTag::new(vec![]), &comment).unwrap_err();
+        assert_eq!(error, BlogError::MalformedLinkTag);
+    }
+
+    #[test]
+    fn a_tag_of_an_unknown_version_is_rejected() {
+        let comment = comment(agent(1), action_hash(1));
+        let tag = CommentTag {
+            version: LINK_TAG_VERSION + 1,
+            ..CommentTag::new(&comment)
+        };
+        let error = check_comment_tag(encode_link_tag(tag).unwrap(), &comment).unwrap_err();
+        assert_eq!(error.code(), "INVALID");
+    }
+
+    proptest! {
+        #[test]
+        fn tags_are_bounded_and_match_their_target(name in ".{0,300}", content in ".{0,600}") {
+            let post = post_with(&name, &content);
+            let tag = PostTag::new(&post);
+            prop_assert!(tag.title.len() <= TAG_TITLE_MAX_BYTES);
+            prop_assert!(tag.excerpt.len() <= TAG_EXCERPT_MAX_BYTES);
+            prop_assert!(name.starts_with(&tag.title));
+            let link_tag = encode_link_tag(tag).unwrap();
+            prop_assert!(link_tag.0.len() <= 500);
+            prop_assert!(check_post_tag(link_tag, &post).is_ok());
+        }
+    }
+}
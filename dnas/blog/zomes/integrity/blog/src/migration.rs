@@ -0,0 +1,456 @@
+use hdi::prelude::*;
+
+use crate::{BlogError, Comment, Post, RuleResult};
+
+/// Proof that a post or comment was imported from a previous DNA in the lineage of this one,
+/// carrying the revision that was exported from the previous DNA as signed by its author
+#[derive(Clone, PartialEq)]
+#[hdk_entry_helper]
+pub struct Provenance {
+    // The action that originally created the post or comment in the previous DNA
+    pub original_hash: ActionHash,
+    // The latest revision of the post or comment in the previous DNA
+    pub revision: SignedActionHashed,
+    pub revision_entry: Entry,
+    // The original action and the revisions after it that an updated revision descends from,
+    // oldest first
+    #[serde(default)]
+    pub lineage: Vec<Action>,
+    // The post or comment that re-creates the revision in this DNA
+    pub imported_hash: ActionHash,
+}
+
+/// Checks that a provenance is created by the author of the imported revision, and that the revision
+/// is authentic, given whether its signature verified and the hashes of its entry, its action and
+/// the actions of its lineage
+pub fn check_provenance_revision(
+    action: &EntryCreationAction,
+    provenance: &Provenance,
+    signature_valid: bool,
+    revision_entry_hash: &EntryHash,
+    revision_action_hash: &ActionHash,
+    lineage_hashes: &[ActionHash],
+) -> RuleResult {
+    let revision_action = provenance.revision.action();
+    if action.author() != revision_action.author() {
+        return Err(BlogError::Unauthorized(
+            "Only the original author can import their content".to_string(),
+        ));
+    }
+    if !signature_valid {
+        return Err(BlogError::Invalid(
+            "The imported revision must be signed by its author".to_string(),
+        ));
+    }
+    if revision_action.entry_hash() != Some(revision_entry_hash) {
+        return Err(BlogError::Invalid(
+            "The imported entry must be the one committed by the signed revision".to_string(),
+        ));
+    }
+    match revision_action {
+        Action::Create(_) => {
+            if revision_action_hash != &provenance.original_hash || !provenance.lineage.is_empty() {
+                return Err(BlogError::Invalid(
+                    "An imported original revision must be the action it claims to be".to_string(),
+                ));
+            }
+        }
+        Action::Update(update) => check_revision_lineage(provenance, update, lineage_hashes)?,
+        _ => {
+            return Err(BlogError::Invalid(
+                "An imported revision must create or update an entry".to_string(),
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Checks that an updated revision descends from the original action of the provenance, created by
+/// the author of the revision, through revisions that each update the one before them
+fn check_revision_lineage(
+    provenance: &Provenance,
+    update: &Update,
+    lineage_hashes: &[ActionHash],
+) -> RuleResult {
+    let Some((Action::Create(original), revisions)) = provenance.lineage.split_first() else {
+        return Err(BlogError::Invalid(
+            "An imported update must carry the action that created the original".to_string(),
+        ));
+    };
+    if original.author != update.author {
+        return Err(BlogError::Unauthorized(
+            "Only the original author can import their content".to_string(),
+        ));
+    }
+    let descends = lineage_hashes.len() == provenance.lineage.len()
+        && lineage_hashes.first() == Some(&provenance.original_hash)
+        && revisions
+            .iter()
+            .map(|revision| match revision {
+                Action::Update(revision) if revision.author == update.author => Some(revision),
+                _ => None,
+            })
+            .chain(std::iter::once(Some(update)))
+            .zip(lineage_hashes)
+            .all(|(revision, updated_hash)| {
+                revision.is_some_and(|revision| &revision.original_action_address == updated_hash)
+            });
+    if !descends {
+        return Err(BlogError::Invalid(
+            "An imported revision must descend from the original action it claims".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the imported record re-creates the revision of the provenance as its original author
+pub fn check_imported_record(provenance: &Provenance, imported_record: &Record) -> RuleResult {
+    if imported_record.action().author() != provenance.revision.action().author() {
+        return Err(BlogError::Unauthorized(
+            "Imported content must be re-created by its original author".to_string(),
+        ));
+    }
+    let matches = if let Ok(original_post) = Post::try_from(provenance.revision_entry.clone()) {
+        Post::try_from(imported_record).is_ok_and(|post| {
+            post.author == original_post.author
+                && post.name == original_post.name
+                && post.content == original_post.content
+                && post.format == original_post.format
+        })
+    } else if let Ok(original_comment) = Comment::try_from(provenance.revision_entry.clone()) {
+        Comment::try_from(imported_record).is_ok_and(|comment| {
+            comment.author == original_comment.author && comment.content == original_comment.content
+        })
+    } else {
+        false
+    };
+    if !matches {
+        return Err(BlogError::Invalid(
+            "The imported content must match the revision it was imported from".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_create_provenance(
+    action: EntryCreationAction,
+    provenance: Provenance,
+) -> ExternResult<ValidateCallbackResult> {
+    let revision_action = provenance.revision.action();
+    let signature_valid = verify_signature(
+        revision_action.author().clone(),
+        provenance.revision.signature().clone(),
+        revision_action,
+    )?;
+    let revision_entry_hash = hash_entry(provenance.revision_entry.clone())?;
+    let revision_action_hash = hash_action(revision_action.clone())?;
+    let lineage_hashes = provenance
+        .lineage
+        .iter()
+        .map(|revision| hash_action(revision.clone()))
+        .collect::<ExternResult<Vec<ActionHash>>>()?;
+    if let Err(error) = check_provenance_revision(
+        &action,
+        &provenance,
+        signature_valid,
+        &revision_entry_hash,
+        &revision_action_hash,
+        &lineage_hashes,
+    ) {
+        return Ok(error.into());
+    }
+    let imported_record = must_get_valid_record(provenance.imported_hash.clone())?;
+    Ok(crate::validation_result(check_imported_record(
+        &provenance,
+        &imported_record,
+    )))
+}
+
+pub fn validate_update_provenance(
+    _action: Update,
+    _provenance: Provenance,
+    _original_action: EntryCreationAction,
+    _original_provenance: Provenance,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(BlogError::Immutable("Provenances cannot be updated".to_string()).into())
+}
+
+pub fn validate_delete_provenance(
+    _action: Delete,
+    _original_action: EntryCreationAction,
+    _original_provenance: Provenance,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(BlogError::Immutable("Provenances cannot be deleted".to_string()).into())
+}
+
+fn validate_provenance_link(
+    action: &CreateLink,
+    target_address: AnyLinkableHash,
+    base_of: impl Fn(&Provenance) -> ActionHash,
+    base_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    let record = must_get_valid_record(action_hash)?;
+    let provenance: crate::Provenance = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BlogError::MissingEntry)?;
+    Ok(crate::validation_result(check_provenance_link(
+        action,
+        &base_address,
+        &record,
+        base_of(&provenance),
+    )))
+}
+
+/// Checks that a provenance link is created by the importer, from the action that `linked_hash` relates
+pub fn check_provenance_link(
+    action: &CreateLink,
+    base_address: &AnyLinkableHash,
+    provenance_record: &Record,
+    linked_hash: ActionHash,
+) -> RuleResult {
+    if provenance_record.action().author() != &action.author {
+        return Err(BlogError::Unauthorized(
+            "Only the importer of a Provenance can link it".to_string(),
+        ));
+    }
+    if base_address != &AnyLinkableHash::from(linked_hash) {
+        return Err(BlogError::Invalid(
+            "A Provenance must be linked from the actions it relates".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_create_link_original_to_provenance(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    validate_provenance_link(
+        &action,
+        target_address,
+        |provenance| provenance.original_hash.clone(),
+        base_address,
+    )
+}
+
+pub fn validate_delete_link_original_to_provenance(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(BlogError::Immutable("OriginalToProvenance links cannot be deleted".to_string()).into())
+}
+
+pub fn validate_create_link_imported_to_provenance(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    validate_provenance_link(
+        &action,
+        target_address,
+        |provenance| provenance.imported_hash.clone(),
+        base_address,
+    )
+}
+
+pub fn validate_delete_link_imported_to_provenance(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(BlogError::Immutable("ImportedToProvenance links cannot be deleted".to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    fn provenance_of(revision_action: Action, revision_entry: Entry) -> Provenance {
+        Provenance {
+            original_hash: action_hash(1),
+            revision: SignedActionHashed::with_presigned(
+                ActionHashed::with_pre_hashed(revision_action, action_hash(1)),
+                Signature([0; 64]),
+            ),
+            revision_entry,
+            lineage: vec![],
+            imported_hash: action_hash(2),
+        }
+    }
+
+    fn post_provenance(post: Post) -> Provenance {
+        let mut revision_action = create(post.author.clone(), 3, 1_000_000);
+        revision_action.entry_hash = entry_hash(9);
+        provenance_of(
+            Action::Create(revision_action),
+            Entry::try_from(post).unwrap(),
+        )
+    }
+
+    #[test]
+    fn imported_revisions_must_be_authentic() {
+        let provenance = post_provenance(post(agent(1)));
+        let action = EntryCreationAction::Create(create(agent(1), 5, 2_000_000));
+        let check = |action: &EntryCreationAction,
+                     signature_valid: bool,
+                     revision_entry_hash: EntryHash,
+                     revision_action_hash: ActionHash| {
+            check_provenance_revision(
+                action,
+                &provenance,
+                signature_valid,
+                &revision_entry_hash,
+                &revision_action_hash,
+                &[],
+            )
+        };
+        assert_eq!(check(&action, true, entry_hash(9), action_hash(1)), Ok(()));
+        assert!(check(&action, false, entry_hash(9), action_hash(1)).is_err());
+        assert!(check(&action, true, entry_hash(8), action_hash(1)).is_err());
+        assert!(check(&action, true, entry_hash(9), action_hash(3)).is_err());
+        let someone_else = EntryCreationAction::Create(create(agent(2), 5, 2_000_000));
+        assert_eq!(
+            check(&someone_else, true, entry_hash(9), action_hash(1))
+                .unwrap_err()
+                .code(),
+            "UNAUTHORIZED"
+        );
+    }
+
+    #[test]
+    fn imported_updates_must_descend_from_the_original() {
+        let author = agent(1);
+        let action = EntryCreationAction::Create(create(author.clone(), 5, 2_000_000));
+        let original = Action::Create(create(author.clone(), 3, 1_000_000));
+        let updated_provenance = |updated_hash: ActionHash, lineage: Vec<Action>| {
+            let mut revision_action = update(author.clone(), updated_hash);
+            revision_action.entry_hash = entry_hash(9);
+            Provenance {
+                lineage,
+                ..provenance_of(
+                    Action::Update(revision_action),
+                    Entry::try_from(post(author.clone())).unwrap(),
+                )
+            }
+        };
+        let check = |provenance: &Provenance, lineage_hashes: &[ActionHash]| {
+            check_provenance_revision(
+                &action,
+                provenance,
+                true,
+                &entry_hash(9),
+                &action_hash(4),
+                lineage_hashes,
+            )
+        };
+
+        let provenance = updated_provenance(action_hash(1), vec![original.clone()]);
+        assert_eq!(check(&provenance, &[action_hash(1)]), Ok(()));
+        assert!(check(&provenance, &[action_hash(3)]).is_err());
+        assert!(check(
+            &updated_provenance(action_hash(3), vec![original.clone()]),
+            &[action_hash(1)]
+        )
+        .is_err());
+        assert!(check(&updated_provenance(action_hash(1), vec![]), &[]).is_err());
+
+        let intermediate = Action::Update(update(author.clone(), action_hash(1)));
+        let provenance =
+            updated_provenance(action_hash(3), vec![original.clone(), intermediate.clone()]);
+        assert_eq!(
+            check(&provenance, &[action_hash(1), action_hash(3)]),
+            Ok(())
+        );
+        assert!(check(&provenance, &[action_hash(1), action_hash(5)]).is_err());
+        assert!(check(&provenance, &[action_hash(1)]).is_err());
+
+        let elsewhere = Action::Update(update(author.clone(), action_hash(6)));
+        assert!(check(
+            &updated_provenance(action_hash(3), vec![original.clone(), elsewhere]),
+            &[action_hash(1), action_hash(3)]
+        )
+        .is_err());
+        let someone_elses = Action::Update(update(agent(2), action_hash(1)));
+        assert!(check(
+            &updated_provenance(action_hash(3), vec![original, someone_elses]),
+            &[action_hash(1), action_hash(3)]
+        )
+        .is_err());
+        let someone_elses_original = Action::Create(create(agent(2), 3, 1_000_000));
+        assert_eq!(
+            check(
+                &updated_provenance(action_hash(1), vec![someone_elses_original]),
+                &[action_hash(1)]
+            )
+            .unwrap_err()
+            .code(),
+            "UNAUTHORIZED"
+        );
+    }
+
+    #[test]
+    fn imported_records_must_match_the_revision() {
+        let original = post(agent(1));
+        let provenance = post_provenance(original.clone());
+        assert_eq!(
+            check_imported_record(&provenance, &post_record(original.clone(), 5)),
+            Ok(())
+        );
+        let mut edited = original.clone();
+        edited.content = "Edited".to_string();
+        assert!(check_imported_record(&provenance, &post_record(edited, 5)).is_err());
+        let mut someone_elses = original.clone();
+        someone_elses.author = agent(2);
+        assert!(check_imported_record(&provenance, &post_record(someone_elses, 5)).is_err());
+
+        let comment = comment(agent(1), action_hash(3));
+        let comment_record = record(
+            Action::Create(create(agent(1), 5, 2_000_000)),
+            Some(Entry::try_from(comment.clone()).unwrap()),
+        );
+        let comment_provenance = provenance_of(
+            Action::Create(create(agent(1), 3, 1_000_000)),
+            Entry::try_from(comment).unwrap(),
+        );
+        assert_eq!(
+            check_imported_record(&comment_provenance, &comment_record),
+            Ok(())
+        );
+        assert!(check_imported_record(&comment_provenance, &post_record(original, 5)).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn provenances_are_linked_by_their_importer_from_the_related_action(
+            importer in arb_agent(),
+            linker in arb_agent(),
+            base in arb_action_hash(),
+            linked_hash in arb_action_hash(),
+        ) {
+            let record = record(Action::Create(create(importer.clone(), 3, 1_000_000)), None);
+            let result = check_provenance_link(
+                &create_link(linker.clone(), 2_000_000),
+                &base.clone().into(),
+                &record,
+                linked_hash.clone(),
+            );
+            prop_assert_eq!(result.is_ok(), importer == linker && base == linked_hash);
+        }
+    }
+}
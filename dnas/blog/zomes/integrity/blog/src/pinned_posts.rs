@@ -0,0 +1,139 @@
+use hdi::prelude::*;
+
+use crate::{BlogError, DnaProperties, RuleResult};
+
+/// Tag of a `PinnedPosts` link, with the time after which the post is no longer pinned
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct PinTag {
+    pub expires_at: Option<Timestamp>,
+}
+
+pub fn validate_create_link_pinned_posts(
+    action: CreateLink,
+    _base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    let record = must_get_valid_record(action_hash)?;
+    let _post: crate::Post = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BlogError::MissingEntry)?;
+    Ok(crate::validation_result(check_create_link_pinned_posts(
+        &action,
+        tag,
+        &crate::DnaProperties::get()?,
+    )))
+}
+
+pub fn check_create_link_pinned_posts(
+    action: &CreateLink,
+    tag: LinkTag,
+    properties: &DnaProperties,
+) -> RuleResult {
+    if PinTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.into_inner()))).is_err() {
+        return Err(BlogError::Invalid(
+            "PinnedPosts links must be tagged with their expiry".to_string(),
+        ));
+    }
+    if !properties.is_moderator(&action.author) {
+        return Err(BlogError::Unauthorized(
+            "Only the progenitor or a moderator can pin a Post".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn check_delete_link_pinned_posts(
+    action: &DeleteLink,
+    properties: &DnaProperties,
+) -> RuleResult {
+    if !properties.is_moderator(&action.author) {
+        return Err(BlogError::Unauthorized(
+            "Only the progenitor or a moderator can unpin a Post".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_delete_link_pinned_posts(
+    action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(crate::validation_result(check_delete_link_pinned_posts(
+        &action,
+        &crate::DnaProperties::get()?,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use hdi::prelude::holo_hash::AgentPubKeyB64;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    fn pin_tag(expires_at: Option<Timestamp>) -> LinkTag {
+        LinkTag::new(
+            SerializedBytes::try_from(PinTag { expires_at })
+                .unwrap()
+                .bytes()
+                .clone(),
+        )
+    }
+
+    fn properties(progenitor: Option<AgentPubKey>, moderators: &[AgentPubKey]) -> DnaProperties {
+        DnaProperties {
+            progenitor: progenitor.map(AgentPubKeyB64::from),
+            moderators: moderators
+                .iter()
+                .cloned()
+                .map(AgentPubKeyB64::from)
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pins_must_be_tagged_with_their_expiry() {
+        let properties = properties(Some(agent(1)), &[]);
+        let action = create_link(agent(1), 2_000_000);
+        assert_eq!(
+            check_create_link_pinned_posts(&action, pin_tag(None), &properties),
+            Ok(())
+        );
+        assert!(
+            check_create_link_pinned_posts(&action, LinkTag::new(vec![0xc1]), &properties).is_err()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn only_moderators_pin_and_unpin_posts(
+            progenitor in prop::option::of(arb_agent()),
+            moderators in prop::collection::vec(arb_agent(), 0..3),
+            agent in arb_agent(),
+            expires_at in prop::option::of(any::<i64>()),
+        ) {
+            let properties = properties(progenitor.clone(), &moderators);
+            let is_moderator = progenitor.as_ref() == Some(&agent) || moderators.contains(&agent);
+            let tag = pin_tag(expires_at.map(Timestamp::from_micros));
+            prop_assert_eq!(
+                check_create_link_pinned_posts(&create_link(agent.clone(), 2_000_000), tag, &properties).is_ok(),
+                is_moderator
+            );
+            prop_assert_eq!(
+                check_delete_link_pinned_posts(&delete_link(agent), &properties).is_ok(),
+                is_moderator
+            );
+        }
+    }
+}
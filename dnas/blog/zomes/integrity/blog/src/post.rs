@@ -1,29 +1,249 @@
+use std::collections::BTreeSet;
+
 use hdi::prelude::*;
 
+use crate::{BlogError, ContentFormat, EditMetadata, PostEnvelope, RevertedRevision, RuleResult};
+
+// The schema version that every new Post must be written with
+pub const POST_SCHEMA_VERSION: u32 = 2;
+
+/// The in-memory representation of a post, whatever schema version it was written with
 #[derive(Clone, PartialEq)]
 #[hdk_entry_helper]
+#[serde(from = "PostVersions", into = "PostVersions")]
 pub struct Post {
+    pub name: String,
+    // Empty for private posts, whose content is only stored in the envelope
+    pub content: String,
+    pub author: AgentPubKey,
+    // Agents that countersigned this post together with its author
+    pub co_authors: Vec<AgentPubKey>,
+    // The agent that committed this post on behalf of its author through a capability grant
+    pub delegate: Option<AgentPubKey>,
+    // The encrypted content of a private post
+    pub envelope: Option<PostEnvelope>,
+    pub format: ContentFormat,
+    // Entry hashes of the File Manifests attached to this post
+    pub attachments: Vec<EntryHash>,
+    // The revision that this update restores, if it was created by reverting the post
+    pub reverted_from: Option<ActionHash>,
+    // Why and how this revision was edited, only present on updates
+    pub edit: Option<EditMetadata>,
+}
+
+/// The unversioned layout that posts were written with before schema versions were introduced
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PostV1 {
     pub name: String,
     pub content: String,
     pub author: AgentPubKey,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub co_authors: Vec<AgentPubKey>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delegate: Option<AgentPubKey>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub envelope: Option<PostEnvelope>,
+    #[serde(default, skip_serializing_if = "ContentFormat::is_plain")]
+    pub format: ContentFormat,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<EntryHash>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverted_from: Option<ActionHash>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edit: Option<EditMetadata>,
+}
+
+/// The layout of `PostV1` with an explicit schema version
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PostV2 {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub post: PostV1,
+}
+
+/// Every layout that a Post entry can be stored with, newest first
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum PostVersions {
+    V2(PostV2),
+    V1(PostV1),
+}
+
+impl PostVersions {
+    pub fn schema_version(&self) -> u32 {
+        match self {
+            PostVersions::V2(post) => post.schema_version,
+            PostVersions::V1(_) => 1,
+        }
+    }
+}
+
+impl From<PostVersions> for Post {
+    fn from(versions: PostVersions) -> Self {
+        let post = match versions {
+            PostVersions::V2(post) => post.post,
+            PostVersions::V1(post) => post,
+        };
+        Post {
+            name: post.name,
+            content: post.content,
+            author: post.author,
+            co_authors: post.co_authors,
+            delegate: post.delegate,
+            envelope: post.envelope,
+            format: post.format,
+            attachments: post.attachments,
+            reverted_from: post.reverted_from,
+            edit: post.edit,
+        }
+    }
+}
+
+impl From<Post> for PostVersions {
+    fn from(post: Post) -> Self {
+        PostVersions::V2(PostV2 {
+            schema_version: POST_SCHEMA_VERSION,
+            post: PostV1 {
+                name: post.name,
+                content: post.content,
+                author: post.author,
+                co_authors: post.co_authors,
+                delegate: post.delegate,
+                envelope: post.envelope,
+                format: post.format,
+                attachments: post.attachments,
+                reverted_from: post.reverted_from,
+                edit: post.edit,
+            },
+        })
+    }
+}
+
+/// The rules of a new Post revision that only depend on the entry itself
+pub fn check_create_post(action: &EntryCreationAction, post: &Post) -> RuleResult {
+    if let (EntryCreationAction::Create(_), Some(_)) = (action, &post.reverted_from) {
+        return Err(BlogError::Invalid(
+            "Only updates can revert a Post".to_string(),
+        ));
+    }
+    if post.delegate.as_ref() == Some(&post.author) {
+        return Err(BlogError::Invalid(
+            "The delegate of a Post must be a different agent than its author".to_string(),
+        ));
+    }
+    crate::check_edit_metadata(action, &post.edit)?;
+    crate::check_content_format(post.format, &post.content)?;
+    crate::check_post_envelope(post)
 }
 
 pub fn validate_create_post(
-    _action: EntryCreationAction,
-    _post: Post,
+    action: EntryCreationAction,
+    post: Post,
 ) -> ExternResult<ValidateCallbackResult> {
-    // TODO: add the appropriate validation rules
+    if let Err(error) = check_create_post(&action, &post) {
+        return Ok(error.into());
+    }
+    let result = crate::validate_attachments(&post.attachments)?;
+    if result != ValidateCallbackResult::Valid {
+        return Ok(result);
+    }
+    let result = crate::validate_post_envelope_keys(&post)?;
+    if result != ValidateCallbackResult::Valid {
+        return Ok(result);
+    }
+    if let EntryCreationAction::Create(create) = &action {
+        if !post.co_authors.is_empty() {
+            let entry = must_get_entry(create.entry_hash.clone())?;
+            return Ok(crate::validation_result(check_co_authors(
+                &post,
+                entry.as_content(),
+            )));
+        }
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// Checks that a co-authored post was committed in a countersigning session
+/// whose signers are exactly its author and its co-authors
+pub fn check_co_authors(post: &Post, entry: &Entry) -> RuleResult {
+    if post.co_authors.is_empty() {
+        return Ok(());
+    }
+    let mut authors: BTreeSet<&AgentPubKey> = BTreeSet::new();
+    authors.insert(&post.author);
+    for co_author in &post.co_authors {
+        if !authors.insert(co_author) {
+            return Err(BlogError::Invalid(
+                "The co-authors of a Post must be distinct from each other and from its author"
+                    .to_string(),
+            ));
+        }
+    }
+    let Entry::CounterSign(session_data, _) = entry else {
+        return Err(BlogError::Invalid(
+            "A co-authored Post must be countersigned by all of its authors".to_string(),
+        ));
+    };
+    if !session_data
+        .preflight_request()
+        .optional_signing_agents
+        .is_empty()
+    {
+        return Err(BlogError::Invalid(
+            "A co-authored Post cannot have optional signers".to_string(),
+        ));
+    }
+    let signers: BTreeSet<&AgentPubKey> = session_data.signing_agents().collect();
+    if signers != authors {
+        return Err(BlogError::Invalid(
+            "The signers of a co-authored Post must be its author and its co-authors".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// The rules of a Post update, given the revision it restores if it is a revert
+pub fn check_update_post(
+    post: &Post,
+    original_post: &Post,
+    reverted: Option<&RevertedRevision>,
+) -> RuleResult {
+    if post.co_authors != original_post.co_authors {
+        return Err(BlogError::Immutable(
+            "The co-authors of a Post cannot be changed".to_string(),
+        ));
+    }
+    if let Some(reverted) = reverted {
+        crate::check_reverted_revision(reverted)?;
+        let Ok(revision_post) = Post::try_from(&reverted.revision) else {
+            return Err(BlogError::Invalid(
+                "A Post can only be reverted to a Post revision".to_string(),
+            ));
+        };
+        if revision_post.name != post.name || revision_post.content != post.content {
+            return Err(BlogError::Invalid(
+                "A reverted Post must match the revision it restores".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
 pub fn validate_update_post(
-    _action: Update,
-    _post: Post,
+    action: Update,
+    post: Post,
     _original_action: EntryCreationAction,
-    _original_post: Post,
+    original_post: Post,
 ) -> ExternResult<ValidateCallbackResult> {
-    // TODO: add the appropriate validation rules
-    Ok(ValidateCallbackResult::Valid)
+    let reverted = match &post.reverted_from {
+        Some(reverted_from) => Some(crate::must_get_reverted_revision(&action, reverted_from)?),
+        None => None,
+    };
+    Ok(crate::validation_result(check_update_post(
+        &post,
+        &original_post,
+        reverted.as_ref(),
+    )))
 }
 
 pub fn validate_delete_post(
@@ -35,38 +255,83 @@ pub fn validate_delete_post(
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// The latest revision of a post that its author had committed when linking it, which the tag of the
+/// link must describe
+fn must_get_listed_revision(
+    action: &CreateLink,
+    post_hash: &ActionHash,
+    post: Post,
+) -> ExternResult<Post> {
+    let activity = must_get_agent_activity(
+        action.author.clone(),
+        ChainFilter::new(action.prev_action.clone()).until(post_hash.clone()),
+    )?;
+    match crate::latest_revision_entry_hash(post_hash, &activity) {
+        Some(entry_hash) => Ok(Post::try_from(must_get_entry(entry_hash)?.content)?),
+        None => Ok(post),
+    }
+}
+
 pub fn validate_create_link_author_to_posts(
-    _action: CreateLink,
-    _base_address: AnyLinkableHash,
+    action: CreateLink,
+    base_address: AnyLinkableHash,
     target_address: AnyLinkableHash,
-    _tag: LinkTag,
+    tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    let action_hash =
-        target_address
-            .into_action_hash()
-            .ok_or(wasm_error!(WasmErrorInner::Guest(
-                "No action hash associated with link".to_string()
-            )))?;
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
     let record = must_get_valid_record(action_hash)?;
-    let _post: crate::Post = record
+    let post: crate::Post = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Linked action must reference an entry".to_string()
-        )))?;
-    // TODO: add the appropriate validation rules
-    Ok(ValidateCallbackResult::Valid)
+        .ok_or(BlogError::MissingEntry)?;
+    if let Err(error) = check_create_link_author_to_posts(&action, &base_address, &record, &post) {
+        return Ok(error.into());
+    }
+    let listed_post = must_get_listed_revision(&action, record.action_address(), post)?;
+    Ok(crate::validation_result(crate::check_post_tag(
+        tag,
+        &listed_post,
+    )))
+}
+
+pub fn check_create_link_author_to_posts(
+    action: &CreateLink,
+    base_address: &AnyLinkableHash,
+    post_record: &Record,
+    post: &Post,
+) -> RuleResult {
+    if post_record.action().author() != &action.author {
+        return Err(BlogError::Unauthorized(
+            "Only the author of a Post can index it".to_string(),
+        ));
+    }
+    let is_author = std::iter::once(&post.author)
+        .chain(post.co_authors.iter())
+        .any(|author| base_address == &AnyLinkableHash::from(author.clone()));
+    if !is_author {
+        return Err(BlogError::Invalid(
+            "A Post can only be indexed under its author and its co-authors".to_string(),
+        ));
+    }
+    Ok(())
 }
 
 pub fn validate_delete_link_author_to_posts(
-    _action: DeleteLink,
-    _original_action: CreateLink,
+    action: DeleteLink,
+    original_action: CreateLink,
     _base: AnyLinkableHash,
     _target: AnyLinkableHash,
     _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    // TODO: add the appropriate validation rules
+    if action.author != original_action.author {
+        return Ok(BlogError::Unauthorized(
+            "Only the author of a Post can remove it from an author's posts".to_string(),
+        )
+        .into());
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
@@ -78,31 +343,22 @@ pub fn validate_create_link_post_updates(
 ) -> ExternResult<ValidateCallbackResult> {
     let action_hash = base_address
         .into_action_hash()
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "No action hash associated with link".to_string()
-        )))?;
+        .ok_or(BlogError::MalformedLink)?;
     let record = must_get_valid_record(action_hash)?;
     let _post: crate::Post = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Linked action must reference an entry".to_string()
-        )))?;
-    let action_hash =
-        target_address
-            .into_action_hash()
-            .ok_or(wasm_error!(WasmErrorInner::Guest(
-                "No action hash associated with link".to_string()
-            )))?;
+        .ok_or(BlogError::MissingEntry)?;
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
     let record = must_get_valid_record(action_hash)?;
     let _post: crate::Post = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Linked action must reference an entry".to_string()
-        )))?;
+        .ok_or(BlogError::MissingEntry)?;
     // TODO: add the appropriate validation rules
     Ok(ValidateCallbackResult::Valid)
 }
@@ -114,42 +370,697 @@ pub fn validate_delete_link_post_updates(
     _target: AnyLinkableHash,
     _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Invalid(
-        "PostUpdates links cannot be deleted".to_string(),
-    ))
+    Ok(BlogError::Immutable("PostUpdates links cannot be deleted".to_string()).into())
 }
 
 pub fn validate_create_link_all_posts(
-    _action: CreateLink,
+    action: CreateLink,
+    _base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    let record = must_get_valid_record(action_hash)?;
+    let post: crate::Post = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BlogError::MissingEntry)?;
+    if let Err(error) = check_create_link_all_posts(&action, &record) {
+        return Ok(error.into());
+    }
+    let listed_post = must_get_listed_revision(&action, record.action_address(), post)?;
+    Ok(crate::validation_result(crate::check_post_tag(
+        tag,
+        &listed_post,
+    )))
+}
+
+pub fn check_create_link_all_posts(action: &CreateLink, post_record: &Record) -> RuleResult {
+    if post_record.action().author() != &action.author {
+        return Err(BlogError::Unauthorized(
+            "Only the author of a Post can list it".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_delete_link_all_posts(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(
+            BlogError::Unauthorized("Only the author of a Post can unlist it".to_string()).into(),
+        );
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_create_link_post_revision_tags(
+    action: CreateLink,
     _base_address: AnyLinkableHash,
     target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    let record = must_get_valid_record(action_hash)?;
+    let post: crate::Post = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BlogError::MissingEntry)?;
+    if let Err(error) = check_create_link_post_revision_tags(&action, &record) {
+        return Ok(error.into());
+    }
+    let listed_post = must_get_listed_revision(&action, record.action_address(), post)?;
+    Ok(crate::validation_result(crate::check_post_tag(
+        tag,
+        &listed_post,
+    )))
+}
+
+pub fn check_create_link_post_revision_tags(
+    action: &CreateLink,
+    post_record: &Record,
+) -> RuleResult {
+    if post_record.action().author() != &action.author {
+        return Err(BlogError::Unauthorized(
+            "Only the author of a Post can retag its listings".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_delete_link_post_revision_tags(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(BlogError::Unauthorized(
+            "Only the author of a Post can remove the tags of its revisions".to_string(),
+        )
+        .into());
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_create_link_author_to_unlisted_posts(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
     _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    let action_hash =
-        target_address
-            .into_action_hash()
-            .ok_or(wasm_error!(WasmErrorInner::Guest(
-                "No action hash associated with link".to_string()
-            )))?;
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
     let record = must_get_valid_record(action_hash)?;
     let _post: crate::Post = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Linked action must reference an entry".to_string()
-        )))?;
-    // TODO: add the appropriate validation rules
+        .ok_or(BlogError::MissingEntry)?;
+    Ok(crate::validation_result(
+        check_create_link_author_to_unlisted_posts(&action, &base_address, &record),
+    ))
+}
+
+pub fn check_create_link_author_to_unlisted_posts(
+    action: &CreateLink,
+    base_address: &AnyLinkableHash,
+    post_record: &Record,
+) -> RuleResult {
+    if post_record.action().author() != &action.author
+        || base_address != &AnyLinkableHash::from(action.author.clone())
+    {
+        return Err(BlogError::Unauthorized(
+            "Only the author of a Post can unlist it".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_delete_link_author_to_unlisted_posts(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(
+            BlogError::Unauthorized("Only the author of a Post can relist it".to_string()).into(),
+        );
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
-pub fn validate_delete_link_all_posts(
+/// Tag of a `PostRestorations` link, pointing to the delete that the restoration undoes
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct RestorationTag {
+    pub delete_hash: ActionHash,
+}
+
+pub fn validate_create_link_post_restorations(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let deleted_post_hash = base_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    let deleted_record = must_get_valid_record(deleted_post_hash.clone())?;
+    let _deleted_post: crate::Post = deleted_record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BlogError::MissingEntry)?;
+    let restoration_tag = match check_restoration_request(&action, &deleted_record, tag) {
+        Ok(restoration_tag) => restoration_tag,
+        Err(error) => return Ok(error.into()),
+    };
+    let delete_action = must_get_action(restoration_tag.delete_hash)?;
+    let grace_period = crate::DnaProperties::get()?.restore_grace_period();
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    let record = must_get_valid_record(action_hash)?;
+    let _post: crate::Post = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BlogError::MissingEntry)?;
+    Ok(crate::validation_result(check_restoration(
+        &action,
+        &deleted_post_hash,
+        delete_action.action(),
+        &record,
+        grace_period,
+    )))
+}
+
+/// Checks that a restoration is made by the author of the deleted post, and reads the delete it undoes
+pub fn check_restoration_request(
+    action: &CreateLink,
+    deleted_record: &Record,
+    tag: LinkTag,
+) -> Result<RestorationTag, BlogError> {
+    if deleted_record.action().author() != &action.author {
+        return Err(BlogError::Unauthorized(
+            "Only the author of a Post can restore it".to_string(),
+        ));
+    }
+    RestorationTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.into_inner()))).map_err(
+        |_| {
+            BlogError::Invalid(
+                "PostRestorations links must be tagged with the delete they undo".to_string(),
+            )
+        },
+    )
+}
+
+/// Checks that a restoration undoes a delete of the restored post within the grace period,
+/// and points to a fresh copy created by its author
+pub fn check_restoration(
+    action: &CreateLink,
+    deleted_post_hash: &ActionHash,
+    delete_action: &Action,
+    restored_record: &Record,
+    grace_period: i64,
+) -> RuleResult {
+    let deleted_at = match delete_action {
+        Action::Delete(delete) if &delete.deletes_address == deleted_post_hash => delete.timestamp,
+        _ => {
+            return Err(BlogError::Invalid(
+                "The tagged action must be a delete of the restored Post".to_string(),
+            ));
+        }
+    };
+    if is_restore_grace_period_expired(deleted_at, action.timestamp, grace_period) {
+        return Err(BlogError::RestoreGracePeriodExpired);
+    }
+    match restored_record.action() {
+        Action::Create(create) if create.author == action.author => Ok(()),
+        _ => Err(BlogError::Invalid(
+            "A Post must be restored to a fresh copy created by its author".to_string(),
+        )),
+    }
+}
+
+/// Whether the grace period, in seconds, to restore a post deleted at `deleted_at` is over at `at`,
+/// saturating instead of overflowing for grace periods and timestamps at the edges of their range
+pub fn is_restore_grace_period_expired(
+    deleted_at: Timestamp,
+    at: Timestamp,
+    grace_period: i64,
+) -> bool {
+    at.as_micros().saturating_sub(deleted_at.as_micros()) > grace_period.saturating_mul(1_000_000)
+}
+
+pub fn validate_delete_link_post_restorations(
     _action: DeleteLink,
     _original_action: CreateLink,
     _base: AnyLinkableHash,
     _target: AnyLinkableHash,
     _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    // TODO: add the appropriate validation rules
-    Ok(ValidateCallbackResult::Valid)
+    Ok(BlogError::Immutable("PostRestorations links cannot be deleted".to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    fn create_action(post: &Post) -> EntryCreationAction {
+        EntryCreationAction::Create(create(post.author.clone(), 3, 1_000_000))
+    }
+
+    // A post that sets the fields that are skipped when they are empty
+    fn full_post() -> Post {
+        Post {
+            co_authors: vec![agent(2)],
+            format: ContentFormat::Markdown,
+            edit: EditMetadata::new(Some("Typo".to_string()), true),
+            ..post(agent(1))
+        }
+    }
+
+    fn layout_v1(post: &Post) -> PostV1 {
+        match PostVersions::from(post.clone()) {
+            PostVersions::V2(post) => post.post,
+            PostVersions::V1(post) => post,
+        }
+    }
+
+    #[test]
+    fn a_v1_post_is_read_as_a_post() {
+        let post = full_post();
+        let bytes = ExternIO::encode(layout_v1(&post)).unwrap();
+        let versions: PostVersions = bytes.decode().unwrap();
+        assert!(matches!(versions, PostVersions::V1(_)));
+        assert_eq!(versions.schema_version(), 1);
+        assert_eq!(bytes.decode::<Post>().unwrap(), post);
+    }
+
+    #[test]
+    fn a_v2_post_is_not_read_as_v1() {
+        let post = full_post();
+        let bytes = ExternIO::encode(PostV2 {
+            schema_version: 2,
+            post: layout_v1(&post),
+        })
+        .unwrap();
+        let versions: PostVersions = bytes.decode().unwrap();
+        assert!(matches!(versions, PostVersions::V2(_)));
+        assert_eq!(versions.schema_version(), 2);
+        assert_eq!(bytes.decode::<Post>().unwrap(), post);
+    }
+
+    #[test]
+    fn a_post_is_written_as_v2() {
+        let post = full_post();
+        let bytes = ExternIO::encode(post.clone()).unwrap();
+        let versions: PostVersions = bytes.decode().unwrap();
+        assert!(matches!(versions, PostVersions::V2(_)));
+        assert_eq!(versions.schema_version(), POST_SCHEMA_VERSION);
+        assert_eq!(Post::from(versions), post);
+    }
+
+    fn countersigned(post: &Post, signers: &[AgentPubKey], optional: &[AgentPubKey]) -> Entry {
+        let Entry::App(bytes) = Entry::try_from(post.clone()).unwrap() else {
+            unreachable!()
+        };
+        let to_agents = |agents: &[AgentPubKey]| -> CounterSigningAgents {
+            agents.iter().map(|agent| (agent.clone(), vec![])).collect()
+        };
+        let request = PreflightRequest::try_new(
+            entry_hash(0),
+            to_agents(signers),
+            to_agents(optional),
+            optional.len() as u8,
+            !optional.is_empty(),
+            CounterSigningSessionTimes::try_new(
+                Timestamp::from_micros(1_000_000),
+                Timestamp::from_micros(60_000_000),
+            )
+            .unwrap(),
+            ActionBase::Create(CreateBase::new(app_entry_type())),
+            PreflightBytes(vec![]),
+        )
+        .unwrap();
+        let responses = (0..signers.len())
+            .map(|index| {
+                (
+                    CounterSigningAgentState::new(index as u8, action_hash(0), 0),
+                    Signature([0; 64]),
+                )
+            })
+            .collect();
+        let session_data = CounterSigningSessionData::try_new(request, responses, vec![]).unwrap();
+        Entry::CounterSign(Box::new(session_data), bytes)
+    }
+
+    fn reverted_to(revision: Record) -> RevertedRevision {
+        RevertedRevision {
+            original_action_hash: action_hash(1),
+            revision_original_action_hash: action_hash(1),
+            revision,
+        }
+    }
+
+    #[test]
+    fn a_sample_post_is_valid() {
+        let post = post(agent(1));
+        assert_eq!(check_create_post(&create_action(&post), &post), Ok(()));
+    }
+
+    #[test]
+    fn only_updates_can_revert_a_post() {
+        let mut post = post(agent(1));
+        post.reverted_from = Some(action_hash(2));
+        assert!(check_create_post(&create_action(&post), &post).is_err());
+        let update = EntryCreationAction::Update(update(agent(1), action_hash(2)));
+        assert_eq!(check_create_post(&update, &post), Ok(()));
+    }
+
+    #[test]
+    fn authors_cannot_delegate_to_themselves() {
+        let mut post = post(agent(1));
+        post.delegate = Some(agent(1));
+        assert!(check_create_post(&create_action(&post), &post).is_err());
+        post.delegate = Some(agent(2));
+        assert_eq!(check_create_post(&create_action(&post), &post), Ok(()));
+    }
+
+    #[test]
+    fn unsafe_markdown_is_rejected() {
+        let mut post = post(agent(1));
+        post.format = ContentFormat::Markdown;
+        post.content = "[x](javascript:alert(1))".to_string();
+        assert_eq!(
+            check_create_post(&create_action(&post), &post)
+                .unwrap_err()
+                .code(),
+            "INVALID"
+        );
+    }
+
+    #[test]
+    fn co_authored_posts_must_be_countersigned_by_exactly_their_authors() {
+        let mut post = post(agent(1));
+        post.co_authors = vec![agent(2)];
+        let plain = Entry::try_from(post.clone()).unwrap();
+        assert!(check_co_authors(&post, &plain).is_err());
+        assert_eq!(
+            check_co_authors(&post, &countersigned(&post, &[agent(1), agent(2)], &[])),
+            Ok(())
+        );
+        assert!(
+            check_co_authors(&post, &countersigned(&post, &[agent(1), agent(3)], &[])).is_err()
+        );
+        assert!(check_co_authors(
+            &post,
+            &countersigned(&post, &[agent(1), agent(2)], &[agent(1)])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn co_authors_must_be_distinct() {
+        let mut post = post(agent(1));
+        post.co_authors = vec![agent(2), agent(2)];
+        let entry = countersigned(&post, &[agent(1), agent(2)], &[]);
+        assert!(check_co_authors(&post, &entry).is_err());
+        post.co_authors = vec![agent(1)];
+        assert!(check_co_authors(&post, &entry).is_err());
+    }
+
+    #[test]
+    fn co_authors_are_immutable() {
+        let original = post(agent(1));
+        let mut post = original.clone();
+        post.co_authors = vec![agent(2)];
+        assert_eq!(
+            check_update_post(&post, &original, None)
+                .unwrap_err()
+                .code(),
+            "IMMUTABLE"
+        );
+    }
+
+    #[test]
+    fn reverts_must_restore_a_post_revision() {
+        let original = post(agent(1));
+        let mut post = original.clone();
+        post.content = "Old content".to_string();
+        let mut revision = original.clone();
+        revision.content = "Old content".to_string();
+        let reverted = reverted_to(post_record(revision, 4));
+        assert_eq!(check_update_post(&post, &original, Some(&reverted)), Ok(()));
+        let reverted = reverted_to(post_record(original.clone(), 4));
+        assert!(check_update_post(&post, &original, Some(&reverted)).is_err());
+        let reverted = reverted_to(record(
+            Action::Create(create(agent(1), 4, 1_000_000)),
+            Some(
+                Entry::try_from(crate::CommentLock {
+                    post_hash: action_hash(1),
+                    locked: true,
+                })
+                .unwrap(),
+            ),
+        ));
+        assert!(check_update_post(&post, &original, Some(&reverted)).is_err());
+    }
+
+    #[test]
+    fn posts_are_indexed_by_their_author_under_their_authors() {
+        let mut post = post(agent(1));
+        post.co_authors = vec![agent(2)];
+        let record = post_record(post.clone(), 3);
+        let action = create_link(agent(1), 2_000_000);
+        assert_eq!(
+            check_create_link_author_to_posts(&action, &agent(2).into(), &record, &post),
+            Ok(())
+        );
+        assert!(
+            check_create_link_author_to_posts(&action, &agent(3).into(), &record, &post).is_err()
+        );
+        let action = create_link(agent(2), 2_000_000);
+        assert_eq!(
+            check_create_link_author_to_posts(&action, &agent(2).into(), &record, &post)
+                .unwrap_err()
+                .code(),
+            "UNAUTHORIZED"
+        );
+    }
+
+    #[test]
+    fn only_the_author_lists_and_unlists_a_post() {
+        let record = post_record(post(agent(1)), 3);
+        let action = create_link(agent(1), 2_000_000);
+        assert_eq!(check_create_link_all_posts(&action, &record), Ok(()));
+        assert_eq!(
+            check_create_link_post_revision_tags(&action, &record),
+            Ok(())
+        );
+        assert_eq!(
+            check_create_link_author_to_unlisted_posts(&action, &agent(1).into(), &record),
+            Ok(())
+        );
+        assert!(
+            check_create_link_author_to_unlisted_posts(&action, &agent(2).into(), &record).is_err()
+        );
+        let action = create_link(agent(2), 2_000_000);
+        assert!(check_create_link_all_posts(&action, &record).is_err());
+        assert!(check_create_link_post_revision_tags(&action, &record).is_err());
+        assert!(
+            check_create_link_author_to_unlisted_posts(&action, &agent(2).into(), &record).is_err()
+        );
+    }
+
+    fn delete_of(deleted_post_hash: &ActionHash, timestamp: i64) -> Action {
+        Action::Delete(Delete {
+            author: agent(1),
+            timestamp: Timestamp::from_micros(timestamp),
+            action_seq: 4,
+            prev_action: action_hash(0),
+            deletes_address: deleted_post_hash.clone(),
+            deletes_entry_address: entry_hash(0),
+            weight: Default::default(),
+        })
+    }
+
+    fn restoration_tag(delete_hash: ActionHash) -> LinkTag {
+        LinkTag::new(
+            SerializedBytes::try_from(RestorationTag { delete_hash })
+                .unwrap()
+                .bytes()
+                .clone(),
+        )
+    }
+
+    #[test]
+    fn only_the_author_can_request_a_restoration() {
+        let deleted_record = post_record(post(agent(1)), 3);
+        let tag = restoration_tag(action_hash(4));
+        let restoration = check_restoration_request(
+            &create_link(agent(1), 2_000_000),
+            &deleted_record,
+            tag.clone(),
+        )
+        .unwrap();
+        assert_eq!(restoration.delete_hash, action_hash(4));
+        assert_eq!(
+            check_restoration_request(&create_link(agent(2), 2_000_000), &deleted_record, tag)
+                .unwrap_err()
+                .code(),
+            "UNAUTHORIZED"
+        );
+        assert!(check_restoration_request(
+            &create_link(agent(1), 2_000_000),
+            &deleted_record,
+            LinkTag::new(vec![1, 2, 3])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn restorations_must_undo_a_delete_of_the_post() {
+        let action = create_link(agent(1), 2_000_000);
+        let restored = post_record(post(agent(1)), 5);
+        let delete = delete_of(&action_hash(1), 1_500_000);
+        assert_eq!(
+            check_restoration(&action, &action_hash(1), &delete, &restored, 60),
+            Ok(())
+        );
+        assert!(check_restoration(&action, &action_hash(2), &delete, &restored, 60).is_err());
+        let not_a_delete = Action::Create(create(agent(1), 4, 1_500_000));
+        assert!(check_restoration(&action, &action_hash(1), &not_a_delete, &restored, 60).is_err());
+        let someone_elses = post_record(post(agent(2)), 5);
+        assert!(check_restoration(&action, &action_hash(1), &delete, &someone_elses, 60).is_err());
+    }
+
+    #[test]
+    fn grace_periods_and_timestamps_out_of_range_do_not_overflow() {
+        let (min, max) = (
+            Timestamp::from_micros(i64::MIN),
+            Timestamp::from_micros(i64::MAX),
+        );
+        assert!(!is_restore_grace_period_expired(min, max, i64::MAX));
+        assert!(is_restore_grace_period_expired(
+            min,
+            max,
+            i64::MAX / 1_000_000 - 1
+        ));
+        assert!(!is_restore_grace_period_expired(max, min, 0));
+        let action = create_link(agent(1), i64::MAX);
+        let delete = delete_of(&action_hash(1), 1_000_000);
+        let restored = post_record(post(agent(1)), 5);
+        assert_eq!(
+            check_restoration(&action, &action_hash(1), &delete, &restored, i64::MAX),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn delete_links_need_the_creator_of_the_link() {
+        let original = create_link(agent(1), 1_000_000);
+        let base: AnyLinkableHash = agent(1).into();
+        let target: AnyLinkableHash = action_hash(1).into();
+        let tag = LinkTag::new(vec![]);
+        for validate in [
+            validate_delete_link_author_to_posts,
+            validate_delete_link_all_posts,
+            validate_delete_link_author_to_unlisted_posts,
+            validate_delete_link_post_revision_tags,
+        ] {
+            let valid = validate(
+                delete_link(agent(1)),
+                original.clone(),
+                base.clone(),
+                target.clone(),
+                tag.clone(),
+            );
+            assert_eq!(valid, Ok(ValidateCallbackResult::Valid));
+            let invalid = validate(
+                delete_link(agent(2)),
+                original.clone(),
+                base.clone(),
+                target.clone(),
+                tag.clone(),
+            );
+            assert!(matches!(invalid, Ok(ValidateCallbackResult::Invalid(_))));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn restorations_expire_after_the_grace_period(
+            deleted_at in 1i64..1_000_000_000,
+            elapsed in 0i64..1_000_000_000,
+            grace_period in 0i64..=i64::MAX,
+        ) {
+            let action = create_link(agent(1), deleted_at + elapsed);
+            let delete = delete_of(&action_hash(1), deleted_at);
+            let restored = post_record(post(agent(1)), 5);
+            let result = check_restoration(&action, &action_hash(1), &delete, &restored, grace_period);
+            if i128::from(elapsed) > i128::from(grace_period) * 1_000_000 {
+                prop_assert_eq!(result, Err(BlogError::RestoreGracePeriodExpired));
+            } else {
+                prop_assert_eq!(result, Ok(()));
+            }
+        }
+
+        #[test]
+        fn co_authored_posts_need_exactly_their_authors_as_signers(
+            author in arb_agent(),
+            co_authors in prop::collection::vec(arb_agent(), 1..4),
+            signers in prop::collection::btree_set(arb_agent(), 2..5),
+        ) {
+            let mut post = post(author.clone());
+            post.co_authors = co_authors.clone();
+            let signers: Vec<AgentPubKey> = signers.into_iter().collect();
+            let entry = countersigned(&post, &signers, &[]);
+            let authors: Vec<&AgentPubKey> = std::iter::once(&author).chain(co_authors.iter()).collect();
+            let distinct = authors.iter().collect::<BTreeSet<_>>().len() == authors.len();
+            let signed_by_authors = signers.len() == authors.len()
+                && authors.iter().all(|author| signers.contains(author));
+            prop_assert_eq!(
+                check_co_authors(&post, &entry).is_ok(),
+                distinct && signed_by_authors
+            );
+        }
+
+        #[test]
+        fn only_an_author_can_index_a_post_under_an_author(
+            author in arb_agent(),
+            co_author in arb_agent(),
+            linker in arb_agent(),
+            base in arb_agent(),
+        ) {
+            let mut post = post(author.clone());
+            post.co_authors = vec![co_author.clone()];
+            let record = post_record(post.clone(), 3);
+            let action = create_link(linker.clone(), 2_000_000);
+            let result = check_create_link_author_to_posts(&action, &base.clone().into(), &record, &post);
+            prop_assert_eq!(
+                result.is_ok(),
+                linker == author && (base == author || base == co_author)
+            );
+        }
+    }
 }
@@ -0,0 +1,274 @@
+use std::collections::BTreeSet;
+
+use hdi::prelude::*;
+
+use crate::{BlogError, Post, RuleResult};
+
+/// An X25519 public key that an agent publishes so that private posts can be shared with them
+#[derive(Clone, PartialEq)]
+#[hdk_entry_helper]
+pub struct EncryptionKey {
+    pub key: X25519PubKey,
+}
+
+/// The symmetric key of a private post, wrapped for one of its recipients
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WrappedKey {
+    pub recipient: AgentPubKey,
+    // The published Encryption Key of the recipient that the key was wrapped for
+    pub recipient_key_hash: ActionHash,
+    pub encrypted_key: XSalsa20Poly1305EncryptedData,
+}
+
+/// The encrypted content of a private post, readable only by its recipients
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PostEnvelope {
+    // The published Encryption Key of the author that wrapped the key for every recipient
+    pub sender_key_hash: ActionHash,
+    pub encrypted_content: XSalsa20Poly1305EncryptedData,
+    pub recipients: Vec<WrappedKey>,
+}
+
+pub fn validate_create_encryption_key(
+    _action: EntryCreationAction,
+    _encryption_key: EncryptionKey,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_update_encryption_key(
+    _action: Update,
+    _encryption_key: EncryptionKey,
+    _original_action: EntryCreationAction,
+    _original_encryption_key: EncryptionKey,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(BlogError::Immutable("Encryption Keys cannot be updated".to_string()).into())
+}
+
+pub fn validate_delete_encryption_key(
+    _action: Delete,
+    _original_action: EntryCreationAction,
+    _original_encryption_key: EncryptionKey,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(BlogError::Immutable("Encryption Keys cannot be deleted".to_string()).into())
+}
+
+/// Checks that the Encryption Key was published by the given agent
+pub fn check_published_key(key_record: &Record, agent: &AgentPubKey) -> RuleResult {
+    if EncryptionKey::try_from(key_record).is_err() {
+        return Err(BlogError::Invalid(
+            "A private Post must reference published Encryption Keys".to_string(),
+        ));
+    }
+    if key_record.action().author() != agent {
+        return Err(BlogError::Unauthorized(
+            "An Encryption Key can only be used for the agent that published it".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the envelope of a private post is well-formed: its content is only
+/// stored encrypted, and its key is wrapped once for each recipient, including the author
+pub fn check_post_envelope(post: &Post) -> RuleResult {
+    let Some(envelope) = &post.envelope else {
+        return Ok(());
+    };
+    if !post.content.is_empty() {
+        return Err(BlogError::Invalid(
+            "The content of a private Post must only be stored encrypted".to_string(),
+        ));
+    }
+    if envelope
+        .encrypted_content
+        .as_encrypted_data_ref()
+        .is_empty()
+    {
+        return Err(BlogError::Invalid(
+            "A private Post must have encrypted content".to_string(),
+        ));
+    }
+    let mut recipients = BTreeSet::new();
+    for wrapped_key in &envelope.recipients {
+        if !recipients.insert(&wrapped_key.recipient) {
+            return Err(BlogError::Invalid(
+                "The key of a private Post must be wrapped once for each recipient".to_string(),
+            ));
+        }
+    }
+    if !recipients.contains(&post.author) {
+        return Err(BlogError::Invalid(
+            "The author of a private Post must be one of its recipients".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that every key in the envelope of a private post was published by the agent it is used for
+pub fn validate_post_envelope_keys(post: &Post) -> ExternResult<ValidateCallbackResult> {
+    let Some(envelope) = &post.envelope else {
+        return Ok(ValidateCallbackResult::Valid);
+    };
+    let keys = envelope
+        .recipients
+        .iter()
+        .map(|wrapped_key| (&wrapped_key.recipient_key_hash, &wrapped_key.recipient))
+        .chain(std::iter::once((&envelope.sender_key_hash, &post.author)));
+    for (key_hash, agent) in keys {
+        let record = must_get_valid_record(key_hash.clone())?;
+        if let Err(error) = check_published_key(&record, agent) {
+            return Ok(error.into());
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_create_link_agent_to_encryption_keys(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    let record = must_get_valid_record(action_hash)?;
+    let _encryption_key: crate::EncryptionKey = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BlogError::MissingEntry)?;
+    Ok(crate::validation_result(
+        check_create_link_agent_to_encryption_keys(&action, &base_address, &record),
+    ))
+}
+
+pub fn check_create_link_agent_to_encryption_keys(
+    action: &CreateLink,
+    base_address: &AnyLinkableHash,
+    key_record: &Record,
+) -> RuleResult {
+    if key_record.action().author() != &action.author
+        || base_address != &AnyLinkableHash::from(action.author.clone())
+    {
+        return Err(BlogError::Unauthorized(
+            "Agents can only link their own Encryption Keys".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_delete_link_agent_to_encryption_keys(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(BlogError::Immutable("AgentToEncryptionKeys links cannot be deleted".to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    fn encrypted(data: Vec<u8>) -> XSalsa20Poly1305EncryptedData {
+        XSalsa20Poly1305EncryptedData::new([0; 24].into(), data)
+    }
+
+    fn private_post(author: AgentPubKey, recipients: &[AgentPubKey]) -> Post {
+        let mut post = post(author);
+        post.content = String::new();
+        post.envelope = Some(PostEnvelope {
+            sender_key_hash: action_hash(1),
+            encrypted_content: encrypted(vec![1, 2, 3]),
+            recipients: recipients
+                .iter()
+                .map(|recipient| WrappedKey {
+                    recipient: recipient.clone(),
+                    recipient_key_hash: action_hash(1),
+                    encrypted_key: encrypted(vec![4]),
+                })
+                .collect(),
+        });
+        post
+    }
+
+    fn key_record(author: AgentPubKey) -> Record {
+        record(
+            Action::Create(create(author, 3, 1_000_000)),
+            Some(
+                Entry::try_from(EncryptionKey {
+                    key: [7; 32].into(),
+                })
+                .unwrap(),
+            ),
+        )
+    }
+
+    #[test]
+    fn public_posts_have_no_envelope_to_check() {
+        assert_eq!(check_post_envelope(&post(agent(1))), Ok(()));
+    }
+
+    #[test]
+    fn private_posts_only_store_encrypted_content() {
+        let mut post = private_post(agent(1), &[agent(1), agent(2)]);
+        assert_eq!(check_post_envelope(&post), Ok(()));
+        post.content = "Leaked".to_string();
+        assert!(check_post_envelope(&post).is_err());
+        let mut post = private_post(agent(1), &[agent(1)]);
+        post.envelope.as_mut().unwrap().encrypted_content = encrypted(vec![]);
+        assert!(check_post_envelope(&post).is_err());
+    }
+
+    #[test]
+    fn published_keys_must_be_encryption_keys_of_the_agent() {
+        assert_eq!(
+            check_published_key(&key_record(agent(1)), &agent(1)),
+            Ok(())
+        );
+        assert_eq!(
+            check_published_key(&key_record(agent(1)), &agent(2))
+                .unwrap_err()
+                .code(),
+            "UNAUTHORIZED"
+        );
+        assert!(check_published_key(&post_record(post(agent(1)), 3), &agent(1)).is_err());
+    }
+
+    #[test]
+    fn agents_only_link_their_own_keys() {
+        let record = key_record(agent(1));
+        let action = create_link(agent(1), 2_000_000);
+        assert_eq!(
+            check_create_link_agent_to_encryption_keys(&action, &agent(1).into(), &record),
+            Ok(())
+        );
+        assert!(
+            check_create_link_agent_to_encryption_keys(&action, &agent(2).into(), &record).is_err()
+        );
+        let action = create_link(agent(2), 2_000_000);
+        assert!(
+            check_create_link_agent_to_encryption_keys(&action, &agent(2).into(), &record).is_err()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn keys_are_wrapped_once_for_each_recipient_including_the_author(
+            author in arb_agent(),
+            recipients in prop::collection::vec(arb_agent(), 0..6),
+        ) {
+            let post = private_post(author.clone(), &recipients);
+            let distinct = recipients.iter().collect::<BTreeSet<_>>().len() == recipients.len();
+            prop_assert_eq!(
+                check_post_envelope(&post).is_ok(),
+                distinct && recipients.contains(&author)
+            );
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use hdi::prelude::holo_hash::AgentPubKeyB64;
+use hdi::prelude::*;
+
+// One week, used when the DNA properties don't configure a grace period
+pub const DEFAULT_RESTORE_GRACE_PERIOD_SECS: i64 = 7 * 24 * 60 * 60;
+
+// 16 MiB, used when the DNA properties don't configure a maximum file size
+pub const DEFAULT_MAX_FILE_SIZE: usize = 16 * 1024 * 1024;
+
+/// The properties that can be set in `dna.yaml` to configure this DNA
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, Default)]
+pub struct DnaProperties {
+    #[serde(default)]
+    pub restore_grace_period_secs: Option<i64>,
+    #[serde(default)]
+    pub progenitor: Option<AgentPubKeyB64>,
+    #[serde(default)]
+    pub moderators: Vec<AgentPubKeyB64>,
+    #[serde(default)]
+    pub max_file_size: Option<usize>,
+}
+
+impl DnaProperties {
+    /// Reads the properties of this DNA, falling back to the defaults if they are missing or malformed
+    pub fn get() -> ExternResult<DnaProperties> {
+        let properties = dna_info()?.modifiers.properties;
+        Ok(DnaProperties::try_from(properties).unwrap_or_default())
+    }
+
+    pub fn restore_grace_period(&self) -> i64 {
+        self.restore_grace_period_secs
+            .unwrap_or(DEFAULT_RESTORE_GRACE_PERIOD_SECS)
+    }
+
+    pub fn max_file_size(&self) -> usize {
+        self.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE)
+    }
+
+    /// Whether the agent is the progenitor of this DNA or one of its moderators
+    pub fn is_moderator(&self, agent: &AgentPubKey) -> bool {
+        self.progenitor
+            .iter()
+            .chain(self.moderators.iter())
+            .any(|moderator| &AgentPubKey::from(moderator.clone()) == agent)
+    }
+}
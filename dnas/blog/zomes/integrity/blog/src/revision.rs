@@ -0,0 +1,218 @@
+use hdi::prelude::*;
+
+use crate::{BlogError, RuleResult};
+
+/// Follows the update chain of the given revision back to the action that originally created the entry
+pub fn get_original_action_hash(revision_hash: ActionHash) -> ExternResult<ActionHash> {
+    let mut action_hash = revision_hash;
+    loop {
+        let action = must_get_action(action_hash.clone())?;
+        match action.action() {
+            Action::Update(update) => action_hash = update.original_action_address.clone(),
+            _ => return Ok(action_hash),
+        }
+    }
+}
+
+/// Returns the entry of the latest revision of an entry among the actions of a source chain, following
+/// each update from the revision it updates, or nothing if the chain never updates the entry
+pub fn latest_revision_entry_hash(
+    original_action_hash: &ActionHash,
+    activity: &[RegisterAgentActivity],
+) -> Option<EntryHash> {
+    let mut actions: Vec<&SignedActionHashed> =
+        activity.iter().map(|activity| &activity.action).collect();
+    actions.sort_by_key(|action| action.action().action_seq());
+    let mut revision_hashes = vec![original_action_hash];
+    let mut latest_entry_hash = None;
+    for action in actions {
+        let Action::Update(update) = action.action() else {
+            continue;
+        };
+        if revision_hashes.contains(&&update.original_action_address) {
+            revision_hashes.push(action.action_address());
+            latest_entry_hash = Some(update.entry_hash.clone());
+        }
+    }
+    latest_entry_hash
+}
+
+/// The revision that a revert update restores, resolved together with the original actions
+/// of both the updated entry and the revision
+#[derive(Debug, Clone)]
+pub struct RevertedRevision {
+    pub original_action_hash: ActionHash,
+    pub revision_original_action_hash: ActionHash,
+    pub revision: Record,
+}
+
+pub fn must_get_reverted_revision(
+    action: &Update,
+    reverted_from: &ActionHash,
+) -> ExternResult<RevertedRevision> {
+    Ok(RevertedRevision {
+        original_action_hash: get_original_action_hash(action.original_action_address.clone())?,
+        revision_original_action_hash: get_original_action_hash(reverted_from.clone())?,
+        revision: must_get_valid_record(reverted_from.clone())?,
+    })
+}
+
+/// Checks that a revert update points to a revision of the same original entry that is being updated
+pub fn check_reverted_revision(reverted: &RevertedRevision) -> RuleResult {
+    if reverted.original_action_hash != reverted.revision_original_action_hash {
+        return Err(BlogError::Invalid(
+            "A revert can only restore a revision of the same original entry".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub const MAX_EDIT_REASON_LENGTH: usize = 200;
+
+/// Describes why and how an entry was edited, recorded on every update that carries it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EditMetadata {
+    pub reason: Option<String>,
+    pub minor: bool,
+}
+
+impl EditMetadata {
+    pub fn new(reason: Option<String>, minor: bool) -> Option<EditMetadata> {
+        match (reason, minor) {
+            (None, false) => None,
+            (reason, minor) => Some(EditMetadata { reason, minor }),
+        }
+    }
+}
+
+pub fn check_edit_metadata(
+    action: &EntryCreationAction,
+    edit: &Option<EditMetadata>,
+) -> RuleResult {
+    let Some(edit) = edit else {
+        return Ok(());
+    };
+    if let EntryCreationAction::Create(_) = action {
+        return Err(BlogError::Invalid(
+            "Edit metadata can only be recorded on updates".to_string(),
+        ));
+    }
+    if let Some(reason) = &edit.reason {
+        if reason.chars().count() > MAX_EDIT_REASON_LENGTH {
+            return Err(BlogError::Invalid(format!(
+                "Edit reason must be at most {MAX_EDIT_REASON_LENGTH} characters long"
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    fn reverted(original: ActionHash, revision_original: ActionHash) -> RevertedRevision {
+        RevertedRevision {
+            original_action_hash: original,
+            revision_original_action_hash: revision_original,
+            revision: post_record(post(agent(1)), 3),
+        }
+    }
+
+    #[test]
+    fn reverts_within_the_same_entry_are_valid() {
+        assert_eq!(
+            check_reverted_revision(&reverted(action_hash(1), action_hash(1))),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn reverts_to_another_entry_are_invalid() {
+        assert_eq!(
+            check_reverted_revision(&reverted(action_hash(1), action_hash(2)))
+                .unwrap_err()
+                .code(),
+            "INVALID"
+        );
+    }
+
+    fn activity(action: Action, hash: u8) -> RegisterAgentActivity {
+        RegisterAgentActivity {
+            action: SignedActionHashed::with_presigned(
+                ActionHashed::with_pre_hashed(action, action_hash(hash)),
+                Signature([0; 64]),
+            ),
+            cached_entry: None,
+        }
+    }
+
+    fn revision(updated_hash: u8, action_seq: u32, entry: u8) -> Action {
+        let mut update = update(agent(1), action_hash(updated_hash));
+        update.action_seq = action_seq;
+        update.entry_hash = entry_hash(entry);
+        Action::Update(update)
+    }
+
+    #[test]
+    fn the_latest_revision_follows_the_updates_of_the_entry() {
+        let original = activity(Action::Create(create(agent(1), 3, 1_000_000)), 1);
+        assert_eq!(
+            latest_revision_entry_hash(&action_hash(1), std::slice::from_ref(&original)),
+            None
+        );
+        let activity = vec![
+            activity(revision(2, 6, 8), 3),
+            activity(revision(1, 4, 7), 2),
+            activity(revision(9, 5, 6), 4),
+            original,
+        ];
+        assert_eq!(
+            latest_revision_entry_hash(&action_hash(1), &activity),
+            Some(entry_hash(8))
+        );
+        assert_eq!(
+            latest_revision_entry_hash(&action_hash(2), &activity),
+            Some(entry_hash(8))
+        );
+        assert_eq!(latest_revision_entry_hash(&action_hash(5), &activity), None);
+    }
+
+    #[test]
+    fn edit_metadata_is_only_recorded_on_updates() {
+        let edit = EditMetadata::new(Some("Typo".to_string()), false);
+        let create = EntryCreationAction::Create(create(agent(1), 3, 1_000_000));
+        let update = EntryCreationAction::Update(update(agent(1), action_hash(1)));
+        assert!(check_edit_metadata(&create, &edit).is_err());
+        assert_eq!(check_edit_metadata(&update, &edit), Ok(()));
+        assert_eq!(check_edit_metadata(&create, &None), Ok(()));
+    }
+
+    #[test]
+    fn edit_metadata_new_omits_empty_metadata() {
+        assert_eq!(EditMetadata::new(None, false), None);
+        assert!(EditMetadata::new(None, true).is_some());
+    }
+
+    proptest! {
+        #[test]
+        fn edit_reasons_are_limited_in_length(reason in "\\PC{0,300}", minor: bool) {
+            let too_long = reason.chars().count() > MAX_EDIT_REASON_LENGTH;
+            let edit = EditMetadata::new(Some(reason), minor);
+            let update = EntryCreationAction::Update(update(agent(1), action_hash(1)));
+            prop_assert_eq!(check_edit_metadata(&update, &edit).is_err(), too_long);
+        }
+
+        #[test]
+        fn reverts_are_valid_iff_the_originals_match(
+            original in arb_action_hash(),
+            revision_original in arb_action_hash(),
+        ) {
+            let result = check_reverted_revision(&reverted(original.clone(), revision_original.clone()));
+            prop_assert_eq!(result.is_ok(), original == revision_original);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use hdi::prelude::*;
+
+use crate::{
+    BlogError, CommentVersions, PostVersions, RuleResult, UnitEntryTypes, COMMENT_SCHEMA_VERSION,
+    POST_SCHEMA_VERSION,
+};
+
+/// Rejects new Post and Comment entries that are not written with their latest schema version,
+/// while entries written with older versions stay readable
+pub fn validate_schema_version(op: &Op) -> ExternResult<ValidateCallbackResult> {
+    let (entry_type, entry) = match op {
+        Op::StoreEntry(StoreEntry { action, entry }) => (action.hashed.entry_type(), entry),
+        Op::StoreRecord(StoreRecord { record }) => {
+            match (record.action().entry_type(), record.entry().as_option()) {
+                (Some(entry_type), Some(entry)) => (entry_type, entry),
+                _ => return Ok(ValidateCallbackResult::Valid),
+            }
+        }
+        Op::RegisterUpdate(RegisterUpdate {
+            update,
+            new_entry: Some(entry),
+        }) => (&update.hashed.entry_type, entry),
+        _ => return Ok(ValidateCallbackResult::Valid),
+    };
+    let bytes = match entry {
+        Entry::App(bytes) | Entry::CounterSign(_, bytes) => bytes,
+        _ => return Ok(ValidateCallbackResult::Valid),
+    };
+    let (schema_version, latest_schema_version) =
+        if entry_type == &EntryType::try_from(UnitEntryTypes::Post)? {
+            let versions: PostVersions =
+                decode(bytes.bytes()).map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+            (versions.schema_version(), POST_SCHEMA_VERSION)
+        } else if entry_type == &EntryType::try_from(UnitEntryTypes::Comment)? {
+            let versions: CommentVersions =
+                decode(bytes.bytes()).map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+            (versions.schema_version(), COMMENT_SCHEMA_VERSION)
+        } else {
+            return Ok(ValidateCallbackResult::Valid);
+        };
+    Ok(crate::validation_result(check_schema_version(
+        schema_version,
+        latest_schema_version,
+    )))
+}
+
+pub fn check_schema_version(schema_version: u32, latest_schema_version: u32) -> RuleResult {
+    if schema_version != latest_schema_version {
+        return Err(BlogError::Invalid(format!(
+            "New entries must be written with schema version {latest_schema_version}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn new_entries_are_written_with_the_latest_schema_version() {
+        let versions = PostVersions::from(post(agent(1)));
+        assert_eq!(
+            check_schema_version(versions.schema_version(), POST_SCHEMA_VERSION),
+            Ok(())
+        );
+        let versions = CommentVersions::from(comment(agent(1), action_hash(1)));
+        assert_eq!(
+            check_schema_version(versions.schema_version(), COMMENT_SCHEMA_VERSION),
+            Ok(())
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn only_the_latest_schema_version_is_accepted(version: u32, latest: u32) {
+            prop_assert_eq!(check_schema_version(version, latest).is_ok(), version == latest);
+        }
+    }
+}
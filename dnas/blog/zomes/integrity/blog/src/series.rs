@@ -0,0 +1,284 @@
+use hdi::prelude::*;
+
+use crate::{BlogError, RuleResult};
+
+#[derive(Clone, PartialEq)]
+#[hdk_entry_helper]
+pub struct Series {
+    pub title: String,
+    pub description: String,
+    // Original action hashes of the posts in the series, in reading order
+    pub posts: Vec<ActionHash>,
+    pub author: AgentPubKey,
+}
+
+/// The rules of a Series that only depend on the entry itself
+pub fn check_create_series(action: &EntryCreationAction, series: &Series) -> RuleResult {
+    if action.author() != &series.author {
+        return Err(BlogError::Unauthorized(
+            "A Series can only be created by its author".to_string(),
+        ));
+    }
+    for (index, post_hash) in series.posts.iter().enumerate() {
+        if series.posts[..index].contains(post_hash) {
+            return Err(BlogError::Invalid(
+                "A Post can only appear once in a Series".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that every post of a Series, given as the records that created them, is by its author
+pub fn check_series_posts(series: &Series, post_records: &[Record]) -> RuleResult {
+    if post_records
+        .iter()
+        .any(|record| record.action().author() != &series.author)
+    {
+        return Err(BlogError::Unauthorized(
+            "Every Post in a Series must be by the author of the Series".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_create_series(
+    action: EntryCreationAction,
+    series: Series,
+) -> ExternResult<ValidateCallbackResult> {
+    if let Err(error) = check_create_series(&action, &series) {
+        return Ok(error.into());
+    }
+    let mut post_records = Vec::with_capacity(series.posts.len());
+    for post_hash in &series.posts {
+        let record = must_get_valid_record(post_hash.clone())?;
+        let _post: crate::Post = record
+            .entry()
+            .to_app_option()
+            .map_err(|e| wasm_error!(e))?
+            .ok_or(BlogError::MissingEntry)?;
+        post_records.push(record);
+    }
+    Ok(crate::validation_result(check_series_posts(
+        &series,
+        &post_records,
+    )))
+}
+
+pub fn validate_update_series(
+    action: Update,
+    series: Series,
+    original_action: EntryCreationAction,
+    original_series: Series,
+) -> ExternResult<ValidateCallbackResult> {
+    if &action.author != original_action.author() || series.author != original_series.author {
+        return Ok(
+            BlogError::Unauthorized("Only the owner of a Series can modify it".to_string()).into(),
+        );
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_delete_series(
+    action: Delete,
+    original_action: EntryCreationAction,
+    _original_series: Series,
+) -> ExternResult<ValidateCallbackResult> {
+    if &action.author != original_action.author() {
+        return Ok(
+            BlogError::Unauthorized("Only the owner of a Series can delete it".to_string()).into(),
+        );
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_create_link_author_to_series(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    let record = must_get_valid_record(action_hash)?;
+    let series: crate::Series = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BlogError::MissingEntry)?;
+    Ok(crate::validation_result(
+        check_create_link_author_to_series(&action, &base_address, &series),
+    ))
+}
+
+pub fn check_create_link_author_to_series(
+    action: &CreateLink,
+    base_address: &AnyLinkableHash,
+    series: &Series,
+) -> RuleResult {
+    if action.author != series.author
+        || base_address != &AnyLinkableHash::from(series.author.clone())
+    {
+        return Err(BlogError::Unauthorized(
+            "A Series can only be linked from its owner".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_delete_link_author_to_series(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(
+            BlogError::Unauthorized("Only the owner of a Series can unlink it".to_string()).into(),
+        );
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_create_link_post_to_series(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = base_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    let record = must_get_valid_record(action_hash)?;
+    let _post: crate::Post = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BlogError::MissingEntry)?;
+    let action_hash = target_address
+        .into_action_hash()
+        .ok_or(BlogError::MalformedLink)?;
+    let record = must_get_valid_record(action_hash)?;
+    let series: crate::Series = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BlogError::MissingEntry)?;
+    Ok(crate::validation_result(check_create_link_post_to_series(
+        &action, &series,
+    )))
+}
+
+pub fn check_create_link_post_to_series(action: &CreateLink, series: &Series) -> RuleResult {
+    if action.author != series.author {
+        return Err(BlogError::Unauthorized(
+            "Only the owner of a Series can add Posts to it".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_delete_link_post_to_series(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(BlogError::Unauthorized(
+            "Only the owner of a Series can remove Posts from it".to_string(),
+        )
+        .into());
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    fn series(author: AgentPubKey, posts: Vec<ActionHash>) -> Series {
+        Series {
+            title: "Series".to_string(),
+            description: "Description".to_string(),
+            posts,
+            author,
+        }
+    }
+
+    #[test]
+    fn series_are_created_by_their_author() {
+        let series = series(agent(1), vec![action_hash(1), action_hash(2)]);
+        let action = EntryCreationAction::Create(create(agent(1), 3, 1_000_000));
+        assert_eq!(check_create_series(&action, &series), Ok(()));
+        let action = EntryCreationAction::Create(create(agent(2), 3, 1_000_000));
+        assert_eq!(
+            check_create_series(&action, &series).unwrap_err().code(),
+            "UNAUTHORIZED"
+        );
+    }
+
+    #[test]
+    fn series_only_contain_posts_by_their_author() {
+        let series = series(agent(1), vec![action_hash(1), action_hash(2)]);
+        let own = post_record(post(agent(1)), 3);
+        let other = post_record(post(agent(2)), 3);
+        assert_eq!(
+            check_series_posts(&series, &[own.clone(), own.clone()]),
+            Ok(())
+        );
+        assert!(check_series_posts(&series, &[own, other]).is_err());
+    }
+
+    #[test]
+    fn series_are_linked_by_their_owner() {
+        let series = series(agent(1), vec![]);
+        let action = create_link(agent(1), 2_000_000);
+        assert_eq!(
+            check_create_link_author_to_series(&action, &agent(1).into(), &series),
+            Ok(())
+        );
+        assert!(check_create_link_author_to_series(&action, &agent(2).into(), &series).is_err());
+        assert_eq!(check_create_link_post_to_series(&action, &series), Ok(()));
+        let action = create_link(agent(2), 2_000_000);
+        assert!(check_create_link_post_to_series(&action, &series).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn posts_appear_once_in_a_series(posts in prop::collection::vec(arb_action_hash(), 0..8)) {
+            let series = series(agent(1), posts.clone());
+            let action = EntryCreationAction::Create(create(agent(1), 3, 1_000_000));
+            let distinct = posts.iter().collect::<std::collections::BTreeSet<_>>().len() == posts.len();
+            prop_assert_eq!(check_create_series(&action, &series).is_ok(), distinct);
+        }
+
+        #[test]
+        fn only_the_owner_modifies_a_series(
+            owner in arb_agent(),
+            editor in arb_agent(),
+            new_author in arb_agent(),
+        ) {
+            let original = series(owner.clone(), vec![]);
+            let updated = series(new_author.clone(), vec![]);
+            let original_action = EntryCreationAction::Create(create(owner.clone(), 3, 1_000_000));
+            let result = validate_update_series(
+                update(editor.clone(), action_hash(1)),
+                updated,
+                original_action,
+                original,
+            )
+            .unwrap();
+            prop_assert_eq!(
+                result == ValidateCallbackResult::Valid,
+                editor == owner && new_author == owner
+            );
+        }
+    }
+}
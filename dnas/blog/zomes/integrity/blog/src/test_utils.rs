@@ -0,0 +1,132 @@
+//! Fixtures to build the already-resolved data that the validation rules are checked against
+
+use hdi::prelude::*;
+use proptest::prelude::*;
+
+use crate::{Comment, ContentFormat, Post};
+
+pub fn agent(n: u8) -> AgentPubKey {
+    AgentPubKey::from_raw_36(vec![n; 36])
+}
+
+pub fn action_hash(n: u8) -> ActionHash {
+    ActionHash::from_raw_36(vec![n; 36])
+}
+
+pub fn entry_hash(n: u8) -> EntryHash {
+    EntryHash::from_raw_36(vec![n; 36])
+}
+
+pub fn app_entry_type() -> EntryType {
+    EntryType::App(AppEntryDef::new(
+        0.into(),
+        0.into(),
+        EntryVisibility::Public,
+    ))
+}
+
+pub fn create(author: AgentPubKey, action_seq: u32, timestamp: i64) -> Create {
+    Create {
+        author,
+        timestamp: Timestamp::from_micros(timestamp),
+        action_seq,
+        prev_action: action_hash(0),
+        entry_type: app_entry_type(),
+        entry_hash: entry_hash(0),
+        weight: Default::default(),
+    }
+}
+
+pub fn update(author: AgentPubKey, original_action_address: ActionHash) -> Update {
+    Update {
+        author,
+        timestamp: Timestamp::from_micros(2_000_000),
+        action_seq: 10,
+        prev_action: action_hash(0),
+        original_action_address,
+        original_entry_address: entry_hash(0),
+        entry_type: app_entry_type(),
+        entry_hash: entry_hash(1),
+        weight: Default::default(),
+    }
+}
+
+pub fn create_link(author: AgentPubKey, timestamp: i64) -> CreateLink {
+    CreateLink {
+        author,
+        timestamp: Timestamp::from_micros(timestamp),
+        action_seq: 10,
+        prev_action: action_hash(0),
+        base_address: action_hash(0).into(),
+        target_address: action_hash(0).into(),
+        zome_index: 0.into(),
+        link_type: 0.into(),
+        tag: LinkTag::new(vec![]),
+        weight: Default::default(),
+    }
+}
+
+pub fn delete_link(author: AgentPubKey) -> DeleteLink {
+    DeleteLink {
+        author,
+        timestamp: Timestamp::from_micros(2_000_000),
+        action_seq: 10,
+        prev_action: action_hash(0),
+        base_address: action_hash(0).into(),
+        link_add_address: action_hash(0),
+    }
+}
+
+/// A record of the given action, hashed with a fixed hash since no rule checks action hashes
+pub fn record(action: Action, entry: Option<Entry>) -> Record {
+    Record::new(
+        SignedActionHashed::with_presigned(
+            ActionHashed::with_pre_hashed(action, action_hash(0xAA)),
+            Signature([0; 64]),
+        ),
+        entry,
+    )
+}
+
+pub fn post(author: AgentPubKey) -> Post {
+    Post {
+        name: "Title".to_string(),
+        content: "Content".to_string(),
+        author,
+        co_authors: vec![],
+        delegate: None,
+        envelope: None,
+        format: ContentFormat::Plain,
+        attachments: vec![],
+        reverted_from: None,
+        edit: None,
+    }
+}
+
+pub fn comment(author: AgentPubKey, post_hash: ActionHash) -> Comment {
+    Comment {
+        content: "Comment".to_string(),
+        post_hash,
+        author,
+        reverted_from: None,
+        edit: None,
+        comment_lock_hash: None,
+    }
+}
+
+pub fn post_record(post: Post, action_seq: u32) -> Record {
+    let author = post.author.clone();
+    record(
+        Action::Create(create(author, action_seq, 1_000_000)),
+        Some(Entry::try_from(post).unwrap()),
+    )
+}
+
+/// One of a handful of agents, so that generated agents regularly coincide
+pub fn arb_agent() -> impl Strategy<Value = AgentPubKey> {
+    (1u8..6).prop_map(agent)
+}
+
+pub fn arb_action_hash() -> impl Strategy<Value = ActionHash> {
+    (1u8..6).prop_map(action_hash)
+}
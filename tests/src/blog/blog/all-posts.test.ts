@@ -1,4 +1,4 @@
-import { assert, test } from "vitest";
+import { assert, expect, test } from "vitest";
 
 import {
   ActionHash,
@@ -13,7 +13,7 @@ import {
 import { CallableCell, dhtSync, runScenario } from "@holochain/tryorama";
 import { decode } from "@msgpack/msgpack";
 
-import { createPost } from "./common.js";
+import { createPost, samplePost } from "./common.js";
 
 test("create a Post and get all posts", async () => {
   await runScenario(async scenario => {
@@ -73,3 +73,131 @@ test("create a Post and get all posts", async () => {
     assert.equal(collectionOutput.length, 0);
   });
 });
+
+test("list posts and comments from the tags of their links", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    // Alice creates a Post and comments on it
+    const post = await samplePost(alice.cells[0], { name: "Tagged", content: "First line\n\nSecond line" });
+    const record: Record = await createPost(alice.cells[0], post);
+    const postHash = record.signed_action.hashed.hash;
+    const commentRecord: Record = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "create_comment",
+      payload: { content: "A comment", post_hash: postHash, author: alice.agentPubKey },
+    });
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    // Bob reads the title, author and excerpt of the Post without getting it
+    const listings: any[] = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_all_post_listings",
+      payload: null,
+    });
+    assert.equal(listings.length, 1);
+    assert.deepEqual(listings[0].post_hash, postHash);
+    assert.deepEqual(listings[0].tag, {
+      version: 1,
+      title: "Tagged",
+      author: alice.agentPubKey,
+      excerpt: "First line Second line",
+    });
+    const authorListings: any[] = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_post_listings_for_author",
+      payload: alice.agentPubKey,
+    });
+    assert.deepEqual(authorListings[0].tag, listings[0].tag);
+
+    const commentListings: any[] = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_comment_listings_for_post",
+      payload: postHash,
+    });
+    assert.equal(commentListings.length, 1);
+    assert.deepEqual(commentListings[0].comment_hash, commentRecord.signed_action.hashed.hash);
+    assert.deepEqual(commentListings[0].tag, { version: 1, author: alice.agentPubKey, excerpt: "A comment" });
+  });
+});
+
+test("unlist and relist a Post", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    // Alice creates a Post and unlists it
+    const record: Record = await createPost(alice.cells[0]);
+    const postHash = record.signed_action.hashed.hash;
+    await alice.cells[0].callZome({ zome_name: "blog", fn_name: "unlist_post", payload: postHash });
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    // Bob no longer sees the Post in the list of all posts, but can still fetch it
+    let collectionOutput: Link[] = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_all_posts",
+      payload: null,
+    });
+    assert.equal(collectionOutput.length, 0);
+    const latest: Record = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_latest_post",
+      payload: postHash,
+    });
+    assert.ok(latest);
+
+    // Bob can't relist Alice's Post
+    await expect(bob.cells[0].callZome({ zome_name: "blog", fn_name: "relist_post", payload: postHash }))
+      .rejects.toThrow("UNAUTHORIZED");
+
+    // Alice relists the Post
+    await alice.cells[0].callZome({ zome_name: "blog", fn_name: "relist_post", payload: postHash });
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    collectionOutput = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_all_posts",
+      payload: null,
+    });
+    assert.equal(collectionOutput.length, 1);
+
+    // The relisted Post is tagged like it was when it was first listed
+    const listings: any[] = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_all_post_listings",
+      payload: null,
+    });
+    assert.equal(listings[0].tag.author.toString(), alice.agentPubKey.toString());
+  });
+});
+
+test("only moderators can pin a Post", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice] = await scenario.addPlayersWithApps([appSource]);
+
+    // The test DNA has no progenitor or moderators configured, so Alice can't pin her Post
+    const record: Record = await createPost(alice.cells[0]);
+    await expect(alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "pin_post",
+      payload: { post_hash: record.signed_action.hashed.hash, expires_at: null },
+    })).rejects.toThrow("UNAUTHORIZED");
+
+    const pinned: Link[] = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_all_posts_pinned_first",
+      payload: null,
+    });
+    assert.equal(pinned.length, 1);
+  });
+});
@@ -0,0 +1,107 @@
+import { assert, expect, test } from "vitest";
+
+import { ActionHash, Record } from "@holochain/client";
+import { dhtSync, runScenario } from "@holochain/tryorama";
+import { decode } from "@msgpack/msgpack";
+
+import { createComment, createPost, sampleComment, samplePost } from "./common.js";
+
+test("export a blog as JSON and replay it into another cell", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    const post = await samplePost(alice.cells[0]);
+    const record: Record = await createPost(alice.cells[0], post);
+    const postHash: ActionHash = record.signed_action.hashed.hash;
+    await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "update_post",
+      payload: {
+        original_post_hash: postHash,
+        previous_post_hash: postHash,
+        updated_post: { ...post, content: "Edited content" },
+        edit_reason: "Typo",
+      },
+    });
+    const commentHash: ActionHash = (await createComment(
+      alice.cells[0],
+      await sampleComment(alice.cells[0], { post_hash: postHash }),
+    )).signed_action.hashed.hash;
+    await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "delete_comment",
+      payload: commentHash,
+    });
+
+    const json: string = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "export_my_blog",
+      payload: null,
+    });
+    const exported = JSON.parse(json);
+    assert.equal(exported.format, "holochain-blog-export");
+    assert.equal(exported.posts.length, 1);
+    assert.equal(exported.posts[0].revisions.length, 2);
+    assert.equal(exported.posts[0].revisions[1].edit.reason, "Typo");
+    assert.ok(exported.comments[0].deletion);
+
+    // Importing into the cell the export came from finds every record already there
+    const ownReport: any = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "import_blog",
+      payload: json,
+    });
+    assert.equal(ownReport.imported.length, 0);
+    assert.equal(ownReport.existing.length, 2);
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    const report: any = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "import_blog",
+      payload: json,
+    });
+    assert.equal(report.imported.length, 2);
+    const importedPostHash: ActionHash = report.imported[0].imported_hash;
+    const latest: Record = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_latest_post",
+      payload: importedPostHash,
+    });
+    assert.equal((decode((latest.entry as any).Present.entry) as any).content, "Edited content");
+    const deletes: any[] = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_all_deletes_for_comment",
+      payload: report.imported[1].imported_hash,
+    });
+    assert.equal(deletes.length, 1);
+
+    // Replaying the same export again doesn't duplicate anything
+    const secondReport: any = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "import_blog",
+      payload: json,
+    });
+    assert.equal(secondReport.imported.length, 0);
+    assert.equal(secondReport.existing.length, 2);
+
+    // Records are matched by the hash they were exported with, not by their content
+    const identicalPost = { ...exported.posts[0], original_hash: exported.comments[0].original_hash };
+    const identicalReport: any = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "import_blog",
+      payload: JSON.stringify({ ...exported, posts: [...exported.posts, identicalPost], comments: [] }),
+    });
+    assert.equal(identicalReport.imported.length, 1);
+    assert.equal(identicalReport.existing.length, 1);
+
+    await expect(bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "import_blog",
+      payload: JSON.stringify({ ...exported, format: "something-else" }),
+    })).rejects.toThrow("INVALID");
+  });
+});
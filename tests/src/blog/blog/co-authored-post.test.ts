@@ -0,0 +1,80 @@
+import { assert, expect, test } from "vitest";
+
+import { ActionHash, Link } from "@holochain/client";
+import { dhtSync, pause, runScenario } from "@holochain/tryorama";
+
+import { samplePost } from "./common.js";
+
+test("create a Post co-authored by two agents", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    const post = await samplePost(alice.cells[0], { co_authors: [bob.agentPubKey] });
+
+    // Alice starts the session and Bob accepts it
+    const aliceResponse: any = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "start_co_authored_post",
+      payload: post,
+    });
+    const bobResponse: any = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "accept_co_authored_post",
+      payload: { post, request: aliceResponse.request },
+    });
+
+    // Both authors commit the same countersigned Post
+    const responses = [aliceResponse, bobResponse];
+    const postHash: ActionHash = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "commit_co_authored_post",
+      payload: { post, responses },
+    });
+    await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "commit_co_authored_post",
+      payload: { post, responses },
+    });
+
+    // Wait for the countersigning session to complete
+    await pause(3000);
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "index_co_authored_post",
+      payload: postHash,
+    });
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    const links: Link[] = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_posts_for_author",
+      payload: bob.agentPubKey,
+    });
+    assert.equal(links.length, 1);
+    assert.deepEqual(links[0].target, postHash);
+
+    // A Post with co-authors can't be created without countersigning it
+    await expect(alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "create_post",
+      payload: post,
+    })).rejects.toThrow();
+
+    // Nor can it be restored once deleted, since its copy would need countersigning too
+    await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "delete_post",
+      payload: postHash,
+    });
+    await expect(alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "restore_post",
+      payload: postHash,
+    })).rejects.toThrow("INVALID: A co-authored Post cannot be restored");
+  });
+});
@@ -9,3 +9,66 @@ import { createComment, sampleComment, createPost } from './common'
 test('placeholder', async () => {
   assert(1 + 1 === 2, 'I failed')
 })
+
+test('lock and unlock comments on a Post', async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + '/../workdir/testing-and-validation.happ'
+    const appSource = { appBundleSource: { path: testAppPath } }
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource])
+    await scenario.shareAllAgents()
+
+    // Alice creates a Post and locks its comments
+    const postHash = (await createPost(alice.cells[0])).signed_action.hashed.hash
+    await alice.cells[0].callZome({ zome_name: 'blog', fn_name: 'lock_comments', payload: postHash })
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0])
+
+    // Bob can't comment on the locked Post
+    await expect(createComment(bob.cells[0], await sampleComment(bob.cells[0], { post_hash: postHash })))
+      .rejects.toThrow("COMMENTS_LOCKED")
+
+    // Alice unlocks the comments and Bob can comment again
+    await alice.cells[0].callZome({ zome_name: 'blog', fn_name: 'unlock_comments', payload: postHash })
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0])
+
+    const record: Record = await createComment(bob.cells[0], await sampleComment(bob.cells[0], { post_hash: postHash }))
+    assert.ok(record)
+
+    const history: Record[] = await bob.cells[0].callZome({
+      zome_name: 'blog',
+      fn_name: 'get_comment_lock_history',
+      payload: postHash,
+    })
+    assert.deepEqual(history.map(record => (decode((record.entry as any).Present.entry) as any).locked), [true, false])
+  })
+})
+
+test('count comments for posts', async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + '/../workdir/testing-and-validation.happ'
+    const appSource = { appBundleSource: { path: testAppPath } }
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource])
+    await scenario.shareAllAgents()
+
+    // Alice comments twice on a Post and deletes one of the comments
+    const postHash = (await createPost(alice.cells[0])).signed_action.hashed.hash
+    const otherPostHash = (await createPost(alice.cells[0])).signed_action.hashed.hash
+    const record: Record = await createComment(alice.cells[0], await sampleComment(alice.cells[0], { post_hash: postHash }))
+    await createComment(alice.cells[0], await sampleComment(alice.cells[0], { post_hash: postHash }))
+    await alice.cells[0].callZome({
+      zome_name: 'blog',
+      fn_name: 'delete_comment',
+      payload: record.signed_action.hashed.hash,
+    })
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0])
+
+    const counts = await bob.cells[0].callZome({
+      zome_name: 'blog',
+      fn_name: 'count_comments_for_posts',
+      payload: [postHash, otherPostHash],
+    })
+    assert.deepEqual(counts, [{ live: 1, deleted: 1 }, { live: 0, deleted: 0 }])
+  })
+})
@@ -0,0 +1,59 @@
+import { assert, expect, test } from "vitest";
+
+import { Record } from "@holochain/client";
+import { runScenario } from "@holochain/tryorama";
+import { decode } from "@msgpack/msgpack";
+
+import { samplePost } from "./common.js";
+
+test("a delegate creates a Post on behalf of its principal until the grant is revoked", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    // Alice assigns a delegation grant to Bob
+    const grant: any = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "issue_delegation_grant",
+      payload: { tag: "release-notes-bot", delegate: bob.agentPubKey },
+    });
+    let grants: Record[] = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_delegation_grants",
+      payload: null,
+    });
+    assert.equal(grants.length, 1);
+
+    // Bob posts as Alice
+    const post = await samplePost(alice.cells[0]);
+    const record: Record = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "create_post_as",
+      payload: { principal: alice.agentPubKey, cap_secret: grant.cap_secret, post },
+    });
+    assert.deepEqual(record.signed_action.hashed.content.author, alice.agentPubKey);
+    const createdPost: any = decode((record.entry as any).Present.entry);
+    assert.deepEqual(createdPost.author, alice.agentPubKey);
+    assert.deepEqual(createdPost.delegate, bob.agentPubKey);
+
+    // Once revoked, the grant can't be used anymore
+    await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "revoke_delegation_grant",
+      payload: grant.grant_hash,
+    });
+    grants = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_delegation_grants",
+      payload: null,
+    });
+    assert.equal(grants.length, 0);
+    await expect(bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "create_post_as",
+      payload: { principal: alice.agentPubKey, cap_secret: grant.cap_secret, post },
+    })).rejects.toThrow();
+  });
+});
@@ -0,0 +1,63 @@
+import { assert, test } from "vitest";
+
+import { ActionHash, encodeHashToBase64, Record } from "@holochain/client";
+import { dhtSync, runScenario } from "@holochain/tryorama";
+
+import { createPost, samplePost } from "./common.js";
+
+test("render the latest Posts as Atom and RSS feeds", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    await createPost(alice.cells[0]);
+    const post = await samplePost(alice.cells[0], { name: "Fish & <Chips>" });
+    const record: Record = await createPost(alice.cells[0], post);
+    const postHash: ActionHash = record.signed_action.hashed.hash;
+    await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "update_post",
+      payload: {
+        original_post_hash: postHash,
+        previous_post_hash: postHash,
+        updated_post: { ...post, content: "Latest revision" },
+      },
+    });
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    const atom: string = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "render_feed",
+      payload: {
+        source: "AllPosts",
+        format: "Atom",
+        limit: 1,
+        title: "Blog",
+        base_url: "https://blog.example/",
+      },
+    });
+    assert.equal(atom.match(/<entry>/g)?.length, 1);
+    assert.include(atom, `<id>urn:holochain:post:${encodeHashToBase64(postHash)}</id>`);
+    assert.include(atom, "<title>Fish &amp; &lt;Chips&gt;</title>");
+    assert.include(atom, "Latest revision");
+    assert.include(atom, `href="https://blog.example/posts/${encodeHashToBase64(postHash)}"`);
+
+    const rss: string = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "render_feed",
+      payload: {
+        source: { Author: alice.agentPubKey },
+        format: "Rss",
+        limit: 10,
+        title: "Alice's blog",
+        base_url: "https://blog.example",
+      },
+    });
+    assert.equal(rss.match(/<item>/g)?.length, 2);
+    assert.include(rss, `<guid isPermaLink="false">urn:holochain:post:${encodeHashToBase64(postHash)}</guid>`);
+    assert.match(rss, /<pubDate>\w{3}, \d{2} \w{3} \d{4} \d{2}:\d{2}:\d{2} GMT<\/pubDate>/);
+  });
+});
@@ -0,0 +1,47 @@
+import { assert, expect, test } from "vitest";
+
+import { EntryHash, Record } from "@holochain/client";
+import { dhtSync, runScenario } from "@holochain/tryorama";
+import { decode } from "@msgpack/msgpack";
+
+import { createPost, samplePost } from "./common.js";
+
+test("upload a file, attach it to a Post and download it", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    // Alice uploads a file spanning several chunks
+    const data = new Uint8Array(600 * 1024).map((_, i) => i % 251);
+    const manifestHash: EntryHash = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "upload_file",
+      payload: { name: "image.png", mime_type: "image/png", data },
+    });
+    assert.ok(manifestHash);
+
+    // Alice attaches the file to a Post
+    const record: Record = await createPost(alice.cells[0], await samplePost(alice.cells[0], { attachments: [manifestHash] }));
+    assert.deepEqual((decode((record.entry as any).Present.entry) as any).attachments, [manifestHash]);
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    // Bob downloads the file
+    const file: any = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "download_file",
+      payload: manifestHash,
+    });
+    assert.equal(file.manifest.chunks.length, 3);
+    assert.deepEqual(new Uint8Array(file.data), data);
+
+    // A manifest whose size doesn't match its chunks is rejected
+    await expect(alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "create_file_manifest",
+      payload: { ...file.manifest, size: file.manifest.size + 1 },
+    })).rejects.toThrow();
+  });
+});
@@ -0,0 +1,114 @@
+import { assert, expect, test } from "vitest";
+
+import { ActionHash, Record } from "@holochain/client";
+import { dhtSync, runScenario } from "@holochain/tryorama";
+
+import { createComment, createPost, sampleComment, samplePost } from "./common.js";
+
+test("export Posts and Comments and import them with their provenance", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    const postHash: ActionHash = (await createPost(alice.cells[0])).signed_action.hashed.hash;
+    const commentHash: ActionHash = (await createComment(
+      alice.cells[0],
+      await sampleComment(alice.cells[0], { post_hash: postHash }),
+    )).signed_action.hashed.hash;
+
+    // The successor DNA is the same one in this test, so Alice exports and imports within her cell
+    const exported: any = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "export_for_migration",
+      payload: null,
+    });
+    assert.equal(exported.comments.length, 1);
+
+    // Bob can't claim Alice's content as his own
+    await expect(bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "import_for_migration",
+      payload: exported,
+    })).rejects.toThrow();
+
+    const report: any = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "import_for_migration",
+      payload: exported,
+    });
+    assert.equal(report.imported.length, exported.posts.length + exported.comments.length);
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    const importedPostHash: ActionHash = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_imported_hash",
+      payload: postHash,
+    });
+    assert.ok(importedPostHash);
+    const importedCommentHash: ActionHash = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_imported_hash",
+      payload: commentHash,
+    });
+    const provenance: any = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_provenance",
+      payload: importedCommentHash,
+    });
+    assert.ok(provenance);
+
+    // Importing the same export again doesn't duplicate anything
+    const secondReport: any = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "import_for_migration",
+      payload: exported,
+    });
+    assert.equal(secondReport.imported.length, 0);
+  });
+});
+
+test("import a Post edited twice with the lineage of its latest revision", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice] = await scenario.addPlayersWithApps([appSource]);
+
+    const postHash: ActionHash = (await createPost(alice.cells[0])).signed_action.hashed.hash;
+    let previousPostHash = postHash;
+    for (const content of ["First edit", "Second edit"]) {
+      const record: Record = await alice.cells[0].callZome({
+        zome_name: "blog",
+        fn_name: "update_post",
+        payload: {
+          original_post_hash: postHash,
+          previous_post_hash: previousPostHash,
+          updated_post: await samplePost(alice.cells[0], { content }),
+        },
+      });
+      previousPostHash = record.signed_action.hashed.hash;
+    }
+
+    const exported: any = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "export_for_migration",
+      payload: null,
+    });
+    assert.equal(exported.posts[0].lineage.length, 2);
+
+    const report: any = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "import_for_migration",
+      payload: exported,
+    });
+    assert.equal(report.imported.length, 1);
+    const importedPostHash: ActionHash = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_imported_hash",
+      payload: postHash,
+    });
+    assert.deepEqual(importedPostHash, report.imported[0].imported_hash);
+  });
+});
@@ -1,4 +1,4 @@
-import { assert, test } from "vitest";
+import { assert, expect, test } from "vitest";
 
 import {
   ActionHash,
@@ -73,7 +73,7 @@ test("create and read Post", async () => {
       fn_name: "get_original_post",
       payload: record.signed_action.hashed.hash,
     });
-    assert.deepEqual(sample, decode((createReadOutput.entry as any).Present.entry) as any);
+    assert.deepEqual({ ...sample, schema_version: 2 }, decode((createReadOutput.entry as any).Present.entry) as any);
   });
 });
 
@@ -124,7 +124,7 @@ test("create and update Post", async () => {
       fn_name: "get_latest_post",
       payload: updatedRecord.signed_action.hashed.hash,
     });
-    assert.deepEqual(contentUpdate, decode((readUpdatedOutput0.entry as any).Present.entry) as any);
+    assert.deepEqual({ ...contentUpdate, schema_version: 2 }, decode((readUpdatedOutput0.entry as any).Present.entry) as any);
 
     // Alice updates the Post again
     contentUpdate = await samplePost(alice.cells[0]);
@@ -150,7 +150,7 @@ test("create and update Post", async () => {
       fn_name: "get_latest_post",
       payload: updatedRecord.signed_action.hashed.hash,
     });
-    assert.deepEqual(contentUpdate, decode((readUpdatedOutput1.entry as any).Present.entry) as any);
+    assert.deepEqual({ ...contentUpdate, schema_version: 2 }, decode((readUpdatedOutput1.entry as any).Present.entry) as any);
 
     // Bob gets all the revisions for Post
     const revisions: Record[] = await bob.cells[0].callZome({
@@ -159,7 +159,7 @@ test("create and update Post", async () => {
       payload: originalActionHash,
     });
     assert.equal(revisions.length, 3);
-    assert.deepEqual(contentUpdate, decode((revisions[2].entry as any).Present.entry) as any);
+    assert.deepEqual({ ...contentUpdate, schema_version: 2 }, decode((revisions[2].entry as any).Present.entry) as any);
   });
 });
 
@@ -216,3 +216,238 @@ test("create and delete Post", async () => {
     assert.equal(deletesForPost.length, 1);
   });
 });
+
+test("diff revisions and get edit history of Post", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    // Alice creates a Post and updates its content
+    const record: Record = await createPost(alice.cells[0], await samplePost(alice.cells[0], { content: "one two three" }));
+    const originalActionHash = record.signed_action.hashed.hash;
+    const updatedRecord: Record = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "update_post",
+      payload: {
+        original_post_hash: originalActionHash,
+        previous_post_hash: originalActionHash,
+        updated_post: await samplePost(alice.cells[0], { content: "one three four" }),
+      },
+    });
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    // Bob diffs the two revisions word by word
+    const diff: any = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "diff_post_revisions",
+      payload: {
+        old_revision_hash: originalActionHash,
+        new_revision_hash: updatedRecord.signed_action.hashed.hash,
+        granularity: "Word",
+      },
+    });
+    assert.deepEqual(diff.name, [{ op: "Equal", text: "Lorem ipsum dolor sit amet, consectetur adipiscing elit." }]);
+    assert.deepEqual(diff.content.filter((chunk: any) => chunk.op === "Delete").map((chunk: any) => chunk.text.trim()), ["two"]);
+    assert.deepEqual(diff.content.filter((chunk: any) => chunk.op === "Insert").map((chunk: any) => chunk.text.trim()), ["four"]);
+
+    // Bob gets the edit history of the Post
+    const history: any[] = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_edit_history_for_post",
+      payload: originalActionHash,
+    });
+    assert.equal(history.length, 2);
+    assert.deepEqual(history[1].change_size, { words_added: 1, words_removed: 1 });
+  });
+});
+
+test("revert Post to a previous revision", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    // Alice creates a Post and overwrites its content by mistake
+    const sample = await samplePost(alice.cells[0]);
+    const record: Record = await createPost(alice.cells[0], sample);
+    const originalActionHash = record.signed_action.hashed.hash;
+    await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "update_post",
+      payload: {
+        original_post_hash: originalActionHash,
+        previous_post_hash: originalActionHash,
+        updated_post: await samplePost(alice.cells[0], { content: "oops" }),
+      },
+    });
+
+    // Alice reverts the Post to its original revision
+    const revertedRecord: Record = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "revert_post",
+      payload: {
+        original_post_hash: originalActionHash,
+        revision_hash: originalActionHash,
+      },
+    });
+    assert.ok(revertedRecord);
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    // Bob gets the latest Post, which matches the original content again
+    const latest: Record = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_latest_post",
+      payload: originalActionHash,
+    });
+    const latestPost = decode((latest.entry as any).Present.entry) as any;
+    assert.equal(latestPost.content, sample.content);
+    assert.deepEqual(latestPost.reverted_from, originalActionHash);
+
+    // Reverting to a revision of another Post is rejected
+    const otherRecord: Record = await createPost(alice.cells[0]);
+    await expect(alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "revert_post",
+      payload: {
+        original_post_hash: originalActionHash,
+        revision_hash: otherRecord.signed_action.hashed.hash,
+      },
+    })).rejects.toThrow("INVALID");
+  });
+});
+
+test("delete and restore Post within the grace period", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    // Alice creates and deletes a Post
+    const record: Record = await createPost(alice.cells[0]);
+    const originalActionHash = record.signed_action.hashed.hash;
+    await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "delete_post",
+      payload: originalActionHash,
+    });
+
+    // The Post shows up as recently deleted
+    let recentlyDeleted: any[] = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_recently_deleted_posts_for_author",
+      payload: alice.agentPubKey,
+    });
+    assert.equal(recentlyDeleted.length, 1);
+    assert.deepEqual(recentlyDeleted[0].original_post_hash, originalActionHash);
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    // Bob can't restore Alice's Post
+    await expect(bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "restore_post",
+      payload: originalActionHash,
+    })).rejects.toThrow();
+
+    // Alice restores the Post
+    const restoredRecord: Record = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "restore_post",
+      payload: originalActionHash,
+    });
+    assert.ok(restoredRecord);
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    // Bob sees the restored copy in the list of all posts, linked back from the original
+    const allPosts: Link[] = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_all_posts",
+      payload: null,
+    });
+    assert.equal(allPosts.length, 1);
+    assert.deepEqual(allPosts[0].target, restoredRecord.signed_action.hashed.hash);
+    const restoredHash = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_restored_post",
+      payload: originalActionHash,
+    });
+    assert.deepEqual(restoredHash, restoredRecord.signed_action.hashed.hash);
+
+    recentlyDeleted = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_recently_deleted_posts_for_author",
+      payload: alice.agentPubKey,
+    });
+    assert.equal(recentlyDeleted.length, 0);
+  });
+});
+
+test("get summaries for a list of Posts", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    // Alice creates two Posts and edits the first one
+    const first: Record = await createPost(alice.cells[0]);
+    const second: Record = await createPost(alice.cells[0]);
+    const update = await samplePost(alice.cells[0], { content: "edited" });
+    await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "update_post",
+      payload: {
+        original_post_hash: first.signed_action.hashed.hash,
+        previous_post_hash: first.signed_action.hashed.hash,
+        updated_post: update,
+      },
+    });
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    const summaries: any[] = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_post_summaries",
+      payload: [first.signed_action.hashed.hash, second.signed_action.hashed.hash],
+    });
+    assert.equal(summaries.length, 2);
+    assert.ok(summaries[0].edited);
+    assert.deepEqual({ ...update, schema_version: 2 }, decode((summaries[0].latest_record.entry as any).Present.entry) as any);
+    assert.notOk(summaries[1].edited);
+    assert.notOk(summaries[1].deleted);
+    assert.deepEqual(summaries[1].author, alice.agentPubKey);
+  });
+});
+
+test("render a markdown Post and reject raw HTML", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice] = await scenario.addPlayersWithApps([appSource]);
+
+    // Alice creates a markdown Post and gets it rendered to HTML
+    const record: Record = await createPost(
+      alice.cells[0],
+      await samplePost(alice.cells[0], { content: "# Title\n\nSome *emphasis*", format: "Markdown" }),
+    );
+    const html: string = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "render_post_html",
+      payload: record.signed_action.hashed.hash,
+    });
+    assert.equal(html, "<h1>Title</h1>\n<p>Some <em>emphasis</em></p>\n");
+
+    // Markdown with raw HTML or script links is rejected
+    for (const content of ["<script>alert(1)</script>", "[click](javascript:alert(1))"]) {
+      await expect(createPost(alice.cells[0], await samplePost(alice.cells[0], { content, format: "Markdown" })))
+        .rejects.toThrow("INVALID");
+    }
+  });
+});
@@ -0,0 +1,65 @@
+import { assert, expect, test } from "vitest";
+
+import { Link, Record } from "@holochain/client";
+import { dhtSync, runScenario } from "@holochain/tryorama";
+import { decode } from "@msgpack/msgpack";
+
+test("only the recipients of a private Post can decrypt it", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob, carol] = await scenario.addPlayersWithApps([appSource, appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    for (const player of [alice, bob, carol]) {
+      await player.cells[0].callZome({
+        zome_name: "blog",
+        fn_name: "publish_encryption_key",
+        payload: null,
+      });
+    }
+    await dhtSync([alice, bob, carol], alice.cells[0].cell_id[0]);
+
+    // Alice shares a private Post with Bob
+    const record: Record = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "create_private_post",
+      payload: { name: "For Bob", content: "Secret release date", recipients: [bob.agentPubKey] },
+    });
+    const post: any = decode((record.entry as any).Present.entry);
+    assert.equal(post.name, "For Bob");
+    assert.equal(post.content, "");
+    assert.equal(post.envelope.recipients.length, 2);
+
+    await dhtSync([alice, bob, carol], alice.cells[0].cell_id[0]);
+
+    // The Post is listed with its plaintext metadata
+    const links: Link[] = await carol.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_all_posts",
+      payload: null,
+    });
+    assert.equal(links.length, 1);
+
+    for (const player of [alice, bob]) {
+      const content: string = await player.cells[0].callZome({
+        zome_name: "blog",
+        fn_name: "decrypt_post",
+        payload: record.signed_action.hashed.hash,
+      });
+      assert.equal(content, "Secret release date");
+    }
+    await expect(carol.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "decrypt_post",
+      payload: record.signed_action.hashed.hash,
+    })).rejects.toThrow();
+
+    // A private Post can't also store its content in plaintext
+    await expect(alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "create_post",
+      payload: { ...post, content: "Secret release date" },
+    })).rejects.toThrow();
+  });
+});
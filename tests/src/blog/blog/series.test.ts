@@ -0,0 +1,94 @@
+import { assert, expect, test } from "vitest";
+
+import { ActionHash, Link, Record } from "@holochain/client";
+import { dhtSync, runScenario } from "@holochain/tryorama";
+import { decode } from "@msgpack/msgpack";
+
+import { createPost } from "./common.js";
+
+test("create a Series, navigate it and reorder its Posts", async () => {
+  await runScenario(async scenario => {
+    const testAppPath = process.cwd() + "/../workdir/testing-and-validation.happ";
+    const appSource = { appBundleSource: { path: testAppPath } };
+    const [alice, bob] = await scenario.addPlayersWithApps([appSource, appSource]);
+    await scenario.shareAllAgents();
+
+    const posts: ActionHash[] = [];
+    for (let i = 0; i < 3; i++) {
+      posts.push((await createPost(alice.cells[0])).signed_action.hashed.hash);
+    }
+
+    // Alice creates a Series with her three Posts
+    const series = {
+      title: "Part series",
+      description: "Lorem ipsum.",
+      posts,
+      author: alice.agentPubKey,
+    };
+    const record: Record = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "create_series",
+      payload: series,
+    });
+    const seriesHash = record.signed_action.hashed.hash;
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    // Bob finds the Series from the author and from one of its Posts
+    let links: Link[] = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_series_for_author",
+      payload: alice.agentPubKey,
+    });
+    assert.equal(links.length, 1);
+    links = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_series_for_post",
+      payload: posts[1],
+    });
+    assert.deepEqual(links[0].target, seriesHash);
+
+    let adjacent: any = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_adjacent_posts_in_series",
+      payload: { original_series_hash: seriesHash, post_hash: posts[1] },
+    });
+    assert.deepEqual(adjacent.previous, posts[0]);
+    assert.deepEqual(adjacent.next, posts[2]);
+
+    // Alice drops the last Post and reorders the rest
+    const updated: Record = await alice.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "update_series",
+      payload: {
+        original_series_hash: seriesHash,
+        previous_series_hash: seriesHash,
+        updated_series: { ...series, posts: [posts[1], posts[0]] },
+      },
+    });
+    assert.deepEqual((decode((updated.entry as any).Present.entry) as any).posts, [posts[1], posts[0]]);
+
+    await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+    adjacent = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_adjacent_posts_in_series",
+      payload: { original_series_hash: seriesHash, post_hash: posts[1] },
+    });
+    assert.equal(adjacent.previous, undefined);
+    assert.deepEqual(adjacent.next, posts[0]);
+    links = await bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "get_series_for_post",
+      payload: posts[2],
+    });
+    assert.equal(links.length, 0);
+
+    // Bob can't add Alice's Posts to a Series of his own
+    await expect(bob.cells[0].callZome({
+      zome_name: "blog",
+      fn_name: "create_series",
+      payload: { ...series, author: bob.agentPubKey },
+    })).rejects.toThrow();
+  });
+});
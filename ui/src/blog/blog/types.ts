@@ -39,17 +39,158 @@ export type BlogSignal = {
 /* dprint-ignore-start */
 export type EntryTypes =
  | ({ type: 'Comment'; } & Comment)
- | ({  type: 'Post'; } & Post);
+ | ({ type: 'CommentLock'; } & CommentLock)
+ | ({ type: 'EncryptionKey'; } & EncryptionKey)
+ | ({ type: 'FileChunk'; } & FileChunk)
+ | ({ type: 'FileManifest'; } & FileManifest)
+ | ({  type: 'Post'; } & Post)
+ | ({ type: 'Provenance'; } & Provenance)
+ | ({ type: 'Series'; } & Series);
 /* dprint-ignore-end */
 
+export interface EditMetadata {
+  reason: string | undefined;
+  minor: boolean;
+}
+
+export type ContentFormat = "Plain" | "Markdown";
+
 export interface Post {
+  schema_version?: number;
   name: string;
   content: string;
   author: AgentPubKey;
+  co_authors?: AgentPubKey[];
+  delegate?: AgentPubKey;
+  envelope?: PostEnvelope;
+  format?: ContentFormat;
+  attachments?: EntryHash[];
+  reverted_from?: ActionHash;
+  edit?: EditMetadata;
 }
 
 export interface Comment {
+  schema_version?: number;
   content: string;
   post_hash: ActionHash;
   author: AgentPubKey;
+  reverted_from?: ActionHash;
+  edit?: EditMetadata;
+  comment_lock_hash?: ActionHash;
+}
+
+export interface CommentLock {
+  post_hash: ActionHash;
+  locked: boolean;
+}
+
+export interface FileChunk {
+  data: Uint8Array;
+}
+
+export interface FileManifest {
+  name: string;
+  mime_type: string;
+  size: number;
+  sha256: Uint8Array;
+  chunks: EntryHash[];
+}
+
+export interface Series {
+  title: string;
+  description: string;
+  posts: ActionHash[];
+  author: AgentPubKey;
+}
+
+export interface EncryptionKey {
+  key: Uint8Array;
+}
+
+export interface XSalsa20Poly1305EncryptedData {
+  nonce: Uint8Array;
+  encrypted_data: Uint8Array;
+}
+
+export interface WrappedKey {
+  recipient: AgentPubKey;
+  recipient_key_hash: ActionHash;
+  encrypted_key: XSalsa20Poly1305EncryptedData;
+}
+
+export interface PostEnvelope {
+  sender_key_hash: ActionHash;
+  encrypted_content: XSalsa20Poly1305EncryptedData;
+  recipients: WrappedKey[];
+}
+
+// Tag of the AllPosts and AuthorToPosts links, describing the post as it was listed, and of the
+// PostRevisionTags links, describing it as it was last edited
+export interface PostTag {
+  version: number;
+  title: string;
+  author: AgentPubKey;
+  excerpt: string;
+}
+
+// Tag of the PostToComments links, describing the comment as it was created, and of the
+// CommentRevisionTags links, describing it as it was last edited
+export interface CommentTag {
+  version: number;
+  author: AgentPubKey;
+  excerpt: string;
+}
+
+export interface PostListing {
+  post_hash: ActionHash;
+  timestamp: number;
+  tag: PostTag;
+}
+
+export interface CommentListing {
+  comment_hash: ActionHash;
+  timestamp: number;
+  tag: CommentTag;
+}
+
+export type BlogErrorCode =
+  | "POST_NOT_FOUND"
+  | "COMMENT_NOT_FOUND"
+  | "REVISION_NOT_FOUND"
+  | "SERIES_NOT_FOUND"
+  | "COMMENT_LOCK_NOT_FOUND"
+  | "ENCRYPTION_KEY_NOT_FOUND"
+  | "FILE_MANIFEST_NOT_FOUND"
+  | "FILE_CHUNK_NOT_FOUND"
+  | "DELEGATION_GRANT_NOT_FOUND"
+  | "LINK_NOT_FOUND"
+  | "MISSING_ENTRY"
+  | "MALFORMED_DETAILS"
+  | "MALFORMED_LINK"
+  | "MALFORMED_LINK_TAG"
+  | "POST_NOT_DELETED"
+  | "POST_ALREADY_RESTORED"
+  | "RESTORE_GRACE_PERIOD_EXPIRED"
+  | "COMMENTS_LOCKED"
+  | "POST_NOT_IN_SERIES"
+  | "NETWORK_ERROR"
+  | "COUNTERSIGNING_FAILED"
+  | "UNAUTHORIZED"
+  | "IMMUTABLE"
+  | "INVALID";
+
+const BLOG_ERROR_CODE = /\b(POST_NOT_FOUND|COMMENT_NOT_FOUND|REVISION_NOT_FOUND|SERIES_NOT_FOUND|COMMENT_LOCK_NOT_FOUND|ENCRYPTION_KEY_NOT_FOUND|FILE_MANIFEST_NOT_FOUND|FILE_CHUNK_NOT_FOUND|DELEGATION_GRANT_NOT_FOUND|LINK_NOT_FOUND|MISSING_ENTRY|MALFORMED_DETAILS|MALFORMED_LINK|MALFORMED_LINK_TAG|POST_NOT_DELETED|POST_ALREADY_RESTORED|RESTORE_GRACE_PERIOD_EXPIRED|COMMENTS_LOCKED|POST_NOT_IN_SERIES|NETWORK_ERROR|COUNTERSIGNING_FAILED|UNAUTHORIZED|IMMUTABLE|INVALID): /;
+
+// Extracts the code of a BlogError from a failed zome call
+export function getBlogErrorCode(error: unknown): BlogErrorCode | undefined {
+  const message = (error as any)?.message ?? String(error);
+  return message.match(BLOG_ERROR_CODE)?.[1] as BlogErrorCode | undefined;
+}
+
+export interface Provenance {
+  original_hash: ActionHash;
+  revision: SignedActionHashed;
+  revision_entry: any;
+  lineage: Array<Create | Update>;
+  imported_hash: ActionHash;
 }